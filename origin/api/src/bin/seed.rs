@@ -0,0 +1,569 @@
+use std::{error::Error, path::PathBuf, process::ExitCode};
+
+use api::seeders::export::{export, ExportTable};
+use api::seeders::fake::{generate, FakeOptions};
+use api::seeders::source::write_rows;
+use api::seeders::sqlx_seeder::{
+	apply, plan, reset, reset_order, seed_folder,
+	seed_tables, PlannedFile, SeedPlan, SeedState,
+	SeedTarget,
+};
+use clap::{Parser, Subcommand};
+use dotenv::dotenv;
+use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
+
+// 서버와 따로 시드 파일을 확인하고 넣는 명령, 실패하면 0이 아닌 코드로 끝난다
+#[derive(Parser)]
+#[command(
+	name = "seed",
+	about = "Manage the seed files in pg-seeder.toml"
+)]
+struct Cli {
+	/// Folder containing pg-seeder.toml (default: SEED_ROOT, or the nearest parent that has one)
+	#[arg(long, global = true)]
+	root: Option<PathBuf>,
+	/// Seed set to use, e.g. dev, test, demo (default: SEED_SET, or dev)
+	#[arg(long, global = true)]
+	set: Option<String>,
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// List pending files in apply order with target tables and row counts
+	Plan,
+	/// Apply pending files
+	Apply {
+		/// Run everything inside a transaction and roll it back
+		#[arg(long)]
+		dry_run: bool,
+	},
+	/// Truncate the seeded tables in reverse dependency order
+	Reset {
+		/// Actually truncate, without this only the tables are listed
+		#[arg(long)]
+		yes: bool,
+		/// Also truncate tables that reference the seeded tables
+		#[arg(long)]
+		cascade: bool,
+	},
+	/// Show applied, changed and pending files
+	Status,
+	/// Write tables to seed files, by default into the seed set folder
+	Export {
+		/// Tables to export, one <table>.json file each
+		#[arg(required = true)]
+		tables: Vec<String>,
+		/// Only export matching rows, e.g. --where "posts:price > 10000"
+		#[arg(long = "where", value_name = "TABLE:CONDITION")]
+		filters: Vec<String>,
+		/// Folder to write to instead of the seed set folder
+		#[arg(long)]
+		out: Option<PathBuf>,
+		/// Overwrite existing files
+		#[arg(long)]
+		force: bool,
+	},
+	/// Generate fake users, addresses, posts, orders and reviews
+	Generate {
+		/// Same seed, same data
+		#[arg(long, default_value_t = 1)]
+		seed: u64,
+		#[arg(long, default_value_t = 100)]
+		users: usize,
+		#[arg(long, default_value_t = 500)]
+		posts: usize,
+		#[arg(long, default_value_t = 300)]
+		orders: usize,
+		#[arg(long, default_value_t = 1000)]
+		reviews: usize,
+		/// Write seed files into this folder instead of inserting into the database
+		#[arg(long)]
+		out: Option<PathBuf>,
+		/// Write .ndjson files instead of .json
+		#[arg(long, requires = "out")]
+		ndjson: bool,
+		/// Overwrite existing files
+		#[arg(long, requires = "out")]
+		force: bool,
+	},
+}
+
+type CommandResult = Result<(), Box<dyn Error>>;
+
+fn row_summary(planned: &PlannedFile) -> String {
+	match planned.row_count() {
+		Ok(count) if planned.problems.is_empty() => {
+			format!("{} rows", count)
+		}
+		Ok(count) => format!(
+			"{} rows, {} problem(s)",
+			count,
+			planned.problems.len()
+		),
+		Err(err) => format!("invalid: {}", err.message),
+	}
+}
+
+fn print_set(plan: &SeedPlan) {
+	println!(
+		"Seed set {} ({})",
+		plan.set,
+		plan.folder.display()
+	);
+}
+
+async fn plan_command(
+	pool: &Pool<Postgres>,
+	target: &SeedTarget,
+) -> CommandResult {
+	let plan = plan(pool, target).await?;
+	print_set(&plan);
+	let mut problems = 0;
+	let mut pending = 0;
+
+	for planned in &plan.files {
+		let note = match &planned.state {
+			SeedState::Pending => "",
+			SeedState::Changed { reapply: true, .. } => {
+				", changed, upsert"
+			}
+			SeedState::Changed { reapply: false, .. } => {
+				println!(
+					"⚠️ {} changed since it was applied and has no upsert config, skipped",
+					planned.file
+				);
+				continue;
+			}
+			SeedState::Cycle(message) => {
+				println!("🔥 {}: {}", planned.file, message);
+				problems += 1;
+				continue;
+			}
+			SeedState::Applied { .. } => continue,
+		};
+
+		if !planned.problems.is_empty() {
+			problems += 1;
+		}
+		pending += 1;
+		println!(
+			"{:>3}. {} → {} ({}{})",
+			pending,
+			planned.file,
+			planned.table,
+			row_summary(planned),
+			note
+		);
+		// 읽지 못한 파일은 위 줄에 이미 나온다
+		if planned.row_count().is_ok() {
+			for problem in &planned.problems {
+				println!("       - {}", problem);
+			}
+		}
+	}
+
+	if pending == 0 && problems == 0 {
+		println!("✅ Nothing to apply");
+	}
+	if problems > 0 {
+		return Err(
+			format!(
+				"{} seed file(s) cannot be applied",
+				problems
+			)
+			.into(),
+		);
+	}
+	Ok(())
+}
+
+async fn apply_command(
+	pool: &Pool<Postgres>,
+	target: &SeedTarget,
+	dry_run: bool,
+) -> CommandResult {
+	let applied = apply(pool, target, dry_run).await?;
+	if dry_run {
+		println!(
+			"✅ {} seed file(s) would be applied",
+			applied
+		);
+	} else {
+		println!("✅ {} seed file(s) applied", applied);
+	}
+	Ok(())
+}
+
+async fn reset_command(
+	pool: &Pool<Postgres>,
+	target: &SeedTarget,
+	yes: bool,
+	cascade: bool,
+) -> CommandResult {
+	if !yes {
+		let tables = reset_order(&plan(pool, target).await?);
+		println!("Would truncate: {}", tables.join(", "));
+		return Err("pass --yes to truncate".into());
+	}
+
+	let tables = reset(pool, target, cascade).await?;
+	println!("✅ Truncated: {}", tables.join(", "));
+	Ok(())
+}
+
+async fn status_command(
+	pool: &Pool<Postgres>,
+	target: &SeedTarget,
+) -> CommandResult {
+	let plan = plan(pool, target).await?;
+	print_set(&plan);
+
+	for planned in &plan.files {
+		let state = match &planned.state {
+			SeedState::Pending => {
+				format!("pending ({})", row_summary(planned))
+			}
+			SeedState::Applied { applied_at, row_count } => {
+				format!(
+					"applied at {} ({} rows)",
+					applied_at, row_count
+				)
+			}
+			SeedState::Changed { applied_at, reapply } => {
+				format!(
+					"changed since {}{}",
+					applied_at,
+					if *reapply {
+						", will upsert"
+					} else {
+						", skipped"
+					}
+				)
+			}
+			SeedState::Cycle(message) => message.clone(),
+		};
+		println!("{:<32} {}", planned.file, state);
+	}
+
+	for seed in &plan.missing {
+		println!(
+			"{:<32} file missing, applied at {} ({} rows)",
+			seed.file, seed.applied_at, seed.row_count
+		);
+	}
+
+	Ok(())
+}
+
+// "posts:price > 10000" 을 테이블별 where 절로 나눈다
+fn export_tables(
+	tables: Vec<String>,
+	filters: Vec<String>,
+) -> Result<Vec<ExportTable>, Box<dyn Error>> {
+	let mut exports: Vec<ExportTable> = tables
+		.into_iter()
+		.map(|table| ExportTable { table, filter: None })
+		.collect();
+
+	for filter in filters {
+		let Some((table, condition)) = filter.split_once(':')
+		else {
+			return Err(
+				format!(
+					"--where {:?} must look like TABLE:CONDITION",
+					filter
+				)
+				.into(),
+			);
+		};
+		let Some(export) = exports
+			.iter_mut()
+			.find(|export| export.table == table.trim())
+		else {
+			return Err(
+				format!(
+					"--where {:?} names a table that is not exported",
+					filter
+				)
+				.into(),
+			);
+		};
+		export.filter = Some(condition.trim().to_string());
+	}
+
+	Ok(exports)
+}
+
+async fn export_command(
+	pool: &Pool<Postgres>,
+	target: &SeedTarget,
+	tables: Vec<String>,
+	filters: Vec<String>,
+	out: Option<PathBuf>,
+	force: bool,
+) -> CommandResult {
+	let exports = export_tables(tables, filters)?;
+	let folder = match out {
+		Some(out) => out,
+		None => seed_folder(target)?.1,
+	};
+
+	for file in export(pool, &exports, &folder, force).await?
+	{
+		println!(
+			"✅ {} → {} ({} rows)",
+			file.table,
+			file.path.display(),
+			file.rows
+		);
+	}
+	Ok(())
+}
+
+// 파일로 쓸 때는 DB에 연결하지 않는다, DB에 넣을 때는 users.password 같은 컬럼 변환을 거친다
+async fn generate_command(
+	target: &SeedTarget,
+	options: FakeOptions,
+	out: Option<PathBuf>,
+	ndjson: bool,
+	force: bool,
+) -> CommandResult {
+	if options.users == 0
+		&& (options.posts > 0 || options.orders > 0)
+	{
+		return Err(
+			"posts and orders need at least one user".into(),
+		);
+	}
+	let tables = generate(&options);
+
+	let Some(folder) = out else {
+		let pool = connect().await?;
+		let tables = tables
+			.into_iter()
+			.map(|fake| (fake.table, fake.rows))
+			.collect();
+		seed_tables(&pool, target, tables).await?;
+		return Ok(());
+	};
+
+	let extension = if ndjson { "ndjson" } else { "json" };
+	for fake in &tables {
+		let path =
+			folder.join(format!("{}.{}", fake.table, extension));
+		if path.exists() && !force {
+			return Err(
+				format!(
+					"{} already exists, pass --force to overwrite",
+					path.display()
+				)
+				.into(),
+			);
+		}
+	}
+	for fake in tables {
+		let rows = fake.rows.len();
+		let path =
+			write_rows(&folder, fake.table, fake.rows, ndjson)?;
+		println!(
+			"✅ {} → {} ({} rows)",
+			fake.table,
+			path.display(),
+			rows
+		);
+	}
+	Ok(())
+}
+
+async fn connect() -> Result<Pool<Postgres>, Box<dyn Error>>
+{
+	let database_url = std::env::var("DATABASE_URL")
+		.map_err(|_| "DATABASE_URL must be set")?;
+
+	PgPoolOptions::new()
+		.max_connections(2)
+		.connect(&database_url)
+		.await
+		.map_err(|err| {
+			format!(
+				"Failed to connect to the database: {:?}",
+				err
+			)
+			.into()
+		})
+}
+
+async fn run(
+	target: &SeedTarget,
+	command: Command,
+) -> CommandResult {
+	match command {
+		Command::Plan => {
+			plan_command(&connect().await?, target).await
+		}
+		Command::Apply { dry_run } => {
+			apply_command(&connect().await?, target, dry_run)
+				.await
+		}
+		Command::Reset { yes, cascade } => {
+			reset_command(&connect().await?, target, yes, cascade)
+				.await
+		}
+		Command::Status => {
+			status_command(&connect().await?, target).await
+		}
+		Command::Export { tables, filters, out, force } => {
+			export_command(
+				&connect().await?,
+				target,
+				tables,
+				filters,
+				out,
+				force,
+			)
+			.await
+		}
+		Command::Generate {
+			seed,
+			users,
+			posts,
+			orders,
+			reviews,
+			out,
+			ndjson,
+			force,
+		} => {
+			let options =
+				FakeOptions { seed, users, posts, orders, reviews };
+			generate_command(target, options, out, ndjson, force)
+				.await
+		}
+	}
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+	dotenv().ok();
+	let cli = Cli::parse();
+
+	// 플래그가 환경 변수보다 우선한다
+	let mut target = SeedTarget::from_env();
+	if cli.root.is_some() {
+		target.root = cli.root;
+	}
+	if cli.set.is_some() {
+		target.set = cli.set;
+	}
+
+	let result = run(&target, cli.command).await;
+
+	match result {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(err) => {
+			eprintln!("🔥 {}", err);
+			ExitCode::FAILURE
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn subcommands_are_parsed() {
+		let cli =
+			Cli::try_parse_from(["seed", "apply", "--dry-run"])
+				.unwrap();
+		assert!(matches!(
+			cli.command,
+			Command::Apply { dry_run: true }
+		));
+
+		let cli =
+			Cli::try_parse_from(["seed", "reset", "--yes"])
+				.unwrap();
+		assert!(matches!(
+			cli.command,
+			Command::Reset { yes: true, cascade: false }
+		));
+
+		// --set, --root는 하위 명령 뒤에 와도 된다
+		let cli = Cli::try_parse_from([
+			"seed", "status", "--set", "test", "--root", "api",
+		])
+		.unwrap();
+		assert_eq!(cli.set.as_deref(), Some("test"));
+		assert_eq!(cli.root, Some(PathBuf::from("api")));
+		assert!(matches!(cli.command, Command::Status));
+
+		assert!(Cli::try_parse_from(["seed"]).is_err());
+		assert!(Cli::try_parse_from(["seed", "plan", "--yes"])
+			.is_err());
+	}
+
+	#[test]
+	fn export_filters_go_to_their_table() {
+		let cli = Cli::try_parse_from([
+			"seed",
+			"export",
+			"users",
+			"posts",
+			"--where",
+			"posts: price > 10000",
+		])
+		.unwrap();
+		let Command::Export { tables, filters, .. } =
+			cli.command
+		else {
+			panic!("not an export command");
+		};
+		let exports = export_tables(tables, filters).unwrap();
+		assert_eq!(exports[0].filter, None);
+		assert_eq!(
+			exports[1].filter.as_deref(),
+			Some("price > 10000")
+		);
+
+		for filter in ["price > 10000", "orders:price > 10000"]
+		{
+			assert!(export_tables(
+				vec!["posts".to_string()],
+				vec![filter.to_string()]
+			)
+			.is_err());
+		}
+		assert!(
+			Cli::try_parse_from(["seed", "export"]).is_err()
+		);
+	}
+
+	#[test]
+	fn generate_files_options_need_out() {
+		let cli = Cli::try_parse_from([
+			"seed", "generate", "--seed", "7", "--users", "10",
+		])
+		.unwrap();
+		assert!(matches!(
+			cli.command,
+			Command::Generate {
+				seed: 7,
+				users: 10,
+				posts: 500,
+				out: None,
+				..
+			}
+		));
+
+		assert!(Cli::try_parse_from([
+			"seed", "generate", "--ndjson"
+		])
+		.is_err());
+		assert!(Cli::try_parse_from([
+			"seed", "generate", "--out", "fake", "--ndjson",
+		])
+		.is_ok());
+	}
+}
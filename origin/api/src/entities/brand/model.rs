@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct Brand {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub slug: String,
+	pub name: String,
+	pub logo_src: Option<String>,
+	pub description: Option<String>,
+	pub created_at: Option<DateTime<Utc>>,
+	pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct BrandSummary {
+	pub uuid: Uuid,
+	pub slug: String,
+	pub name: String,
+	pub logo_src: Option<String>,
+	pub product_count: i64,
+}
+
+// average_rating은 리뷰 수로 가중평균한 값이다
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct BrandStats {
+	pub product_count: i64,
+	pub review_count: i64,
+	pub average_rating: f64,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BrandInput {
+	pub name: String,
+	pub slug: Option<String>,
+	pub logo_src: Option<String>,
+	pub description: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BrandUpdate {
+	pub name: Option<String>,
+	pub slug: Option<String>,
+	pub logo_src: Option<String>,
+	pub description: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BrandMergeInput {
+	pub into: Uuid,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BrandPageQuery {
+	pub page: Option<i64>,
+}
@@ -0,0 +1,494 @@
+use actix_web::{
+	get, post, put, web, HttpResponse, Responder,
+};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::model::{
+	Brand, BrandInput, BrandMergeInput, BrandPageQuery,
+	BrandStats, BrandSummary, BrandUpdate,
+};
+use crate::entities::{
+	category::repo::make_slug, post::model::Post,
+	user::auth::AdminUser,
+};
+use crate::error::{conflict_on_unique, ApiError};
+use crate::AppState;
+
+const LIMIT: i64 = 12;
+const SLUG_CONFLICT: &str = "Brand slug already exists";
+
+pub async fn brand_stats(
+	pool: &PgPool,
+	brand_id: Uuid,
+) -> Result<BrandStats, sqlx::Error> {
+	sqlx::query_as!(
+		BrandStats,
+		r#"select
+			count(*) as "product_count!",
+			coalesce(sum(num_reviews), 0)::bigint as "review_count!",
+			coalesce(sum(rating * num_reviews) / nullif(sum(num_reviews), 0), 0)::double precision as "average_rating!"
+		from posts where brand_id = $1"#,
+		brand_id
+	)
+	.fetch_one(pool)
+	.await
+}
+
+// source 브랜드의 상품과 별칭을 target으로 옮기고 source는 지운다
+pub async fn merge_brands(
+	pool: &PgPool,
+	source_id: Uuid,
+	target_id: Uuid,
+) -> Result<Brand, ApiError> {
+	if source_id == target_id {
+		return Err(ApiError::BadRequest(
+			"A brand cannot be merged into itself".to_string(),
+		));
+	}
+
+	let mut tx = pool.begin().await?;
+
+	let source = sqlx::query_as!(
+		Brand,
+		"select * from brands where uuid = $1 for update",
+		source_id
+	)
+	.fetch_optional(&mut *tx)
+	.await?
+	.ok_or(ApiError::NotFound("Brand not found"))?;
+
+	let target = sqlx::query_as!(
+		Brand,
+		"select * from brands where uuid = $1 for update",
+		target_id
+	)
+	.fetch_optional(&mut *tx)
+	.await?
+	.ok_or(ApiError::NotFound("Target brand not found"))?;
+
+	sqlx::query!("update brand_aliases set brand_id = $2 where brand_id = $1", source.uuid, target.uuid)
+		.execute(&mut *tx)
+		.await?;
+
+	sqlx::query!(
+		"insert into brand_aliases (alias_slug, brand_id) values ($1, $2)
+		on conflict (alias_slug) do update set brand_id = excluded.brand_id",
+		source.slug,
+		target.uuid
+	)
+	.execute(&mut *tx)
+	.await?;
+
+	sqlx::query!(
+		"update posts set brand_id = $2, brand = $3 where brand_id = $1",
+		source.uuid,
+		target.uuid,
+		target.name
+	)
+	.execute(&mut *tx)
+	.await?;
+
+	sqlx::query!(
+		"delete from brands where uuid = $1",
+		source.uuid
+	)
+	.execute(&mut *tx)
+	.await?;
+
+	tx.commit().await?;
+	Ok(target)
+}
+
+pub async fn update_brand(
+	pool: &PgPool,
+	brand_id: Uuid,
+	input: &BrandUpdate,
+) -> Result<Brand, ApiError> {
+	let slug = match &input.slug {
+		Some(slug) => Some(make_slug(pool, slug).await?),
+		None => None,
+	};
+
+	let mut tx = pool.begin().await?;
+
+	let old_slug = sqlx::query_scalar!(
+		"select slug from brands where uuid = $1 for update",
+		brand_id
+	)
+	.fetch_optional(&mut *tx)
+	.await?
+	.ok_or(ApiError::NotFound("Brand not found"))?;
+
+	let brand = sqlx::query_as!(
+		Brand,
+		"update brands set
+			name = coalesce($2, name),
+			slug = coalesce($3, slug),
+			logo_src = coalesce($4, logo_src),
+			description = coalesce($5, description),
+			updated_at = now()
+		where uuid = $1
+		returning *",
+		brand_id,
+		input.name.as_deref().map(str::trim),
+		slug,
+		input.logo_src,
+		input.description
+	)
+	.fetch_optional(&mut *tx)
+	.await
+	.map_err(|err| conflict_on_unique(err, SLUG_CONFLICT))?
+	.ok_or(ApiError::NotFound("Brand not found"))?;
+
+	// slug를 바꾸면 예전 slug로 들어오는 상품도 이 브랜드로 연결되도록 별칭으로 남긴다
+	if brand.slug != old_slug {
+		sqlx::query!(
+			"delete from brand_aliases where alias_slug = $1",
+			brand.slug
+		)
+		.execute(&mut *tx)
+		.await?;
+
+		sqlx::query!(
+			"insert into brand_aliases (alias_slug, brand_id) values ($1, $2)
+			on conflict (alias_slug) do update set brand_id = excluded.brand_id",
+			old_slug,
+			brand.uuid
+		)
+		.execute(&mut *tx)
+		.await?;
+	}
+
+	// 브랜드 이름을 바꾸면 상품의 brand 문자열도 같이 바꾼다
+	if input.name.is_some() {
+		sqlx::query!(
+			"update posts set brand = $2 where brand_id = $1",
+			brand_id,
+			brand.name
+		)
+		.execute(&mut *tx)
+		.await?;
+	}
+
+	tx.commit().await?;
+	Ok(brand)
+}
+
+#[get("")]
+pub async fn get_brands(
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let query_result = sqlx::query_as!(
+		BrandSummary,
+		r#"select b.uuid, b.slug, b.name, b.logo_src, count(p.id) as "product_count!"
+		from brands b
+		left join posts p on p.brand_id = b.uuid
+		group by b.id
+		order by b.name"#
+	)
+	.fetch_all(&data.db)
+	.await;
+
+	match query_result {
+		Ok(brands) => HttpResponse::Ok().json(brands),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+// 브랜드 상세, 통계와 상품 목록을 같이 보내준다
+#[get("/{slug}")]
+pub async fn get_brand_detail(
+	path: web::Path<String>,
+	query: web::Query<BrandPageQuery>,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let page = query.page.unwrap_or(0).max(0);
+
+	let query_result = sqlx::query_as!(
+		Brand,
+		"select * from brands where slug = $1",
+		path.into_inner()
+	)
+	.fetch_optional(&data.db)
+	.await;
+
+	let brand = match query_result {
+		Ok(Some(brand)) => brand,
+		Ok(None) => {
+			return ApiError::NotFound("Brand not found")
+				.into_response()
+		}
+		Err(err) => {
+			return ApiError::Database(err).into_response()
+		}
+	};
+
+	let stats = match brand_stats(&data.db, brand.uuid).await
+	{
+		Ok(stats) => stats,
+		Err(err) => {
+			return ApiError::Database(err).into_response()
+		}
+	};
+
+	let query_result = sqlx::query_as!(
+		Post,
+		"select * from posts where brand_id = $1 order by id limit $2 offset $3",
+		brand.uuid,
+		LIMIT,
+		page * LIMIT
+	)
+	.fetch_all(&data.db)
+	.await;
+
+	match query_result {
+		Ok(posts) => HttpResponse::Ok().json(json!({
+			"brand": brand,
+			"stats": stats,
+			"posts": posts,
+			"page": page,
+		})),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[post("")]
+pub async fn create_brand(
+	body: web::Json<BrandInput>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let name = body.name.trim();
+	if name.is_empty() {
+		return ApiError::BadRequest(
+			"name is required".to_string(),
+		)
+		.into_response();
+	}
+
+	let slug = match make_slug(
+		&data.db,
+		body.slug.as_deref().unwrap_or(name),
+	)
+	.await
+	{
+		Ok(slug) => slug,
+		Err(err) => return err.into_response(),
+	};
+
+	let query_result = sqlx::query_as!(
+		Brand,
+		"insert into brands (name, slug, logo_src, description) values ($1, $2, $3, $4)
+		returning *",
+		name,
+		slug,
+		body.logo_src,
+		body.description
+	)
+	.fetch_one(&data.db)
+	.await;
+
+	match query_result {
+		Ok(brand) => HttpResponse::Created().json(brand),
+		Err(err) => {
+			conflict_on_unique(err, SLUG_CONFLICT).into_response()
+		}
+	}
+}
+
+#[put("/{brand_id}")]
+pub async fn edit_brand(
+	path: web::Path<Uuid>,
+	body: web::Json<BrandUpdate>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match update_brand(&data.db, path.into_inner(), &body)
+		.await
+	{
+		Ok(brand) => HttpResponse::Ok().json(brand),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[post("/{brand_id}/merge")]
+pub async fn merge_brand(
+	path: web::Path<Uuid>,
+	body: web::Json<BrandMergeInput>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match merge_brands(&data.db, path.into_inner(), body.into)
+		.await
+	{
+		Ok(brand) => HttpResponse::Ok().json(brand),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::{insert_post, insert_user};
+
+	async fn insert_branded_post(
+		pool: &PgPool,
+		seller: Uuid,
+		title: &str,
+		brand: &str,
+	) -> Uuid {
+		let post_id = insert_post(pool, seller, title).await;
+		sqlx::query_scalar(
+			"update posts set brand = $2, brand_id = null where uuid = $1
+			returning brand_id",
+		)
+		.bind(post_id)
+		.bind(brand)
+		.fetch_one(pool)
+		.await
+		.unwrap()
+	}
+
+	async fn brand_by_slug(
+		pool: &PgPool,
+		slug: &str,
+	) -> Uuid {
+		sqlx::query_scalar(
+			"select uuid from brands where slug = $1",
+		)
+		.bind(slug)
+		.fetch_one(pool)
+		.await
+		.unwrap()
+	}
+
+	fn update(slug: &str) -> BrandUpdate {
+		BrandUpdate {
+			name: None,
+			slug: Some(slug.to_string()),
+			logo_src: None,
+			description: None,
+		}
+	}
+
+	#[sqlx::test(
+		migrator = "crate::entities::migration::repo::MIGRATOR"
+	)]
+	async fn brand_spellings_share_one_brand(pool: PgPool) {
+		let seller = insert_user(&pool, "seller").await;
+		let first = insert_branded_post(
+			&pool,
+			seller,
+			"a",
+			"New Balance",
+		)
+		.await;
+		let second = insert_branded_post(
+			&pool,
+			seller,
+			"b",
+			" new  balance ",
+		)
+		.await;
+		assert_eq!(first, second);
+
+		let names: Vec<String> = sqlx::query_scalar(
+			"select distinct brand from posts where brand_id = $1",
+		)
+		.bind(first)
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+		assert_eq!(names, ["New Balance"]);
+	}
+
+	#[sqlx::test(
+		migrator = "crate::entities::migration::repo::MIGRATOR"
+	)]
+	async fn merged_brand_spelling_is_routed_to_target(
+		pool: PgPool,
+	) {
+		let seller = insert_user(&pool, "seller").await;
+		insert_branded_post(&pool, seller, "a", "Nike").await;
+		insert_branded_post(&pool, seller, "b", "Nikey").await;
+		let nike = brand_by_slug(&pool, "nike").await;
+		let nikey = brand_by_slug(&pool, "nikey").await;
+
+		merge_brands(&pool, nikey, nike).await.unwrap();
+
+		let brand_id =
+			insert_branded_post(&pool, seller, "c", "NIKEY")
+				.await;
+		assert_eq!(brand_id, nike);
+		assert_eq!(
+			brand_stats(&pool, nike).await.unwrap().product_count,
+			3
+		);
+		assert!(matches!(
+			merge_brands(&pool, nike, nike).await,
+			Err(ApiError::BadRequest(_))
+		));
+	}
+
+	#[sqlx::test(
+		migrator = "crate::entities::migration::repo::MIGRATOR"
+	)]
+	async fn old_slug_is_kept_as_alias(pool: PgPool) {
+		let seller = insert_user(&pool, "seller").await;
+		insert_branded_post(&pool, seller, "a", "Adidas").await;
+		let adidas = brand_by_slug(&pool, "adidas").await;
+
+		let brand = update_brand(
+			&pool,
+			adidas,
+			&update("adidas-originals"),
+		)
+		.await
+		.unwrap();
+		assert_eq!(brand.slug, "adidas-originals");
+
+		let brand_id =
+			insert_branded_post(&pool, seller, "b", "adidas")
+				.await;
+		assert_eq!(brand_id, adidas);
+
+		// 되돌리면 새 slug가 별칭이 되고 예전 별칭은 사라진다
+		update_brand(&pool, adidas, &update("adidas"))
+			.await
+			.unwrap();
+		let aliases: Vec<String> = sqlx::query_scalar(
+			"select alias_slug from brand_aliases where brand_id = $1",
+		)
+		.bind(adidas)
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+		assert_eq!(aliases, ["adidas-originals"]);
+	}
+
+	#[sqlx::test(
+		migrator = "crate::entities::migration::repo::MIGRATOR"
+	)]
+	async fn average_rating_is_weighted_by_reviews(
+		pool: PgPool,
+	) {
+		let seller = insert_user(&pool, "seller").await;
+		let brand_id =
+			insert_branded_post(&pool, seller, "a", "Puma").await;
+		insert_branded_post(&pool, seller, "b", "Puma").await;
+		sqlx::query(
+			"update posts set rating = case title when 'a' then 5 else 2 end,
+				num_reviews = case title when 'a' then 3 else 1 end",
+		)
+		.execute(&pool)
+		.await
+		.unwrap();
+
+		let stats = brand_stats(&pool, brand_id).await.unwrap();
+		assert_eq!(stats.product_count, 2);
+		assert_eq!(stats.review_count, 4);
+		assert!((stats.average_rating - 4.25).abs() < 1e-9);
+	}
+}
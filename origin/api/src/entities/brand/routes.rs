@@ -0,0 +1,15 @@
+use actix_web::web;
+
+use super::repo::{
+	create_brand, edit_brand, get_brand_detail, get_brands,
+	merge_brand,
+};
+
+pub fn brand_routes() -> actix_web::Scope {
+	web::scope("")
+		.service(get_brands)
+		.service(get_brand_detail)
+		.service(create_brand)
+		.service(edit_brand)
+		.service(merge_brand)
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct Category {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub parent_id: Option<Uuid>,
+	pub slug: String,
+	pub name: String,
+	pub names: Value,
+	pub display_order: i64,
+	pub created_at: Option<DateTime<Utc>>,
+	pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Category {
+	// 요청한 언어의 이름이 없으면 기본 이름(name)을 사용한다
+	pub fn localized_name(
+		&self,
+		lang: Option<&str>,
+	) -> String {
+		lang
+			.and_then(|lang| self.names.get(lang))
+			.and_then(Value::as_str)
+			.unwrap_or(&self.name)
+			.to_string()
+	}
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct CategoryNode {
+	#[serde(flatten)]
+	pub category: Category,
+	pub display_name: String,
+	pub children: Vec<CategoryNode>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Breadcrumb {
+	pub uuid: Uuid,
+	pub slug: String,
+	pub display_name: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LangQuery {
+	pub lang: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CategoryPostsQuery {
+	pub page: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CategoryInput {
+	pub name: String,
+	pub slug: Option<String>,
+	pub parent_id: Option<Uuid>,
+	pub names: Option<Value>,
+	pub display_order: Option<i64>,
+}
+
+// 수정할 값만 보낸다, parent_id를 null로 보내면 최상위로 옮긴다
+#[derive(Deserialize, Debug)]
+pub struct CategoryUpdate {
+	pub name: Option<String>,
+	pub slug: Option<String>,
+	#[serde(default, deserialize_with = "double_option")]
+	pub parent_id: Option<Option<Uuid>>,
+	pub names: Option<Value>,
+	pub display_order: Option<i64>,
+}
+
+// 필드가 없으면 None, null이면 Some(None)
+fn double_option<'de, D, T>(
+	deserializer: D,
+) -> Result<Option<Option<T>>, D::Error>
+where
+	D: Deserializer<'de>,
+	T: Deserialize<'de>,
+{
+	Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn missing_language_falls_back_to_name() {
+		let category = Category {
+			id: 1,
+			uuid: Uuid::new_v4(),
+			parent_id: None,
+			slug: "shirts".to_string(),
+			name: "셔츠".to_string(),
+			names: json!({"en": "Shirts"}),
+			display_order: 0,
+			created_at: None,
+			updated_at: None,
+		};
+		assert_eq!(
+			category.localized_name(Some("en")),
+			"Shirts"
+		);
+		assert_eq!(category.localized_name(Some("ja")), "셔츠");
+		assert_eq!(category.localized_name(None), "셔츠");
+	}
+
+	#[test]
+	fn null_parent_moves_to_top_level() {
+		let update: CategoryUpdate =
+			serde_json::from_value(json!({"parent_id": null}))
+				.unwrap();
+		assert_eq!(update.parent_id, Some(None));
+
+		let update: CategoryUpdate =
+			serde_json::from_value(json!({"name": "셔츠"}))
+				.unwrap();
+		assert_eq!(update.parent_id, None);
+	}
+}
@@ -0,0 +1,483 @@
+use std::collections::HashMap;
+
+use actix_web::{
+	delete, get, post, put, web, HttpResponse, Responder,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::model::{
+	Breadcrumb, Category, CategoryInput, CategoryNode,
+	CategoryUpdate, LangQuery,
+};
+use crate::entities::user::auth::AdminUser;
+use crate::error::{conflict_on_unique, ApiError};
+use crate::AppState;
+
+const SLUG_CONFLICT: &str = "Category slug already exists";
+
+pub async fn load_categories(
+	pool: &PgPool,
+) -> Result<Vec<Category>, sqlx::Error> {
+	sqlx::query_as!(Category, "select * from categories order by display_order, name, id")
+		.fetch_all(pool)
+		.await
+}
+
+// parent_id 기준으로 묶어서 트리를 만든다, 형제끼리는 display_order 순서를 유지한다
+pub fn build_tree(
+	categories: Vec<Category>,
+	lang: Option<&str>,
+) -> Vec<CategoryNode> {
+	let mut by_parent: HashMap<Option<Uuid>, Vec<Category>> =
+		HashMap::new();
+	for category in categories {
+		by_parent
+			.entry(category.parent_id)
+			.or_default()
+			.push(category);
+	}
+
+	fn build(
+		parent_id: Option<Uuid>,
+		by_parent: &mut HashMap<Option<Uuid>, Vec<Category>>,
+		lang: Option<&str>,
+	) -> Vec<CategoryNode> {
+		let children =
+			by_parent.remove(&parent_id).unwrap_or_default();
+
+		children
+			.into_iter()
+			.map(|category| CategoryNode {
+				display_name: category.localized_name(lang),
+				children: build(
+					Some(category.uuid),
+					by_parent,
+					lang,
+				),
+				category,
+			})
+			.collect()
+	}
+
+	build(None, &mut by_parent, lang)
+}
+
+// 최상위 카테고리부터 slug에 해당하는 카테고리까지
+pub async fn load_breadcrumb(
+	pool: &PgPool,
+	slug: &str,
+) -> Result<Vec<Category>, sqlx::Error> {
+	sqlx::query_as::<_, Category>(
+		"with recursive chain as (
+			select c.*, 0 as depth from categories c where c.slug = $1
+			union all
+			select c.*, chain.depth + 1 from categories c join chain on c.uuid = chain.parent_id
+		)
+		select id, uuid, parent_id, slug, name, names, display_order, created_at, updated_at
+		from chain order by depth desc",
+	)
+	.bind(slug)
+	.fetch_all(pool)
+	.await
+}
+
+// 마이그레이션의 category_slug 함수로 slug를 만든다, 기호만 있으면 slug를 만들 수 없다
+pub async fn make_slug(
+	pool: &PgPool,
+	value: &str,
+) -> Result<String, ApiError> {
+	let slug =
+		sqlx::query_scalar!("select category_slug($1)", value)
+			.fetch_one(pool)
+			.await?
+			.unwrap_or_default();
+
+	if slug.is_empty() {
+		return Err(ApiError::BadRequest(
+			"slug must contain letters or digits".to_string(),
+		));
+	}
+	Ok(slug)
+}
+
+// 새 부모가 자기 자신이거나 자신의 하위 카테고리면 순환이 생긴다
+async fn creates_cycle(
+	pool: &PgPool,
+	category_id: Uuid,
+	parent_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+	let found: Option<bool> = sqlx::query_scalar(
+		"with recursive tree as (
+			select uuid from categories where uuid = $1
+			union all
+			select c.uuid from categories c join tree t on c.parent_id = t.uuid
+		)
+		select exists (select 1 from tree where uuid = $2)",
+	)
+	.bind(category_id)
+	.bind(parent_id)
+	.fetch_one(pool)
+	.await?;
+
+	Ok(found.unwrap_or(false))
+}
+
+pub async fn update_category(
+	pool: &PgPool,
+	category_id: Uuid,
+	input: &CategoryUpdate,
+) -> Result<Category, ApiError> {
+	if let Some(Some(parent_id)) = input.parent_id {
+		if creates_cycle(pool, category_id, parent_id).await? {
+			return Err(ApiError::BadRequest(
+				"A category cannot be moved under itself or its descendants".to_string(),
+			));
+		}
+	}
+
+	let slug = match &input.slug {
+		Some(slug) => Some(make_slug(pool, slug).await?),
+		None => None,
+	};
+
+	let mut tx = pool.begin().await?;
+
+	let category = sqlx::query_as!(
+		Category,
+		"update categories set
+			name = coalesce($2, name),
+			slug = coalesce($3, slug),
+			parent_id = case when $4 then $5 else parent_id end,
+			names = coalesce($6, names),
+			display_order = coalesce($7, display_order),
+			updated_at = now()
+		where uuid = $1
+		returning *",
+		category_id,
+		input.name.as_deref().map(str::trim),
+		slug,
+		input.parent_id.is_some(),
+		input.parent_id.flatten(),
+		input.names,
+		input.display_order
+	)
+	.fetch_optional(&mut *tx)
+	.await
+	.map_err(|err| match &err {
+		sqlx::Error::Database(db_err)
+			if db_err.is_foreign_key_violation() =>
+		{
+			ApiError::NotFound("Parent category not found")
+		}
+		_ => conflict_on_unique(err, SLUG_CONFLICT),
+	})?
+	.ok_or(ApiError::NotFound("Category not found"))?;
+
+	// 이름을 바꾸면 상품의 category 문자열도 같이 바꿔서 검색 결과가 어긋나지 않게 한다
+	if input.name.is_some() {
+		sqlx::query!(
+			"update posts set category = $2 where category_id = $1",
+			category_id,
+			category.name
+		)
+		.execute(&mut *tx)
+		.await?;
+	}
+
+	tx.commit().await?;
+	Ok(category)
+}
+
+#[get("/tree")]
+pub async fn get_category_tree(
+	query: web::Query<LangQuery>,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match load_categories(&data.db).await {
+		Ok(categories) => HttpResponse::Ok()
+			.json(build_tree(categories, query.lang.as_deref())),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[get("/{slug}/breadcrumb")]
+pub async fn get_breadcrumb(
+	path: web::Path<String>,
+	query: web::Query<LangQuery>,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match load_breadcrumb(&data.db, &path.into_inner()).await
+	{
+		Ok(chain) if chain.is_empty() => {
+			ApiError::NotFound("Category not found")
+				.into_response()
+		}
+		Ok(chain) => {
+			let breadcrumb: Vec<Breadcrumb> = chain
+				.iter()
+				.map(|category| Breadcrumb {
+					uuid: category.uuid,
+					slug: category.slug.clone(),
+					display_name: category
+						.localized_name(query.lang.as_deref()),
+				})
+				.collect();
+			HttpResponse::Ok().json(breadcrumb)
+		}
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[post("")]
+pub async fn create_category(
+	body: web::Json<CategoryInput>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let name = body.name.trim();
+	if name.is_empty() {
+		return ApiError::BadRequest(
+			"name is required".to_string(),
+		)
+		.into_response();
+	}
+
+	let slug = match make_slug(
+		&data.db,
+		body.slug.as_deref().unwrap_or(name),
+	)
+	.await
+	{
+		Ok(slug) => slug,
+		Err(err) => return err.into_response(),
+	};
+
+	let query_result = sqlx::query_as!(
+		Category,
+		"insert into categories (name, slug, parent_id, names, display_order)
+		values ($1, $2, $3, $4, $5)
+		returning *",
+		name,
+		slug,
+		body.parent_id,
+		body.names.clone().unwrap_or_else(|| serde_json::json!({})),
+		body.display_order.unwrap_or(0)
+	)
+	.fetch_one(&data.db)
+	.await;
+
+	match query_result {
+		Ok(category) => HttpResponse::Created().json(category),
+		Err(sqlx::Error::Database(db_err))
+			if db_err.is_foreign_key_violation() =>
+		{
+			ApiError::NotFound("Parent category not found")
+				.into_response()
+		}
+		Err(err) => {
+			conflict_on_unique(err, SLUG_CONFLICT).into_response()
+		}
+	}
+}
+
+#[put("/{category_id}")]
+pub async fn edit_category(
+	path: web::Path<Uuid>,
+	body: web::Json<CategoryUpdate>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match update_category(&data.db, path.into_inner(), &body)
+		.await
+	{
+		Ok(category) => HttpResponse::Ok().json(category),
+		Err(err) => err.into_response(),
+	}
+}
+
+// 하위 카테고리가 있으면 지울 수 없다, 연결된 상품은 category_id가 null이 된다
+#[delete("/{category_id}")]
+pub async fn delete_category(
+	path: web::Path<Uuid>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let query_result = sqlx::query!(
+		"delete from categories where uuid = $1",
+		path.into_inner()
+	)
+	.execute(&data.db)
+	.await;
+
+	match query_result {
+		Ok(result) if result.rows_affected() == 0 => {
+			ApiError::NotFound("Category not found")
+				.into_response()
+		}
+		Ok(_) => HttpResponse::NoContent().finish(),
+		Err(sqlx::Error::Database(db_err))
+			if db_err.is_foreign_key_violation() =>
+		{
+			ApiError::Conflict(
+				"Category still has child categories",
+			)
+			.into_response()
+		}
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::{insert_post, insert_user};
+	use serde_json::json;
+
+	fn category(
+		name: &str,
+		parent_id: Option<Uuid>,
+		display_order: i64,
+	) -> Category {
+		Category {
+			id: 0,
+			uuid: Uuid::new_v4(),
+			parent_id,
+			slug: name.to_lowercase(),
+			name: name.to_string(),
+			names: json!({"en": format!("{} (en)", name)}),
+			display_order,
+			created_at: None,
+			updated_at: None,
+		}
+	}
+
+	#[test]
+	fn tree_is_built_from_parent_ids() {
+		let men = category("Men", None, 0);
+		let shirts = category("Shirts", Some(men.uuid), 0);
+		let suits = category("Suits", Some(men.uuid), 1);
+		let women = category("Women", None, 1);
+
+		let tree = build_tree(
+			vec![men.clone(), shirts, suits, women],
+			Some("en"),
+		);
+
+		assert_eq!(tree.len(), 2);
+		assert_eq!(tree[0].display_name, "Men (en)");
+		let children: Vec<_> = tree[0]
+			.children
+			.iter()
+			.map(|node| node.category.name.as_str())
+			.collect();
+		assert_eq!(children, ["Shirts", "Suits"]);
+		assert!(tree[1].children.is_empty());
+	}
+
+	async fn insert_category(
+		pool: &PgPool,
+		slug: &str,
+		parent_id: Option<Uuid>,
+	) -> Uuid {
+		sqlx::query_scalar(
+			"insert into categories (name, slug, parent_id) values ($1, $1, $2)
+			returning uuid",
+		)
+		.bind(slug)
+		.bind(parent_id)
+		.fetch_one(pool)
+		.await
+		.unwrap()
+	}
+
+	#[sqlx::test(
+		migrator = "crate::entities::migration::repo::MIGRATOR"
+	)]
+	async fn category_cannot_move_under_its_descendant(
+		pool: PgPool,
+	) {
+		let men = insert_category(&pool, "men", None).await;
+		let shirts =
+			insert_category(&pool, "shirts", Some(men)).await;
+		let women = insert_category(&pool, "women", None).await;
+
+		assert!(creates_cycle(&pool, men, men).await.unwrap());
+		assert!(creates_cycle(&pool, men, shirts)
+			.await
+			.unwrap());
+		assert!(!creates_cycle(&pool, shirts, women)
+			.await
+			.unwrap());
+	}
+
+	#[sqlx::test(
+		migrator = "crate::entities::migration::repo::MIGRATOR"
+	)]
+	async fn deleted_category_is_not_recreated(pool: PgPool) {
+		let seller = insert_user(&pool, "seller").await;
+		let post_id = insert_post(&pool, seller, "post").await;
+
+		// insert_post는 category 문자열만 넣으므로 트리거가 카테고리를 만든다
+		let category_id: Option<Uuid> = sqlx::query_scalar(
+			"select category_id from posts where uuid = $1",
+		)
+		.bind(post_id)
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+		assert!(category_id.is_some());
+
+		sqlx::query(
+			"delete from categories where slug = 'category'",
+		)
+		.execute(&pool)
+		.await
+		.unwrap();
+
+		let count: i64 = sqlx::query_scalar(
+			"select count(*) from categories where slug = 'category'",
+		)
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+		assert_eq!(count, 0);
+
+		let category_id: Option<Uuid> = sqlx::query_scalar(
+			"select category_id from posts where uuid = $1",
+		)
+		.bind(post_id)
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+		assert_eq!(category_id, None);
+	}
+
+	#[sqlx::test(
+		migrator = "crate::entities::migration::repo::MIGRATOR"
+	)]
+	async fn changed_category_text_is_linked_again(
+		pool: PgPool,
+	) {
+		let seller = insert_user(&pool, "seller").await;
+		let post_id = insert_post(&pool, seller, "post").await;
+
+		let category_id: Option<Uuid> = sqlx::query_scalar(
+			"update posts set category = '남자 정장', category_id = null
+			where uuid = $1 returning category_id",
+		)
+		.bind(post_id)
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+
+		let slug: String = sqlx::query_scalar(
+			"select slug from categories where uuid = $1",
+		)
+		.bind(category_id)
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+		assert_eq!(slug, "남자-정장");
+	}
+}
@@ -0,0 +1,15 @@
+use actix_web::web;
+
+use super::repo::{
+	create_category, delete_category, edit_category,
+	get_breadcrumb, get_category_tree,
+};
+
+pub fn category_routes() -> actix_web::Scope {
+	web::scope("")
+		.service(get_category_tree)
+		.service(get_breadcrumb)
+		.service(create_category)
+		.service(edit_category)
+		.service(delete_category)
+}
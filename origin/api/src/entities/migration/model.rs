@@ -0,0 +1,132 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{migrate::MigrateError, FromRow};
+
+pub const MIGRATION_APPLIED: &str = "applied";
+// 적용한 뒤에 up.sql이 바뀌었다
+pub const MIGRATION_CHANGED: &str = "changed";
+// 실행 중에 실패해서 반쯤 적용된 채로 남았다
+pub const MIGRATION_FAILED: &str = "failed";
+// 이 바이너리에 없는 버전, DB가 바이너리보다 앞서 있다
+pub const MIGRATION_UNKNOWN: &str = "unknown";
+
+#[derive(FromRow, Clone, Debug)]
+pub struct MigrationRecord {
+	pub version: i64,
+	pub description: String,
+	pub installed_on: DateTime<Utc>,
+	pub success: bool,
+	pub checksum: Vec<u8>,
+	pub execution_time: i64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct AppliedMigration {
+	pub version: i64,
+	pub description: String,
+	pub installed_on: DateTime<Utc>,
+	pub execution_ms: i64,
+	pub state: &'static str,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PendingMigration {
+	pub version: i64,
+	pub description: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct MigrationStatus {
+	pub latest: Option<i64>,
+	pub applied: Vec<AppliedMigration>,
+	pub pending: Vec<PendingMigration>,
+}
+
+impl MigrationStatus {
+	pub fn unknown(&self) -> Vec<i64> {
+		self
+			.applied
+			.iter()
+			.filter(|migration| {
+				migration.state == MIGRATION_UNKNOWN
+			})
+			.map(|migration| migration.version)
+			.collect()
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum MigrateStep {
+	Up,
+	// 마지막으로 적용한 하나만 되돌린다
+	Down,
+	// 0이면 모두 되돌린다
+	To(i64),
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MigrationRun {
+	pub applied: Vec<i64>,
+	pub reverted: Vec<i64>,
+}
+
+#[derive(Debug)]
+pub enum MigrationError {
+	Database(sqlx::Error),
+	Migrate(MigrateError),
+	Ahead(Vec<i64>),
+	Changed(i64),
+	Failed(i64),
+	Irreversible(i64),
+	UnknownTarget(i64),
+}
+
+impl From<sqlx::Error> for MigrationError {
+	fn from(err: sqlx::Error) -> Self {
+		MigrationError::Database(err)
+	}
+}
+
+impl From<MigrateError> for MigrationError {
+	fn from(err: MigrateError) -> Self {
+		MigrationError::Migrate(err)
+	}
+}
+
+impl fmt::Display for MigrationError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			MigrationError::Database(err) => write!(f, "{}", err),
+			MigrationError::Migrate(err) => write!(f, "{}", err),
+			MigrationError::Ahead(versions) => write!(
+				f,
+				"database has migration(s) {:?} that this binary does not know, deploy a newer build",
+				versions
+			),
+			MigrationError::Changed(version) => write!(
+				f,
+				"migration {} was changed after it was applied",
+				version
+			),
+			MigrationError::Failed(version) => write!(
+				f,
+				"migration {} failed halfway, fix it by hand and remove its row from _sqlx_migrations",
+				version
+			),
+			MigrationError::Irreversible(version) => write!(
+				f,
+				"migration {} has no down script",
+				version
+			),
+			MigrationError::UnknownTarget(version) => write!(
+				f,
+				"no migration with version {}",
+				version
+			),
+		}
+	}
+}
+
+impl std::error::Error for MigrationError {}
@@ -0,0 +1,441 @@
+use std::collections::HashMap;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use sqlx::{
+	migrate::{Migrate, Migration, Migrator},
+	PgConnection, PgPool,
+};
+
+use super::model::{
+	AppliedMigration, MigrateStep, MigrationError,
+	MigrationRecord, MigrationRun, MigrationStatus,
+	PendingMigration, MIGRATION_APPLIED, MIGRATION_CHANGED,
+	MIGRATION_FAILED, MIGRATION_UNKNOWN,
+};
+use crate::entities::user::auth::AdminUser;
+use crate::error::ApiError;
+use crate::AppState;
+
+// src/migrations의 파일을 바이너리에 넣는다
+pub static MIGRATOR: Migrator =
+	sqlx::migrate!("./src/migrations");
+
+// users가 posts보다 늦게 만들어지던 때의 버전 → 지금 버전
+const RENAMED_VERSIONS: &[(i64, i64)] =
+	&[(20240103061914, 20240103054000)];
+
+fn current_version(version: i64) -> i64 {
+	RENAMED_VERSIONS
+		.iter()
+		.find(|(old, _)| *old == version)
+		.map_or(version, |(_, new)| *new)
+}
+
+fn up_migrations(
+) -> impl Iterator<Item = &'static Migration> {
+	MIGRATOR.iter().filter(|migration| {
+		!migration.migration_type.is_down_migration()
+	})
+}
+
+fn down_migration(
+	version: i64,
+) -> Option<&'static Migration> {
+	MIGRATOR.iter().find(|migration| {
+		migration.version == version
+			&& migration.migration_type.is_down_migration()
+	})
+}
+
+async fn migrations_table_exists(
+	conn: &mut PgConnection,
+) -> Result<bool, sqlx::Error> {
+	sqlx::query_scalar(
+		"select to_regclass('_sqlx_migrations') is not null",
+	)
+	.fetch_one(conn)
+	.await
+}
+
+async fn rename_legacy_versions(
+	conn: &mut PgConnection,
+) -> Result<(), sqlx::Error> {
+	for (old, new) in RENAMED_VERSIONS {
+		sqlx::query(
+			"update _sqlx_migrations set version = $2 where version = $1
+			and not exists (select 1 from _sqlx_migrations where version = $2)",
+		)
+		.bind(old)
+		.bind(new)
+		.execute(&mut *conn)
+		.await?;
+	}
+	Ok(())
+}
+
+// _sqlx_migrations 기록을 바이너리에 든 마이그레이션과 맞춰 본다, 테이블이 없으면 모두 pending
+pub async fn migration_status(
+	conn: &mut PgConnection,
+) -> Result<MigrationStatus, sqlx::Error> {
+	let records = if migrations_table_exists(conn).await? {
+		sqlx::query_as::<_, MigrationRecord>(
+			"select version, description, installed_on, success, checksum, execution_time
+			from _sqlx_migrations order by version",
+		)
+		.fetch_all(conn)
+		.await?
+	} else {
+		Vec::new()
+	};
+
+	let known: HashMap<i64, &Migration> = up_migrations()
+		.map(|migration| (migration.version, migration))
+		.collect();
+
+	let mut applied: Vec<AppliedMigration> = records
+		.into_iter()
+		.map(|record| {
+			let version = current_version(record.version);
+			let state = match known.get(&version) {
+				None => MIGRATION_UNKNOWN,
+				Some(_) if !record.success => MIGRATION_FAILED,
+				Some(migration)
+					if *migration.checksum != *record.checksum =>
+				{
+					MIGRATION_CHANGED
+				}
+				Some(_) => MIGRATION_APPLIED,
+			};
+			AppliedMigration {
+				version,
+				description: record.description,
+				installed_on: record.installed_on,
+				execution_ms: record.execution_time / 1_000_000,
+				state,
+			}
+		})
+		.collect();
+	applied.sort_by_key(|migration| migration.version);
+
+	let pending = up_migrations()
+		.filter(|migration| {
+			!applied
+				.iter()
+				.any(|applied| applied.version == migration.version)
+		})
+		.map(|migration| PendingMigration {
+			version: migration.version,
+			description: migration.description.to_string(),
+		})
+		.collect();
+
+	Ok(MigrationStatus {
+		latest: applied
+			.last()
+			.map(|migration| migration.version),
+		applied,
+		pending,
+	})
+}
+
+fn check_applied(
+	status: &MigrationStatus,
+) -> Result<(), MigrationError> {
+	let unknown = status.unknown();
+	if !unknown.is_empty() {
+		return Err(MigrationError::Ahead(unknown));
+	}
+	for migration in &status.applied {
+		match migration.state {
+			MIGRATION_FAILED => {
+				return Err(MigrationError::Failed(
+					migration.version,
+				))
+			}
+			MIGRATION_CHANGED => {
+				return Err(MigrationError::Changed(
+					migration.version,
+				))
+			}
+			_ => {}
+		}
+	}
+	Ok(())
+}
+
+// 서버 시작 전에 부른다, DB에 이 바이너리가 모르는 버전이 있으면 에러
+pub async fn check_not_ahead(
+	pool: &PgPool,
+) -> Result<MigrationStatus, MigrationError> {
+	let mut conn = pool.acquire().await?;
+	let status = migration_status(&mut conn).await?;
+	let unknown = status.unknown();
+	if !unknown.is_empty() {
+		return Err(MigrationError::Ahead(unknown));
+	}
+	Ok(status)
+}
+
+async fn migrate_locked(
+	conn: &mut PgConnection,
+	step: MigrateStep,
+) -> Result<MigrationRun, MigrationError> {
+	conn.ensure_migrations_table().await?;
+	rename_legacy_versions(conn).await?;
+	let status = migration_status(conn).await?;
+	check_applied(&status)?;
+
+	let latest = status.latest.unwrap_or(0);
+	let (up_to, down_to) = match step {
+		MigrateStep::Up => (Some(i64::MAX), None),
+		MigrateStep::Down => {
+			let previous = status
+				.applied
+				.iter()
+				.rev()
+				.nth(1)
+				.map_or(0, |migration| migration.version);
+			(None, Some(previous))
+		}
+		MigrateStep::To(version) => {
+			let exists = up_migrations()
+				.any(|migration| migration.version == version);
+			if version != 0 && !exists {
+				return Err(MigrationError::UnknownTarget(version));
+			}
+			if version >= latest {
+				(Some(version), None)
+			} else {
+				(None, Some(version))
+			}
+		}
+	};
+
+	let mut run = MigrationRun::default();
+	if let Some(target) = up_to {
+		for pending in &status.pending {
+			if pending.version > target {
+				break;
+			}
+			let migration = up_migrations()
+				.find(|migration| {
+					migration.version == pending.version
+				})
+				.ok_or(MigrationError::UnknownTarget(
+					pending.version,
+				))?;
+			conn.apply(migration).await?;
+			run.applied.push(pending.version);
+		}
+	}
+	if let Some(target) = down_to {
+		for applied in status.applied.iter().rev() {
+			if applied.version <= target {
+				break;
+			}
+			let migration = down_migration(applied.version)
+				.ok_or(MigrationError::Irreversible(
+					applied.version,
+				))?;
+			conn.revert(migration).await?;
+			run.reverted.push(applied.version);
+		}
+	}
+	Ok(run)
+}
+
+// 다른 서버나 명령과 겹치지 않게 advisory lock을 잡고 돌린다
+pub async fn migrate(
+	pool: &PgPool,
+	step: MigrateStep,
+) -> Result<MigrationRun, MigrationError> {
+	let mut conn = pool.acquire().await?;
+	conn.lock().await?;
+	let result = migrate_locked(&mut conn, step).await;
+	conn.unlock().await?;
+	result
+}
+
+#[get("/admin")]
+pub async fn get_migration_status(
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let query_result = match data.db.acquire().await {
+		Ok(mut conn) => migration_status(&mut conn).await,
+		Err(err) => Err(err),
+	};
+
+	match query_result {
+		Ok(status) => HttpResponse::Ok().json(status),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const USERS: i64 = 20240103054000;
+	const POSTS: i64 = 20240103054658;
+
+	async fn status(pool: &PgPool) -> MigrationStatus {
+		let mut conn = pool.acquire().await.unwrap();
+		migration_status(&mut conn).await.unwrap()
+	}
+
+	async fn table_exists(
+		pool: &PgPool,
+		table: &str,
+	) -> bool {
+		sqlx::query_scalar("select to_regclass($1) is not null")
+			.bind(table)
+			.fetch_one(pool)
+			.await
+			.unwrap()
+	}
+
+	#[test]
+	fn renamed_versions_map_to_the_current_file() {
+		assert_eq!(current_version(20240103061914), USERS);
+		assert_eq!(current_version(POSTS), POSTS);
+		assert!(RENAMED_VERSIONS.iter().all(|(old, new)| {
+			up_migrations()
+				.all(|migration| migration.version != *old)
+				&& up_migrations()
+					.any(|migration| migration.version == *new)
+		}));
+	}
+
+	// posts가 users를 참조하므로 users가 먼저 만들어진다
+	#[test]
+	fn every_migration_can_be_reverted_in_order() {
+		let versions: Vec<i64> = up_migrations()
+			.map(|migration| migration.version)
+			.collect();
+		assert_eq!(&versions[..2], [USERS, POSTS]);
+		assert!(versions
+			.iter()
+			.all(|version| down_migration(*version).is_some()));
+	}
+
+	#[sqlx::test(migrations = false)]
+	async fn fresh_database_has_everything_pending(
+		pool: PgPool,
+	) {
+		let status = status(&pool).await;
+		assert_eq!(status.latest, None);
+		assert!(status.applied.is_empty());
+		assert_eq!(
+			status.pending.len(),
+			up_migrations().count()
+		);
+	}
+
+	#[sqlx::test(migrations = false)]
+	async fn migrate_goes_up_down_and_to_a_version(
+		pool: PgPool,
+	) {
+		let run =
+			migrate(&pool, MigrateStep::Up).await.unwrap();
+		assert_eq!(run.applied.len(), up_migrations().count());
+		let applied = status(&pool).await;
+		assert!(applied.pending.is_empty());
+		assert!(applied.applied.iter().all(
+			|migration| migration.state == MIGRATION_APPLIED
+		));
+
+		let latest = applied.latest.unwrap();
+		let run =
+			migrate(&pool, MigrateStep::Down).await.unwrap();
+		assert_eq!(run.reverted, [latest]);
+		assert_eq!(status(&pool).await.pending.len(), 1);
+
+		let run =
+			migrate(&pool, MigrateStep::To(USERS)).await.unwrap();
+		assert_eq!(run.reverted.last(), Some(&POSTS));
+		assert!(table_exists(&pool, "users").await);
+		assert!(!table_exists(&pool, "posts").await);
+
+		let err =
+			migrate(&pool, MigrateStep::To(1)).await.unwrap_err();
+		assert!(matches!(
+			err,
+			MigrationError::UnknownTarget(1)
+		));
+
+		migrate(&pool, MigrateStep::To(0)).await.unwrap();
+		assert!(!table_exists(&pool, "users").await);
+		assert_eq!(status(&pool).await.latest, None);
+	}
+
+	// 이름을 바꾸기 전에 users를 적용한 DB도 다시 적용하지 않는다
+	#[sqlx::test(migrations = false)]
+	async fn legacy_users_version_is_renamed(pool: PgPool) {
+		migrate(&pool, MigrateStep::Up).await.unwrap();
+		sqlx::query(
+			"update _sqlx_migrations set version = 20240103061914 where version = $1",
+		)
+		.bind(USERS)
+		.execute(&pool)
+		.await
+		.unwrap();
+
+		let legacy = status(&pool).await;
+		assert!(legacy.pending.is_empty());
+		assert_eq!(legacy.applied[0].version, USERS);
+		assert_eq!(legacy.applied[0].state, MIGRATION_APPLIED);
+
+		let run =
+			migrate(&pool, MigrateStep::Up).await.unwrap();
+		assert!(run.applied.is_empty());
+		let versions: Vec<i64> = sqlx::query_scalar(
+			"select version from _sqlx_migrations order by version limit 1",
+		)
+		.fetch_all(&pool)
+		.await
+		.unwrap();
+		assert_eq!(versions, [USERS]);
+	}
+
+	#[sqlx::test(migrations = false)]
+	async fn database_ahead_of_the_binary_is_refused(
+		pool: PgPool,
+	) {
+		migrate(&pool, MigrateStep::Up).await.unwrap();
+		sqlx::query(
+			"insert into _sqlx_migrations (version, description, success, checksum, execution_time)
+			values (29990101000000, 'future', true, '\\x00', 0)",
+		)
+		.execute(&pool)
+		.await
+		.unwrap();
+
+		let err = check_not_ahead(&pool).await.unwrap_err();
+		assert!(matches!(
+			err,
+			MigrationError::Ahead(ref versions) if versions == &[29990101000000]
+		));
+		let err =
+			migrate(&pool, MigrateStep::Up).await.unwrap_err();
+		assert!(matches!(err, MigrationError::Ahead(_)));
+	}
+
+	#[sqlx::test(migrations = false)]
+	async fn changed_migrations_stop_migrate(pool: PgPool) {
+		migrate(&pool, MigrateStep::Up).await.unwrap();
+		sqlx::query(
+			"update _sqlx_migrations set checksum = '\\x00' where version = $1",
+		)
+		.bind(POSTS)
+		.execute(&pool)
+		.await
+		.unwrap();
+
+		let status = status(&pool).await;
+		assert_eq!(status.applied[1].state, MIGRATION_CHANGED);
+		let err =
+			migrate(&pool, MigrateStep::Down).await.unwrap_err();
+		assert!(matches!(err, MigrationError::Changed(POSTS)));
+	}
+}
@@ -0,0 +1,7 @@
+use actix_web::web;
+
+use super::repo::get_migration_status;
+
+pub fn migration_routes() -> actix_web::Scope {
+	web::scope("").service(get_migration_status)
+}
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+// notifications.kind 값
+pub const NOTIFY_PRICE_DROP: &str = "price_drop";
+pub const NOTIFY_RESTOCK: &str = "restock";
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct Notification {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub user_id: Uuid,
+	pub post_id: Option<Uuid>,
+	pub kind: String,
+	pub message: String,
+	pub payload: serde_json::Value,
+	pub is_read: bool,
+	pub sent_at: Option<DateTime<Utc>>,
+	pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct NotificationQuery {
+	pub page: Option<i64>,
+	pub unread: Option<bool>,
+}
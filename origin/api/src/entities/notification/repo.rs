@@ -0,0 +1,83 @@
+use actix_web::{get, post, web, HttpResponse, Responder};
+use serde_json::json;
+use uuid::Uuid;
+
+use super::model::{Notification, NotificationQuery};
+use crate::entities::user::auth::AuthUser;
+use crate::error::ApiError;
+use crate::AppState;
+
+const LIMIT: i64 = 20;
+
+#[get("")]
+pub async fn get_notifications(
+	query: web::Query<NotificationQuery>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let offset = query.page.unwrap_or(0).max(0) * LIMIT;
+
+	let query_result = sqlx::query_as!(
+		Notification,
+		"select * from notifications
+		where user_id = $1 and (not $2 or not is_read)
+		order by created_at desc, id desc
+		limit $3 offset $4",
+		user.uuid,
+		query.unread.unwrap_or(false),
+		LIMIT,
+		offset
+	)
+	.fetch_all(&data.db)
+	.await;
+
+	match query_result {
+		Ok(notifications) => {
+			HttpResponse::Ok().json(notifications)
+		}
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[post("/{notification_id}/read")]
+pub async fn read_notification(
+	path: web::Path<Uuid>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let query_result = sqlx::query!(
+		"update notifications set is_read = true where uuid = $1 and user_id = $2",
+		path.into_inner(),
+		user.uuid
+	)
+	.execute(&data.db)
+	.await;
+
+	match query_result {
+		Ok(result) if result.rows_affected() == 0 => {
+			ApiError::NotFound("Notification not found")
+				.into_response()
+		}
+		Ok(_) => HttpResponse::NoContent().finish(),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[post("/read-all")]
+pub async fn read_all_notifications(
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let query_result = sqlx::query!(
+		"update notifications set is_read = true where user_id = $1 and not is_read",
+		user.uuid
+	)
+	.execute(&data.db)
+	.await;
+
+	match query_result {
+		Ok(result) => HttpResponse::Ok()
+			.json(json!({"updated": result.rows_affected()})),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
@@ -0,0 +1,13 @@
+use actix_web::web;
+
+use super::repo::{
+	get_notifications, read_all_notifications,
+	read_notification,
+};
+
+pub fn notification_routes() -> actix_web::Scope {
+	web::scope("")
+		.service(get_notifications)
+		.service(read_all_notifications)
+		.service(read_notification)
+}
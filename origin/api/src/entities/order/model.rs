@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+// order_items.status 값
+pub const ORDER_ITEM_PENDING: &str = "pending";
+pub const ORDER_ITEM_SHIPPED: &str = "shipped";
+pub const ORDER_ITEM_DELIVERED: &str = "delivered";
+pub const ORDER_ITEM_REFUNDED: &str = "refunded";
+pub const ORDER_ITEM_CANCELLED: &str = "cancelled";
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct Order {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub user_id: Uuid,
+	pub total_price: i64,
+	pub delivery_fee: i64,
+	pub created_at: Option<DateTime<Utc>>,
+	pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct OrderItem {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub order_id: Uuid,
+	pub post_id: Uuid,
+	pub size: String,
+	pub quantity: i64,
+	pub price: i64,
+	pub status: String,
+	pub delivered_at: Option<DateTime<Utc>>,
+	pub refunded_at: Option<DateTime<Utc>>,
+	pub created_at: Option<DateTime<Utc>>,
+	pub updated_at: Option<DateTime<Utc>>,
+}
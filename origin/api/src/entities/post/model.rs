@@ -15,7 +15,9 @@ pub struct Post {
 	pub thumbnail_src: Option<Vec<String>>,
 	pub description: String,
 	pub brand: String,
+	pub brand_id: Option<Uuid>,
 	pub category: String,
+	pub category_id: Option<Uuid>,
 	pub size: serde_json::Value,
 	pub price: i64,
 	pub count_in_stock: i64,
@@ -27,3 +29,132 @@ pub struct Post {
 	pub created_at: Option<DateTime<Utc>>,
 	pub updated_at: Option<DateTime<Utc>>,
 }
+
+// sale은 할인율(%)이다
+pub fn effective_price(price: i64, sale: i64) -> i64 {
+	price - price * sale.clamp(0, 100) / 100
+}
+
+// 상품 등록/수정 요청, 수정도 전체 값을 다시 보낸다
+#[derive(Deserialize, Debug)]
+pub struct PostInput {
+	pub title: String,
+	pub image_src: String,
+	pub thumbnail_src: Option<Vec<String>>,
+	pub description: String,
+	pub brand: String,
+	pub category: String,
+	pub size: serde_json::Value,
+	pub price: i64,
+	pub count_in_stock: Option<i64>,
+	pub sale: Option<i64>,
+	pub free_shipping: Option<bool>,
+	pub delivery_fee: Option<i64>,
+}
+
+impl PostInput {
+	pub fn validate(&self) -> Option<&'static str> {
+		let required =
+			[&self.title, &self.brand, &self.category];
+		if required.iter().any(|value| {
+			value.trim().is_empty() || value.chars().count() > 100
+		}) {
+			return Some("title, brand and category are required and must be at most 100 characters");
+		}
+		if self.image_src.trim().is_empty()
+			|| self.description.trim().is_empty()
+		{
+			return Some(
+				"image_src and description are required",
+			);
+		}
+		let Some(sizes) = self.size.as_object() else {
+			return Some(
+				"size must be an object of size to stock count",
+			);
+		};
+		if sizes
+			.values()
+			.any(|count| count.as_i64().is_none_or(|c| c < 0))
+		{
+			return Some(
+				"size stock counts must be non-negative integers",
+			);
+		}
+		if self.price < 0
+			|| self.count_in_stock.unwrap_or(0) < 0
+			|| self.delivery_fee.unwrap_or(0) < 0
+		{
+			return Some("price, count_in_stock and delivery_fee must be non-negative");
+		}
+		if !(0..=100).contains(&self.sale.unwrap_or(0)) {
+			return Some("sale must be between 0 and 100");
+		}
+		None
+	}
+
+	// 사이즈별 재고가 있으면 합계를 전체 재고로 쓴다
+	pub fn total_stock(&self) -> i64 {
+		match self.size.as_object() {
+			Some(sizes) if !sizes.is_empty() => sizes
+				.values()
+				.filter_map(|count| count.as_i64())
+				.sum(),
+			_ => self.count_in_stock.unwrap_or(0),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sale_is_a_clamped_percentage() {
+		assert_eq!(effective_price(10_000, 0), 10_000);
+		assert_eq!(effective_price(10_000, 15), 8_500);
+		assert_eq!(effective_price(10_000, 150), 0);
+		assert_eq!(effective_price(10_000, -5), 10_000);
+	}
+
+	fn input(size: serde_json::Value) -> PostInput {
+		PostInput {
+			title: "셔츠".to_string(),
+			image_src: "/images/shirt.png".to_string(),
+			thumbnail_src: None,
+			description: "옥스포드 셔츠".to_string(),
+			brand: "brand".to_string(),
+			category: "shirts".to_string(),
+			size,
+			price: 30_000,
+			count_in_stock: Some(7),
+			sale: None,
+			free_shipping: None,
+			delivery_fee: None,
+		}
+	}
+
+	#[test]
+	fn stock_is_summed_from_sizes() {
+		let sizes = serde_json::json!({"95": 2, "100": 3});
+		assert_eq!(input(sizes).total_stock(), 5);
+		assert_eq!(
+			input(serde_json::json!({})).total_stock(),
+			7
+		);
+	}
+
+	#[test]
+	fn negative_size_stock_is_rejected() {
+		assert_eq!(
+			input(serde_json::json!({"95": 1})).validate(),
+			None
+		);
+		assert!(input(serde_json::json!({"95": -1}))
+			.validate()
+			.is_some());
+		assert!(input(serde_json::json!([1, 2]))
+			.validate()
+			.is_some());
+	}
+}
@@ -1,18 +1,31 @@
-use super::model::Post;
-use actix_web::{get, web, HttpResponse, Responder};
+use super::model::{Post, PostInput};
+use actix_web::{
+	delete, get, post, put, web, HttpResponse, Responder,
+};
 
+use crate::entities::qna::repo::answered_public_questions;
+use crate::entities::user::auth::SellerUser;
+use crate::error::ApiError;
 use crate::AppState;
 use serde::Deserialize;
 use serde_json::json;
+use uuid::Uuid;
 
 #[derive(Deserialize, Debug)]
 pub struct PaginationParam {
 	pub pagination: Option<i32>,
 }
 
+// category에 slug를 주면 하위 카테고리 상품까지 포함해서 보여준다
+#[derive(Deserialize, Debug)]
+pub struct PostFilter {
+	pub category: Option<String>,
+}
+
 #[get("/pagination/{pagination}")]
 pub async fn get_posts_pagination(
 	param: web::Path<PaginationParam>,
+	filter: web::Query<PostFilter>,
 	data: web::Data<AppState>,
 ) -> impl Responder {
 	println!("pagination: {:?}", param.pagination.unwrap());
@@ -26,14 +39,22 @@ pub async fn get_posts_pagination(
 	// 		.await;
 	let query_result = sqlx::query_as!(
 		Post,
-		"select * from posts order by id limit $1 offset $2",
+		"with recursive tree as (
+			select uuid from categories where slug = $3
+			union all
+			select c.uuid from categories c join tree t on c.parent_id = t.uuid
+		)
+		select p.* from posts p
+		where $3::varchar is null or p.category_id in (select uuid from tree)
+		order by p.id limit $1 offset $2",
 		LIMIT,
-		offset as i32
+		offset as i32,
+		filter.category.as_deref()
 	)
 	.fetch_all(&data.db)
 	.await;
 
-	if let Err(_) = query_result {
+	if query_result.is_err() {
 		let message = "Something bad happened while fetching all note items";
 		return HttpResponse::InternalServerError()
 			.json(json!({"status": "error","message": message}));
@@ -66,7 +87,7 @@ pub async fn get_search_posts(
 .fetch_all(&data.db)
 .await;
 
-	if let Err(_) = query_result {
+	if query_result.is_err() {
 		let message = "Something bad happened while fetching all note items";
 		return HttpResponse::InternalServerError().json(
 			json!({
@@ -83,3 +104,208 @@ pub async fn get_search_posts(
 
 	// HttpResponse::Ok().body(response)
 }
+
+#[derive(Deserialize, Debug)]
+pub struct DetailQuery {
+	pub question_page: Option<i64>,
+}
+
+// 상품 상세, 답변이 달린 공개 질문을 페이지 단위로 같이 보내준다
+#[get("/{uuid}")]
+pub async fn get_post_detail(
+	path: web::Path<Uuid>,
+	query: web::Query<DetailQuery>,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let post_id = path.into_inner();
+	let question_page = query.question_page.unwrap_or(0);
+
+	let query_result = sqlx::query_as!(
+		Post,
+		"select * from posts where uuid = $1",
+		post_id
+	)
+	.fetch_optional(&data.db)
+	.await;
+
+	let post = match query_result {
+		Ok(Some(post)) => post,
+		Ok(None) => {
+			return ApiError::NotFound("Product not found")
+				.into_response()
+		}
+		Err(err) => {
+			return ApiError::Database(err).into_response()
+		}
+	};
+
+	let questions = match answered_public_questions(
+		&data.db,
+		post_id,
+		question_page,
+	)
+	.await
+	{
+		Ok(page) => page,
+		Err(err) => {
+			return ApiError::Database(err).into_response()
+		}
+	};
+
+	HttpResponse::Ok().json(json!({
+		"post": post,
+		"questions": questions.questions,
+		"question_page": questions.page,
+		"question_has_more": questions.has_more,
+	}))
+}
+
+#[post("")]
+pub async fn create_post(
+	body: web::Json<PostInput>,
+	seller: SellerUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	if let Some(message) = body.validate() {
+		return ApiError::BadRequest(message.to_string())
+			.into_response();
+	}
+
+	// category_id, brand_id는 트리거가 채워준다
+	let query_result = sqlx::query_as!(
+		Post,
+		"insert into posts (user_id, title, image_src, thumbnail_src, description, brand, category,
+			size, price, count_in_stock, sale, free_shipping, delivery_fee)
+		values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+		returning *",
+		seller.0.uuid,
+		body.title.trim(),
+		body.image_src.trim(),
+		body.thumbnail_src.as_deref(),
+		body.description,
+		body.brand.trim(),
+		body.category.trim(),
+		body.size,
+		body.price,
+		body.total_stock(),
+		body.sale.unwrap_or(0),
+		body.free_shipping.unwrap_or(false),
+		body.delivery_fee.unwrap_or(0)
+	)
+	.fetch_one(&data.db)
+	.await;
+
+	match query_result {
+		Ok(post) => HttpResponse::Created().json(post),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+// 상품을 올린 판매자나 관리자만 수정/삭제할 수 있다
+async fn check_post_owner(
+	data: &AppState,
+	post_id: Uuid,
+	seller: &SellerUser,
+) -> Result<(), ApiError> {
+	let owner_id = sqlx::query_scalar!(
+		"select user_id from posts where uuid = $1",
+		post_id
+	)
+	.fetch_optional(&data.db)
+	.await?
+	.ok_or(ApiError::NotFound("Product not found"))?;
+
+	if owner_id != seller.0.uuid && !seller.0.is_admin {
+		return Err(ApiError::Forbidden(
+			"You can only manage your own products",
+		));
+	}
+	Ok(())
+}
+
+#[put("/{uuid}")]
+pub async fn edit_post(
+	path: web::Path<Uuid>,
+	body: web::Json<PostInput>,
+	seller: SellerUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	if let Some(message) = body.validate() {
+		return ApiError::BadRequest(message.to_string())
+			.into_response();
+	}
+
+	let post_id = path.into_inner();
+	if let Err(err) =
+		check_post_owner(&data, post_id, &seller).await
+	{
+		return err.into_response();
+	}
+
+	// 카테고리나 브랜드가 바뀌면 id를 비워서 트리거가 다시 연결하게 한다
+	let query_result = sqlx::query_as!(
+		Post,
+		"update posts set title = $2, image_src = $3, thumbnail_src = $4, description = $5,
+			category_id = case when category = $7 then category_id end,
+			brand_id = case when brand = $6 then brand_id end,
+			brand = $6, category = $7, size = $8, price = $9, count_in_stock = $10,
+			sale = $11, free_shipping = $12, delivery_fee = $13, updated_at = now()
+		where uuid = $1
+		returning *",
+		post_id,
+		body.title.trim(),
+		body.image_src.trim(),
+		body.thumbnail_src.as_deref(),
+		body.description,
+		body.brand.trim(),
+		body.category.trim(),
+		body.size,
+		body.price,
+		body.total_stock(),
+		body.sale.unwrap_or(0),
+		body.free_shipping.unwrap_or(false),
+		body.delivery_fee.unwrap_or(0)
+	)
+	.fetch_one(&data.db)
+	.await;
+
+	match query_result {
+		Ok(post) => HttpResponse::Ok().json(post),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[delete("/{uuid}")]
+pub async fn delete_post(
+	path: web::Path<Uuid>,
+	seller: SellerUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let post_id = path.into_inner();
+	if let Err(err) =
+		check_post_owner(&data, post_id, &seller).await
+	{
+		return err.into_response();
+	}
+
+	let query_result = sqlx::query!(
+		"delete from posts where uuid = $1",
+		post_id
+	)
+	.execute(&data.db)
+	.await;
+
+	match query_result {
+		Ok(_) => HttpResponse::NoContent().finish(),
+		// 주문 내역이 있는 상품은 지울 수 없다
+		Err(sqlx::Error::Database(db_err))
+			if db_err.is_foreign_key_violation() =>
+		{
+			ApiError::Conflict(
+				"Product has orders and cannot be deleted",
+			)
+			.into_response()
+		}
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
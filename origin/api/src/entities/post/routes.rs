@@ -1,9 +1,16 @@
 use actix_web::web;
 
-use super::repo::{get_posts_pagination, get_search_posts};
+use super::repo::{
+	create_post, delete_post, edit_post, get_post_detail,
+	get_posts_pagination, get_search_posts,
+};
 
 pub fn post_routes() -> actix_web::Scope {
 	web::scope("")
 		.service(get_posts_pagination)
 		.service(get_search_posts)
+		.service(get_post_detail)
+		.service(create_post)
+		.service(edit_post)
+		.service(delete_post)
 }
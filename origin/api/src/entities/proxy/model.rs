@@ -0,0 +1,153 @@
+use serde::Deserialize;
+
+// 캐시가 너비별로 무한히 늘어나지 않도록 정해진 너비만 받는다
+pub const PROXY_WIDTHS: [u32; 7] =
+	[160, 320, 480, 640, 960, 1280, 1920];
+pub const MAX_REDIRECTS: usize = 3;
+
+#[derive(Deserialize, Debug)]
+pub struct ProxyQuery {
+	pub url: String,
+	pub w: Option<u32>,
+	pub format: Option<String>,
+}
+
+pub struct ProxyConfig {
+	pub allowed_hosts: Vec<String>,
+	pub cache_dir: String,
+	pub max_bytes: usize,
+	pub timeout_secs: u64,
+}
+
+impl ProxyConfig {
+	pub fn from_env() -> Self {
+		let env_or = |name: &str, default: &str| {
+			std::env::var(name)
+				.unwrap_or_else(|_| default.to_string())
+		};
+
+		ProxyConfig {
+			allowed_hosts: env_or(
+				"IMAGE_PROXY_ALLOWED_HOSTS",
+				"w3s.link",
+			)
+			.split(',')
+			.map(|host| host.trim().to_lowercase())
+			.filter(|host| !host.is_empty())
+			.collect(),
+			cache_dir: env_or(
+				"IMAGE_PROXY_CACHE_DIR",
+				"cache/images",
+			),
+			max_bytes: env_or("IMAGE_PROXY_MAX_BYTES", "")
+				.parse()
+				.unwrap_or(15 * 1024 * 1024),
+			timeout_secs: env_or("IMAGE_PROXY_TIMEOUT_SECS", "")
+				.parse()
+				.unwrap_or(10),
+		}
+	}
+}
+
+// 등록된 호스트와 그 하위 도메인만 허용한다 (w3s.link면 <cid>.ipfs.w3s.link도 허용)
+// IP 주소는 목록에 그대로 있을 때만 허용한다
+pub fn is_allowed_host(
+	allowed_hosts: &[String],
+	host: &str,
+) -> bool {
+	let host = host.to_lowercase();
+	let is_ip = host
+		.trim_start_matches('[')
+		.trim_end_matches(']')
+		.parse::<std::net::IpAddr>()
+		.is_ok();
+
+	allowed_hosts.iter().any(|allowed| {
+		host == *allowed
+			|| (!is_ip
+				&& host.ends_with(&format!(".{}", allowed)))
+	})
+}
+
+pub fn is_allowed_url(
+	allowed_hosts: &[String],
+	url: &reqwest::Url,
+) -> bool {
+	matches!(url.scheme(), "http" | "https")
+		&& url.username().is_empty()
+		&& url.password().is_none()
+		&& url.host_str().is_some_and(|host| {
+			is_allowed_host(allowed_hosts, host)
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn hosts(hosts: &[&str]) -> Vec<String> {
+		hosts.iter().map(|host| host.to_string()).collect()
+	}
+
+	#[test]
+	fn allows_listed_hosts_and_subdomains() {
+		let allowed = hosts(&["w3s.link"]);
+		assert!(is_allowed_host(&allowed, "w3s.link"));
+		assert!(is_allowed_host(
+			&allowed,
+			"bafy123.ipfs.w3s.link"
+		));
+		assert!(is_allowed_host(&allowed, "W3S.Link"));
+	}
+
+	#[test]
+	fn rejects_lookalike_hosts() {
+		let allowed = hosts(&["w3s.link"]);
+		assert!(!is_allowed_host(&allowed, "evilw3s.link"));
+		assert!(!is_allowed_host(
+			&allowed,
+			"w3s.link.evil.com"
+		));
+		assert!(!is_allowed_host(&allowed, "example.com"));
+	}
+
+	#[test]
+	fn ip_addresses_must_match_exactly() {
+		let allowed = hosts(&["10.0.0.1", "0.1"]);
+		assert!(is_allowed_host(&allowed, "10.0.0.1"));
+		// "10.0.0.1"이 ".0.1"로 끝나도 IP는 하위 도메인으로 보지 않는다
+		assert!(!is_allowed_host(&hosts(&["0.1"]), "10.0.0.1"));
+		assert!(!is_allowed_host(&allowed, "127.0.0.1"));
+		assert!(!is_allowed_host(
+			&hosts(&["w3s.link"]),
+			"[::1]"
+		));
+	}
+
+	#[test]
+	fn urls_need_http_and_no_credentials() {
+		let allowed = hosts(&["w3s.link"]);
+		let url = |url: &str| reqwest::Url::parse(url).unwrap();
+
+		assert!(is_allowed_url(
+			&allowed,
+			&url("https://a.w3s.link/x.png")
+		));
+		assert!(is_allowed_url(
+			&allowed,
+			&url("http://w3s.link/x.png")
+		));
+		assert!(!is_allowed_url(
+			&allowed,
+			&url("ftp://w3s.link/x.png")
+		));
+		assert!(!is_allowed_url(
+			&allowed,
+			&url("https://user:pw@w3s.link/x.png")
+		));
+		assert!(!is_allowed_url(
+			&allowed,
+			&url("https://w3s.link@evil.com/x.png")
+		));
+	}
+}
@@ -0,0 +1,281 @@
+use std::time::Duration;
+
+use actix_web::{
+	get, http::header, web, HttpRequest, HttpResponse,
+	Responder,
+};
+use reqwest::redirect::Policy;
+use sha2::{Digest, Sha256};
+
+use super::model::{
+	is_allowed_url, ProxyConfig, ProxyQuery, MAX_REDIRECTS,
+	PROXY_WIDTHS,
+};
+use crate::entities::upload::process::{
+	decode_image, encode_image, ImageKind, ProcessError,
+};
+use crate::error::ApiError;
+use crate::storage::{
+	backend::Storage, local::LocalStorage,
+};
+use crate::AppState;
+
+// 외부 이미지를 받아서 디스크에 캐시하고 크기/포맷별 변환본을 만든다
+pub struct ImageProxy {
+	client: reqwest::Client,
+	allowed_hosts: Vec<String>,
+	cache: LocalStorage,
+	max_bytes: usize,
+}
+
+fn proxy_error(err: impl std::fmt::Display) -> ApiError {
+	println!("🔥 image proxy failed: {}", err);
+	ApiError::BadGateway("Failed to load the remote image")
+}
+
+fn process_error(err: ProcessError) -> ApiError {
+	match err {
+		ProcessError::Unsupported => {
+			ApiError::UnsupportedMediaType(
+				"Remote file is not a JPEG, PNG or WebP image",
+			)
+		}
+		ProcessError::Invalid(message) => proxy_error(message),
+	}
+}
+
+impl ImageProxy {
+	pub fn new(config: ProxyConfig) -> Self {
+		// 리다이렉트도 허용된 호스트로만 따라간다
+		let allowed_hosts = config.allowed_hosts.clone();
+		let policy = Policy::custom(move |attempt| {
+			if attempt.previous().len() >= MAX_REDIRECTS
+				|| !is_allowed_url(&allowed_hosts, attempt.url())
+			{
+				attempt.stop()
+			} else {
+				attempt.follow()
+			}
+		});
+
+		let client = reqwest::Client::builder()
+			.timeout(Duration::from_secs(config.timeout_secs))
+			.redirect(policy)
+			.build()
+			.expect("image proxy http client");
+
+		ImageProxy {
+			client,
+			allowed_hosts: config.allowed_hosts,
+			cache: LocalStorage::new(
+				config.cache_dir,
+				String::new(),
+			),
+			max_bytes: config.max_bytes,
+		}
+	}
+
+	pub fn from_env() -> Self {
+		ImageProxy::new(ProxyConfig::from_env())
+	}
+
+	async fn download(
+		&self,
+		url: reqwest::Url,
+	) -> Result<Vec<u8>, ApiError> {
+		let mut response = self
+			.client
+			.get(url)
+			.send()
+			.await
+			.map_err(proxy_error)?;
+		if !response.status().is_success() {
+			return Err(proxy_error(response.status()));
+		}
+
+		let mut bytes = Vec::new();
+		while let Some(chunk) =
+			response.chunk().await.map_err(proxy_error)?
+		{
+			if bytes.len() + chunk.len() > self.max_bytes {
+				return Err(ApiError::PayloadTooLarge(format!(
+					"remote image must be at most {} bytes",
+					self.max_bytes
+				)));
+			}
+			bytes.extend_from_slice(&chunk);
+		}
+		Ok(bytes)
+	}
+
+	// 원본은 이미지로 읽히는지 확인한 뒤에만 캐시한다
+	async fn original(
+		&self,
+		url: reqwest::Url,
+		cache_key: &str,
+	) -> Result<Vec<u8>, ApiError> {
+		if let Some(bytes) = self
+			.cache
+			.get(cache_key)
+			.await
+			.map_err(proxy_error)?
+		{
+			return Ok(bytes);
+		}
+
+		let bytes = self.download(url).await?;
+		let bytes = web::block(move || {
+			decode_image(&bytes).map(|_| bytes)
+		})
+		.await
+		.map_err(proxy_error)?
+		.map_err(process_error)?;
+
+		self
+			.cache
+			.put(cache_key, bytes.clone(), "")
+			.await
+			.map_err(proxy_error)?;
+		Ok(bytes)
+	}
+
+	pub async fn variant(
+		&self,
+		url: &str,
+		width: Option<u32>,
+		format: Option<ImageKind>,
+		accepts_webp: bool,
+	) -> Result<(ImageKind, Vec<u8>), ApiError> {
+		let url = reqwest::Url::parse(url).map_err(|_| {
+			ApiError::BadRequest(
+				"url is not a valid URL".to_string(),
+			)
+		})?;
+		if !is_allowed_url(&self.allowed_hosts, &url) {
+			return Err(ApiError::Forbidden(
+				"This image host is not allowed",
+			));
+		}
+
+		let hash =
+			hex::encode(Sha256::digest(url.as_str().as_bytes()));
+		let original = self
+			.original(url, &format!("{}/original", hash))
+			.await?;
+
+		let original_kind = image::guess_format(&original)
+			.ok()
+			.and_then(ImageKind::from_format)
+			.ok_or(ProcessError::Unsupported)
+			.map_err(process_error)?;
+		let kind = match (format, accepts_webp) {
+			(Some(kind), _) => kind,
+			(None, true) => ImageKind::WebP,
+			(None, false) => original_kind,
+		};
+
+		let cache_key = match width {
+			Some(width) => {
+				format!("{}/w{}.{}", hash, width, kind.extension())
+			}
+			None => format!("{}/full.{}", hash, kind.extension()),
+		};
+		if let Some(bytes) = self
+			.cache
+			.get(&cache_key)
+			.await
+			.map_err(proxy_error)?
+		{
+			return Ok((kind, bytes));
+		}
+
+		// 원본보다 크게 늘리지는 않는다
+		let bytes = web::block(move || {
+			let (_, image) = decode_image(&original)?;
+			let image = match width {
+				Some(width) if width < image.width() => image
+					.resize(
+						width,
+						image.height(),
+						image::imageops::FilterType::Lanczos3,
+					),
+				_ => image,
+			};
+			encode_image(&image, kind).map_err(|err| {
+				ProcessError::Invalid(err.to_string())
+			})
+		})
+		.await
+		.map_err(proxy_error)?
+		.map_err(process_error)?;
+
+		self
+			.cache
+			.put(&cache_key, bytes.clone(), kind.content_type())
+			.await
+			.map_err(proxy_error)?;
+		Ok((kind, bytes))
+	}
+}
+
+// GET /api/image?url=...&w=320&format=webp
+// format이 없으면 Accept 헤더를 보고 webp를 고른다
+#[get("")]
+pub async fn get_proxy_image(
+	req: HttpRequest,
+	query: web::Query<ProxyQuery>,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	if let Some(width) = query.w {
+		if !PROXY_WIDTHS.contains(&width) {
+			return ApiError::BadRequest(format!(
+				"w must be one of {:?}",
+				PROXY_WIDTHS
+			))
+			.into_response();
+		}
+	}
+
+	let format = match query.format.as_deref() {
+		Some(format) => match ImageKind::from_extension(format)
+		{
+			Some(kind) => Some(kind),
+			None => {
+				return ApiError::BadRequest(
+					"format must be one of webp, jpg, png"
+						.to_string(),
+				)
+				.into_response()
+			}
+		},
+		None => None,
+	};
+
+	let accepts_webp = req
+		.headers()
+		.get(header::ACCEPT)
+		.and_then(|value| value.to_str().ok())
+		.is_some_and(|accept| accept.contains("image/webp"));
+
+	match data
+		.image_proxy
+		.variant(&query.url, query.w, format, accepts_webp)
+		.await
+	{
+		// 원본 주소가 IPFS처럼 내용이 바뀌지 않는 주소라서 오래 캐시한다
+		Ok((kind, bytes)) => {
+			let mut response = HttpResponse::Ok();
+			response
+				.content_type(kind.content_type())
+				.insert_header((
+					header::CACHE_CONTROL,
+					"public, max-age=31536000, immutable",
+				));
+			if format.is_none() {
+				response.insert_header((header::VARY, "Accept"));
+			}
+			response.body(bytes)
+		}
+		Err(err) => err.into_response(),
+	}
+}
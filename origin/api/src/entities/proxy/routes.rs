@@ -0,0 +1,7 @@
+use actix_web::web;
+
+use super::repo::get_proxy_image;
+
+pub fn proxy_routes() -> actix_web::Scope {
+	web::scope("").service(get_proxy_image)
+}
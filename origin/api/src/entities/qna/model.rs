@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const MAX_QNA_CONTENT: usize = 1000;
+pub const PRIVATE_QUESTION_TEXT: &str = "비밀글입니다.";
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct Question {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub post_id: Uuid,
+	pub user_id: Uuid,
+	pub content: String,
+	pub is_private: bool,
+	pub created_at: Option<DateTime<Utc>>,
+	pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct Answer {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub question_id: Uuid,
+	pub user_id: Uuid,
+	pub content: String,
+	pub created_at: Option<DateTime<Utc>>,
+	pub updated_at: Option<DateTime<Utc>>,
+}
+
+// 질문과 그 질문에 달린 답변 목록
+#[derive(Clone, Debug, Serialize)]
+pub struct QuestionThread {
+	#[serde(flatten)]
+	pub question: Question,
+	pub answers: Vec<Answer>,
+}
+
+// 한 페이지의 질문, 다음 페이지가 있으면 has_more가 true
+#[derive(Clone, Debug, Serialize)]
+pub struct QuestionPage {
+	pub questions: Vec<QuestionThread>,
+	pub page: i64,
+	pub has_more: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct QuestionInput {
+	pub content: String,
+	pub is_private: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AnswerInput {
+	pub content: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct QnaPageQuery {
+	pub page: Option<i64>,
+}
+
+// 비어있거나 너무 긴 질문/답변이면 에러 메시지를 돌려준다
+pub fn validate_content(content: &str) -> Option<String> {
+	let content = content.trim();
+
+	if content.is_empty() {
+		return Some("content is required".to_string());
+	}
+	if content.chars().count() > MAX_QNA_CONTENT {
+		return Some(format!(
+			"content must be at most {} characters",
+			MAX_QNA_CONTENT
+		));
+	}
+	None
+}
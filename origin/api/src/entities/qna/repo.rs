@@ -0,0 +1,441 @@
+use actix_web::{
+	delete, get, post, web, HttpResponse, Responder,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::model::{
+	validate_content, Answer, AnswerInput, QnaPageQuery,
+	Question, QuestionInput, QuestionPage, QuestionThread,
+	PRIVATE_QUESTION_TEXT,
+};
+use crate::entities::user::auth::AuthUser;
+use crate::error::ApiError;
+use crate::AppState;
+
+pub const LIMIT: i64 = 10;
+
+// 질문 목록에 답변을 붙여서 돌려준다
+async fn attach_answers(
+	pool: &PgPool,
+	questions: Vec<Question>,
+) -> Result<Vec<QuestionThread>, sqlx::Error> {
+	let question_ids: Vec<Uuid> = questions
+		.iter()
+		.map(|question| question.uuid)
+		.collect();
+
+	let answers = sqlx::query_as!(
+		Answer,
+		"select * from answers where question_id = any($1) order by id",
+		&question_ids
+	)
+	.fetch_all(pool)
+	.await?;
+
+	Ok(
+		questions
+			.into_iter()
+			.map(|question| {
+				let answers = answers
+					.iter()
+					.filter(|answer| {
+						answer.question_id == question.uuid
+					})
+					.cloned()
+					.collect();
+				QuestionThread { question, answers }
+			})
+			.collect(),
+	)
+}
+
+// 한 개 더 읽어서 다음 페이지가 있는지 본다
+fn split_page(
+	mut questions: Vec<Question>,
+) -> (Vec<Question>, bool) {
+	let has_more = questions.len() as i64 > LIMIT;
+	questions.truncate(LIMIT as usize);
+	(questions, has_more)
+}
+
+// 상품 상세에 보여줄 답변 완료된 공개 질문
+pub async fn answered_public_questions(
+	pool: &PgPool,
+	post_id: Uuid,
+	page: i64,
+) -> Result<QuestionPage, sqlx::Error> {
+	let page = page.max(0);
+	let questions = sqlx::query_as!(
+		Question,
+		"select * from questions q
+		where q.post_id = $1 and not q.is_private
+			and exists (select 1 from answers a where a.question_id = q.uuid)
+		order by q.created_at desc, q.id desc
+		limit $2 offset $3",
+		post_id,
+		LIMIT + 1,
+		page * LIMIT
+	)
+	.fetch_all(pool)
+	.await?;
+
+	let (questions, has_more) = split_page(questions);
+	Ok(QuestionPage {
+		questions: attach_answers(pool, questions).await?,
+		page,
+		has_more,
+	})
+}
+
+async fn post_seller(
+	pool: &PgPool,
+	post_id: Uuid,
+) -> Result<Uuid, ApiError> {
+	sqlx::query_scalar!(
+		"select user_id from posts where uuid = $1",
+		post_id
+	)
+	.fetch_optional(pool)
+	.await?
+	.ok_or(ApiError::NotFound("Product not found"))
+}
+
+// 비밀글은 작성자, 판매자, 관리자만 내용을 볼 수 있다
+fn mask_private(
+	mut thread: QuestionThread,
+	viewer: Option<&AuthUser>,
+	seller_id: Uuid,
+) -> QuestionThread {
+	let can_read = match viewer {
+		Some(user) => {
+			user.is_admin
+				|| user.uuid == seller_id
+				|| user.uuid == thread.question.user_id
+		}
+		None => false,
+	};
+
+	if thread.question.is_private && !can_read {
+		thread.question.content =
+			PRIVATE_QUESTION_TEXT.to_string();
+		for answer in thread.answers.iter_mut() {
+			answer.content = PRIVATE_QUESTION_TEXT.to_string();
+		}
+	}
+	thread
+}
+
+pub async fn list_questions(
+	pool: &PgPool,
+	post_id: Uuid,
+	viewer: Option<&AuthUser>,
+	page: i64,
+) -> Result<QuestionPage, ApiError> {
+	let seller_id = post_seller(pool, post_id).await?;
+	let page = page.max(0);
+
+	let questions = sqlx::query_as!(
+		Question,
+		"select * from questions where post_id = $1
+		order by created_at desc, id desc
+		limit $2 offset $3",
+		post_id,
+		LIMIT + 1,
+		page * LIMIT
+	)
+	.fetch_all(pool)
+	.await?;
+
+	let (questions, has_more) = split_page(questions);
+	let threads = attach_answers(pool, questions).await?;
+
+	let questions = threads
+		.into_iter()
+		.map(|thread| mask_private(thread, viewer, seller_id))
+		.collect();
+	Ok(QuestionPage { questions, page, has_more })
+}
+
+pub async fn insert_answer(
+	pool: &PgPool,
+	question_id: Uuid,
+	user: &AuthUser,
+	content: &str,
+) -> Result<Answer, ApiError> {
+	let seller_id = sqlx::query_scalar!(
+		"select p.user_id from questions q
+		join posts p on p.uuid = q.post_id
+		where q.uuid = $1",
+		question_id
+	)
+	.fetch_optional(pool)
+	.await?
+	.ok_or(ApiError::NotFound("Question not found"))?;
+
+	if !user.is_admin && user.uuid != seller_id {
+		return Err(ApiError::Forbidden(
+			"Only the seller or an admin can answer",
+		));
+	}
+
+	let answer = sqlx::query_as!(
+		Answer,
+		"insert into answers (question_id, user_id, content) values ($1, $2, $3)
+		returning *",
+		question_id,
+		user.uuid,
+		content
+	)
+	.fetch_one(pool)
+	.await?;
+
+	Ok(answer)
+}
+
+#[get("/post/{post_id}")]
+pub async fn get_post_questions(
+	path: web::Path<Uuid>,
+	query: web::Query<QnaPageQuery>,
+	user: Option<AuthUser>,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let page = query.page.unwrap_or(0);
+
+	match list_questions(
+		&data.db,
+		path.into_inner(),
+		user.as_ref(),
+		page,
+	)
+	.await
+	{
+		Ok(page) => HttpResponse::Ok().json(page),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[post("/post/{post_id}")]
+pub async fn create_question(
+	path: web::Path<Uuid>,
+	body: web::Json<QuestionInput>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	if let Some(message) = validate_content(&body.content) {
+		return ApiError::BadRequest(message).into_response();
+	}
+
+	let post_id = path.into_inner();
+	if let Err(err) = post_seller(&data.db, post_id).await {
+		return err.into_response();
+	}
+
+	let query_result = sqlx::query_as!(
+		Question,
+		"insert into questions (post_id, user_id, content, is_private) values ($1, $2, $3, $4)
+		returning *",
+		post_id,
+		user.uuid,
+		body.content.trim(),
+		body.is_private.unwrap_or(false)
+	)
+	.fetch_one(&data.db)
+	.await;
+
+	match query_result {
+		Ok(question) => HttpResponse::Created().json(question),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[post("/{question_id}/answer")]
+pub async fn create_answer(
+	path: web::Path<Uuid>,
+	body: web::Json<AnswerInput>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	if let Some(message) = validate_content(&body.content) {
+		return ApiError::BadRequest(message).into_response();
+	}
+
+	match insert_answer(
+		&data.db,
+		path.into_inner(),
+		&user,
+		body.content.trim(),
+	)
+	.await
+	{
+		Ok(answer) => HttpResponse::Created().json(answer),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[delete("/{question_id}")]
+pub async fn delete_question(
+	path: web::Path<Uuid>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let question_id = path.into_inner();
+
+	let query_result = sqlx::query_scalar!(
+		"select user_id from questions where uuid = $1",
+		question_id
+	)
+	.fetch_optional(&data.db)
+	.await;
+
+	match query_result {
+		Ok(Some(owner_id))
+			if owner_id == user.uuid || user.is_admin => {}
+		Ok(Some(_)) => {
+			return ApiError::Forbidden(
+				"You can only delete your own question",
+			)
+			.into_response()
+		}
+		Ok(None) => {
+			return ApiError::NotFound("Question not found")
+				.into_response()
+		}
+		Err(err) => {
+			return ApiError::Database(err).into_response()
+		}
+	}
+
+	let query_result = sqlx::query!(
+		"delete from questions where uuid = $1",
+		question_id
+	)
+	.execute(&data.db)
+	.await;
+
+	match query_result {
+		Ok(_) => HttpResponse::NoContent().finish(),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::entities::qna::model::MAX_QNA_CONTENT;
+
+	fn question(user_id: Uuid, is_private: bool) -> Question {
+		Question {
+			id: 1,
+			uuid: Uuid::new_v4(),
+			post_id: Uuid::new_v4(),
+			user_id,
+			content: "사이즈 문의".to_string(),
+			is_private,
+			created_at: None,
+			updated_at: None,
+		}
+	}
+
+	fn thread(
+		user_id: Uuid,
+		is_private: bool,
+	) -> QuestionThread {
+		let question = question(user_id, is_private);
+		let answer = Answer {
+			id: 1,
+			uuid: Uuid::new_v4(),
+			question_id: question.uuid,
+			user_id: Uuid::new_v4(),
+			content: "정사이즈입니다".to_string(),
+			created_at: None,
+			updated_at: None,
+		};
+		QuestionThread { question, answers: vec![answer] }
+	}
+
+	fn viewer(uuid: Uuid, is_admin: bool) -> AuthUser {
+		AuthUser { uuid, is_admin }
+	}
+
+	#[test]
+	fn private_question_is_masked_for_others() {
+		let (author, seller) = (Uuid::new_v4(), Uuid::new_v4());
+		let stranger = viewer(Uuid::new_v4(), false);
+
+		for viewer in [None, Some(&stranger)] {
+			let masked =
+				mask_private(thread(author, true), viewer, seller);
+			assert_eq!(
+				masked.question.content,
+				PRIVATE_QUESTION_TEXT
+			);
+			assert_eq!(
+				masked.answers[0].content,
+				PRIVATE_QUESTION_TEXT
+			);
+		}
+	}
+
+	#[test]
+	fn private_question_is_readable_by_author_seller_and_admin(
+	) {
+		let (author, seller) = (Uuid::new_v4(), Uuid::new_v4());
+		let readers = [
+			viewer(author, false),
+			viewer(seller, false),
+			viewer(Uuid::new_v4(), true),
+		];
+
+		for reader in readers.iter() {
+			let thread = mask_private(
+				thread(author, true),
+				Some(reader),
+				seller,
+			);
+			assert_eq!(thread.question.content, "사이즈 문의");
+			assert_eq!(
+				thread.answers[0].content,
+				"정사이즈입니다"
+			);
+		}
+	}
+
+	#[test]
+	fn public_question_is_not_masked() {
+		let thread = mask_private(
+			thread(Uuid::new_v4(), false),
+			None,
+			Uuid::new_v4(),
+		);
+		assert_eq!(thread.question.content, "사이즈 문의");
+	}
+
+	#[test]
+	fn has_more_is_set_only_when_extra_row_was_read() {
+		let rows = |count: i64| {
+			(0..count)
+				.map(|_| question(Uuid::new_v4(), false))
+				.collect::<Vec<_>>()
+		};
+
+		let (page, has_more) = split_page(rows(LIMIT));
+		assert_eq!(page.len() as i64, LIMIT);
+		assert!(!has_more);
+
+		let (page, has_more) = split_page(rows(LIMIT + 1));
+		assert_eq!(page.len() as i64, LIMIT);
+		assert!(has_more);
+
+		let (page, has_more) = split_page(rows(0));
+		assert!(page.is_empty() && !has_more);
+	}
+
+	#[test]
+	fn content_is_validated() {
+		assert_eq!(validate_content(" 문의 "), None);
+		assert!(validate_content("  ").is_some());
+		let long = "가".repeat(MAX_QNA_CONTENT + 1);
+		assert!(validate_content(&long).is_some());
+	}
+}
@@ -0,0 +1,14 @@
+use actix_web::web;
+
+use super::repo::{
+	create_answer, create_question, delete_question,
+	get_post_questions,
+};
+
+pub fn qna_routes() -> actix_web::Scope {
+	web::scope("")
+		.service(get_post_questions)
+		.service(create_question)
+		.service(create_answer)
+		.service(delete_question)
+}
@@ -0,0 +1,154 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+pub const MAX_REVIEW_PHOTOS: usize = 5;
+pub const MAX_REVIEW_CONTENT: usize = 2000;
+pub const MAX_REPORT_REASON: usize = 500;
+
+// reviews.status 값, visible 리뷰만 목록과 평점 집계에 포함된다
+pub const REVIEW_VISIBLE: &str = "visible";
+pub const REVIEW_PENDING: &str = "pending";
+pub const REVIEW_HIDDEN: &str = "hidden";
+
+// review_reports.status 값
+pub const REPORT_OPEN: &str = "open";
+pub const REPORT_RESOLVED: &str = "resolved";
+
+// 신고가 이 횟수 이상 쌓이면 관리자 확인 전까지 자동으로 숨긴다
+pub fn report_threshold() -> i64 {
+	parse_report_threshold(
+		std::env::var("REVIEW_REPORT_THRESHOLD")
+			.ok()
+			.as_deref(),
+	)
+}
+
+// 값이 없거나 1보다 작으면 기본값 3을 사용한다
+fn parse_report_threshold(value: Option<&str>) -> i64 {
+	value
+		.and_then(|value| value.trim().parse().ok())
+		.filter(|threshold| *threshold >= 1)
+		.unwrap_or(3)
+}
+
+// visible 리뷰만 자동으로 숨긴다, 관리자가 처리한 리뷰는 신고가 쌓여도 그대로 둔다
+pub fn should_auto_hide(
+	status: &str,
+	report_count: i64,
+	threshold: i64,
+) -> bool {
+	status == REVIEW_VISIBLE && report_count >= threshold
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct Review {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub post_id: Uuid,
+	pub user_id: Uuid,
+	pub order_item_id: Option<Uuid>,
+	pub rating: i64,
+	pub content: String,
+	pub photos: Option<Vec<String>>,
+	pub helpful_count: i64,
+	pub status: String,
+	pub report_count: i64,
+	pub created_at: Option<DateTime<Utc>>,
+	pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReviewInput {
+	pub rating: i64,
+	pub content: String,
+	pub photos: Option<Vec<String>>,
+}
+
+impl ReviewInput {
+	// 에러가 있으면 에러 메시지를 돌려준다
+	pub fn validate(&self) -> Option<&'static str> {
+		if !(1..=5).contains(&self.rating) {
+			return Some("rating must be between 1 and 5");
+		}
+		if self.content.trim().is_empty() {
+			return Some("content is required");
+		}
+		if self.content.chars().count() > MAX_REVIEW_CONTENT {
+			return Some("content is too long");
+		}
+		if let Some(photos) = &self.photos {
+			if photos.len() > MAX_REVIEW_PHOTOS {
+				return Some("too many photos");
+			}
+		}
+		None
+	}
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewSort {
+	#[default]
+	Newest,
+	Helpful,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReviewListQuery {
+	pub sort: Option<ReviewSort>,
+	pub rating: Option<i64>,
+	pub page: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ReportInput {
+	pub reason: String,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct ReviewReport {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub review_id: Uuid,
+	pub user_id: Uuid,
+	pub reason: String,
+	pub status: String,
+	pub created_at: Option<DateTime<Utc>>,
+	pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn threshold_defaults_to_three() {
+		assert_eq!(parse_report_threshold(None), 3);
+		assert_eq!(parse_report_threshold(Some("many")), 3);
+		assert_eq!(parse_report_threshold(Some("0")), 3);
+		assert_eq!(parse_report_threshold(Some(" 5 ")), 5);
+	}
+
+	#[test]
+	fn only_visible_reviews_are_auto_hidden() {
+		assert!(!should_auto_hide(REVIEW_VISIBLE, 2, 3));
+		assert!(should_auto_hide(REVIEW_VISIBLE, 3, 3));
+		assert!(!should_auto_hide(REVIEW_PENDING, 4, 3));
+		assert!(!should_auto_hide(REVIEW_HIDDEN, 4, 3));
+	}
+
+	#[test]
+	fn review_input_is_validated() {
+		let input = |rating, content: &str| ReviewInput {
+			rating,
+			content: content.to_string(),
+			photos: None,
+		};
+		assert_eq!(input(4, "good").validate(), None);
+		assert!(input(0, "good").validate().is_some());
+		assert!(input(6, "good").validate().is_some());
+		assert!(input(3, "  ").validate().is_some());
+	}
+}
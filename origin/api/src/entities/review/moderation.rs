@@ -0,0 +1,472 @@
+use actix_web::{
+	delete, get, post, web, HttpResponse, Responder,
+};
+use serde::Deserialize;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::model::{
+	report_threshold, should_auto_hide, ReportInput, Review,
+	ReviewReport, MAX_REPORT_REASON, REPORT_OPEN,
+	REPORT_RESOLVED, REVIEW_HIDDEN, REVIEW_PENDING,
+	REVIEW_VISIBLE,
+};
+use super::repo::{
+	find_review, lock_post, remove_review, sync_post_rating,
+};
+use crate::entities::user::auth::{AdminUser, AuthUser};
+use crate::error::ApiError;
+use crate::AppState;
+
+const LIMIT: i64 = 20;
+
+pub async fn add_helpful_vote(
+	pool: &PgPool,
+	review_id: Uuid,
+	user: &AuthUser,
+) -> Result<i64, ApiError> {
+	let mut tx = pool.begin().await?;
+	let review = find_review(&mut tx, review_id).await?;
+
+	if review.status != REVIEW_VISIBLE {
+		return Err(ApiError::NotFound("Review not found"));
+	}
+	if review.user_id == user.uuid {
+		return Err(ApiError::Forbidden(
+			"You cannot vote for your own review",
+		));
+	}
+
+	let inserted = sqlx::query!(
+		"insert into review_votes (review_id, user_id) values ($1, $2)
+		on conflict do nothing",
+		review_id,
+		user.uuid
+	)
+	.execute(&mut *tx)
+	.await?
+	.rows_affected();
+
+	// 이미 투표한 경우에는 helpful_count를 올리지 않는다
+	let helpful_count = sqlx::query_scalar!(
+		"update reviews set helpful_count = helpful_count + $2 where uuid = $1
+		returning helpful_count",
+		review_id,
+		inserted as i64
+	)
+	.fetch_one(&mut *tx)
+	.await?;
+
+	tx.commit().await?;
+	Ok(helpful_count)
+}
+
+pub async fn remove_helpful_vote(
+	pool: &PgPool,
+	review_id: Uuid,
+	user: &AuthUser,
+) -> Result<i64, ApiError> {
+	let mut tx = pool.begin().await?;
+	find_review(&mut tx, review_id).await?;
+
+	let deleted = sqlx::query!(
+		"delete from review_votes where review_id = $1 and user_id = $2",
+		review_id,
+		user.uuid
+	)
+	.execute(&mut *tx)
+	.await?
+	.rows_affected();
+
+	let helpful_count = sqlx::query_scalar!(
+		"update reviews set helpful_count = greatest(helpful_count - $2, 0) where uuid = $1
+		returning helpful_count",
+		review_id,
+		deleted as i64
+	)
+	.fetch_one(&mut *tx)
+	.await?;
+
+	tx.commit().await?;
+	Ok(helpful_count)
+}
+
+pub async fn insert_report(
+	pool: &PgPool,
+	review_id: Uuid,
+	user: &AuthUser,
+	reason: &str,
+) -> Result<ReviewReport, ApiError> {
+	let mut tx = pool.begin().await?;
+	let review = find_review(&mut tx, review_id).await?;
+
+	if review.user_id == user.uuid {
+		return Err(ApiError::Forbidden(
+			"You cannot report your own review",
+		));
+	}
+	// 리뷰 작성/수정과 같은 순서(posts -> reviews)로 잠가야 데드락이 생기지 않는다
+	lock_post(&mut tx, review.post_id).await?;
+
+	let report = sqlx::query_as!(
+		ReviewReport,
+		"insert into review_reports (review_id, user_id, reason) values ($1, $2, $3)
+		on conflict do nothing
+		returning *",
+		review_id,
+		user.uuid,
+		reason
+	)
+	.fetch_optional(&mut *tx)
+	.await?
+	.ok_or(ApiError::Conflict("You have already reported this review"))?;
+
+	// 위에서 읽은 review는 잠그기 전 값이다, 숨길지는 잠근 뒤의 status로 정한다
+	let reported = sqlx::query!(
+		"update reviews set report_count = report_count + 1 where uuid = $1
+		returning report_count, status",
+		review_id
+	)
+	.fetch_one(&mut *tx)
+	.await?;
+
+	if should_auto_hide(
+		&reported.status,
+		reported.report_count,
+		report_threshold(),
+	) {
+		sqlx::query!(
+			"update reviews set status = $2 where uuid = $1",
+			review_id,
+			REVIEW_PENDING
+		)
+		.execute(&mut *tx)
+		.await?;
+		sync_post_rating(&mut tx, review.post_id).await?;
+	}
+
+	tx.commit().await?;
+	Ok(report)
+}
+
+// 관리자 처리 결과에 따라 리뷰 상태를 바꾸고 열려있는 신고는 모두 처리완료로 바꾼다
+pub async fn set_review_status(
+	pool: &PgPool,
+	review_id: Uuid,
+	status: &str,
+) -> Result<Review, ApiError> {
+	let mut tx = pool.begin().await?;
+	let review = find_review(&mut tx, review_id).await?;
+	lock_post(&mut tx, review.post_id).await?;
+
+	// 승인하면 신고 누적을 초기화해서 다시 신고가 쌓여야 숨겨지도록 한다
+	let review = sqlx::query_as!(
+		Review,
+		"update reviews set status = $2,
+			report_count = case when $3 then 0 else report_count end
+		where uuid = $1
+		returning *",
+		review_id,
+		status,
+		status == REVIEW_VISIBLE
+	)
+	.fetch_one(&mut *tx)
+	.await?;
+
+	sqlx::query!(
+		"update review_reports set status = $2, updated_at = now()
+		where review_id = $1 and status = $3",
+		review_id,
+		REPORT_RESOLVED,
+		REPORT_OPEN
+	)
+	.execute(&mut *tx)
+	.await?;
+
+	sync_post_rating(&mut tx, review.post_id).await?;
+	tx.commit().await?;
+
+	Ok(review)
+}
+
+#[post("/{review_id}/helpful")]
+pub async fn vote_helpful(
+	path: web::Path<Uuid>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match add_helpful_vote(&data.db, path.into_inner(), &user)
+		.await
+	{
+		Ok(helpful_count) => HttpResponse::Ok()
+			.json(json!({"helpful_count": helpful_count})),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[delete("/{review_id}/helpful")]
+pub async fn unvote_helpful(
+	path: web::Path<Uuid>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match remove_helpful_vote(
+		&data.db,
+		path.into_inner(),
+		&user,
+	)
+	.await
+	{
+		Ok(helpful_count) => HttpResponse::Ok()
+			.json(json!({"helpful_count": helpful_count})),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[post("/{review_id}/report")]
+pub async fn report_review(
+	path: web::Path<Uuid>,
+	body: web::Json<ReportInput>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let reason = body.reason.trim();
+
+	if reason.is_empty()
+		|| reason.chars().count() > MAX_REPORT_REASON
+	{
+		return HttpResponse::BadRequest().json(json!({
+			"status": "fail",
+			"message": "reason is required and must be at most 500 characters"
+		}));
+	}
+
+	match insert_report(
+		&data.db,
+		path.into_inner(),
+		&user,
+		reason,
+	)
+	.await
+	{
+		Ok(report) => HttpResponse::Created().json(report),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[derive(Deserialize, Debug)]
+pub struct QueueQuery {
+	pub page: Option<i64>,
+}
+
+// 자동 숨김된 리뷰와 처리되지 않은 신고가 있는 리뷰를 신고가 많은 순서로 보여준다
+#[get("/admin/queue")]
+pub async fn get_moderation_queue(
+	query: web::Query<QueueQuery>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let offset = query.page.unwrap_or(0).max(0) * LIMIT;
+
+	let query_result = sqlx::query_as!(
+		Review,
+		"select * from reviews r
+		where r.status = $1
+			or exists (select 1 from review_reports rr where rr.review_id = r.uuid and rr.status = $2)
+		order by r.report_count desc, r.id
+		limit $3 offset $4",
+		REVIEW_PENDING,
+		REPORT_OPEN,
+		LIMIT,
+		offset
+	)
+	.fetch_all(&data.db)
+	.await;
+
+	let reviews = match query_result {
+		Ok(reviews) => reviews,
+		Err(err) => {
+			return ApiError::Database(err).into_response()
+		}
+	};
+
+	let review_ids: Vec<Uuid> =
+		reviews.iter().map(|review| review.uuid).collect();
+	let query_result = sqlx::query_as!(
+		ReviewReport,
+		"select * from review_reports where review_id = any($1) and status = $2 order by id",
+		&review_ids,
+		REPORT_OPEN
+	)
+	.fetch_all(&data.db)
+	.await;
+
+	let reports = match query_result {
+		Ok(reports) => reports,
+		Err(err) => {
+			return ApiError::Database(err).into_response()
+		}
+	};
+
+	let queue: Vec<_> = reviews
+		.into_iter()
+		.map(|review| {
+			let review_reports: Vec<_> = reports
+				.iter()
+				.filter(|report| report.review_id == review.uuid)
+				.collect();
+			json!({"review": review, "reports": review_reports})
+		})
+		.collect();
+
+	HttpResponse::Ok().json(queue)
+}
+
+#[post("/admin/{review_id}/approve")]
+pub async fn approve_review(
+	path: web::Path<Uuid>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match set_review_status(
+		&data.db,
+		path.into_inner(),
+		REVIEW_VISIBLE,
+	)
+	.await
+	{
+		Ok(review) => HttpResponse::Ok().json(review),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[post("/admin/{review_id}/hide")]
+pub async fn hide_review(
+	path: web::Path<Uuid>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match set_review_status(
+		&data.db,
+		path.into_inner(),
+		REVIEW_HIDDEN,
+	)
+	.await
+	{
+		Ok(review) => HttpResponse::Ok().json(review),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[delete("/admin/{review_id}")]
+pub async fn admin_delete_review(
+	path: web::Path<Uuid>,
+	admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match remove_review(&data.db, path.into_inner(), &admin.0)
+		.await
+	{
+		Ok(()) => HttpResponse::NoContent().finish(),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::{insert_post, insert_user};
+
+	fn user(uuid: Uuid) -> AuthUser {
+		AuthUser { uuid, is_admin: false }
+	}
+
+	#[sqlx::test(
+		migrator = "crate::entities::migration::repo::MIGRATOR"
+	)]
+	async fn reports_hide_review_until_approved(
+		pool: PgPool,
+	) {
+		let author = insert_user(&pool, "author").await;
+		let post_id = insert_post(&pool, author, "post").await;
+		let review_id: Uuid = sqlx::query_scalar(
+			"insert into reviews (post_id, user_id, rating, content)
+			values ($1, $2, 5, 'good') returning uuid",
+		)
+		.bind(post_id)
+		.bind(author)
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+		let mut tx = pool.begin().await.unwrap();
+		sync_post_rating(&mut tx, post_id).await.unwrap();
+		tx.commit().await.unwrap();
+
+		for name in ["a", "b", "c"] {
+			let reporter = insert_user(&pool, name).await;
+			insert_report(
+				&pool,
+				review_id,
+				&user(reporter),
+				"spam",
+			)
+			.await
+			.unwrap();
+		}
+
+		let (status, num_reviews): (String, i64) =
+			sqlx::query_as(
+				"select r.status, p.num_reviews from reviews r
+			join posts p on p.uuid = r.post_id where r.uuid = $1",
+			)
+			.bind(review_id)
+			.fetch_one(&pool)
+			.await
+			.unwrap();
+		assert_eq!(status, REVIEW_PENDING);
+		assert_eq!(num_reviews, 0);
+
+		let review =
+			set_review_status(&pool, review_id, REVIEW_VISIBLE)
+				.await
+				.unwrap();
+		assert_eq!(review.status, REVIEW_VISIBLE);
+		assert_eq!(review.report_count, 0);
+
+		let open: i64 = sqlx::query_scalar(
+			"select count(*) from review_reports where status = $1",
+		)
+		.bind(REPORT_OPEN)
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+		assert_eq!(open, 0);
+	}
+
+	#[sqlx::test(
+		migrator = "crate::entities::migration::repo::MIGRATOR"
+	)]
+	async fn own_review_cannot_be_reported(pool: PgPool) {
+		let author = insert_user(&pool, "author").await;
+		let post_id = insert_post(&pool, author, "post").await;
+		let review_id: Uuid = sqlx::query_scalar(
+			"insert into reviews (post_id, user_id, rating, content)
+			values ($1, $2, 5, 'good') returning uuid",
+		)
+		.bind(post_id)
+		.bind(author)
+		.fetch_one(&pool)
+		.await
+		.unwrap();
+
+		let result = insert_report(
+			&pool,
+			review_id,
+			&user(author),
+			"spam",
+		)
+		.await;
+		assert!(matches!(result, Err(ApiError::Forbidden(_))));
+	}
+}
@@ -0,0 +1,283 @@
+use actix_web::{
+	delete, get, post, put, web, HttpResponse, Responder,
+};
+use serde_json::json;
+use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
+use uuid::Uuid;
+
+use super::model::{
+	Review, ReviewInput, ReviewListQuery, ReviewSort,
+	REVIEW_VISIBLE,
+};
+use crate::entities::{
+	order::model::ORDER_ITEM_DELIVERED, user::auth::AuthUser,
+};
+use crate::error::{conflict_on_unique, ApiError};
+use crate::AppState;
+
+const LIMIT: i64 = 10;
+
+// 같은 상품의 리뷰가 동시에 작성/수정되어도 집계가 꼬이지 않도록 posts 행을 먼저 잠근다
+pub async fn lock_post(
+	tx: &mut Transaction<'_, Postgres>,
+	post_id: Uuid,
+) -> Result<(), ApiError> {
+	sqlx::query!(
+		"select id from posts where uuid = $1 for update",
+		post_id
+	)
+	.fetch_optional(&mut **tx)
+	.await?
+	.ok_or(ApiError::NotFound("Product not found"))?;
+	Ok(())
+}
+
+// posts.rating, posts.num_reviews를 visible 상태의 리뷰 기준으로 다시 계산한다
+pub async fn sync_post_rating(
+	tx: &mut Transaction<'_, Postgres>,
+	post_id: Uuid,
+) -> Result<(), sqlx::Error> {
+	sqlx::query!(
+		"update posts set
+			rating = coalesce((select avg(rating)::double precision from reviews where post_id = $1 and status = $2), 0),
+			num_reviews = (select count(*) from reviews where post_id = $1 and status = $2)
+		where uuid = $1",
+		post_id,
+		REVIEW_VISIBLE
+	)
+	.execute(&mut **tx)
+	.await?;
+	Ok(())
+}
+
+pub async fn find_review(
+	tx: &mut Transaction<'_, Postgres>,
+	review_id: Uuid,
+) -> Result<Review, ApiError> {
+	sqlx::query_as!(
+		Review,
+		"select * from reviews where uuid = $1",
+		review_id
+	)
+	.fetch_optional(&mut **tx)
+	.await?
+	.ok_or(ApiError::NotFound("Review not found"))
+}
+
+pub async fn insert_review(
+	pool: &PgPool,
+	post_id: Uuid,
+	user: &AuthUser,
+	input: &ReviewInput,
+) -> Result<Review, ApiError> {
+	let mut tx = pool.begin().await?;
+	lock_post(&mut tx, post_id).await?;
+
+	// 배송완료된 주문상품이 있는 구매자만 리뷰를 작성할 수 있다
+	let order_item_id = sqlx::query_scalar!(
+		"select oi.uuid from order_items oi
+		join orders o on o.uuid = oi.order_id
+		where o.user_id = $1 and oi.post_id = $2 and oi.status = $3
+		order by oi.delivered_at desc nulls last
+		limit 1",
+		user.uuid,
+		post_id,
+		ORDER_ITEM_DELIVERED
+	)
+	.fetch_optional(&mut *tx)
+	.await?
+	.ok_or(ApiError::Forbidden("Only customers who received this product can review it"))?;
+
+	let review = sqlx::query_as!(
+		Review,
+		"insert into reviews (post_id, user_id, order_item_id, rating, content, photos)
+		values ($1, $2, $3, $4, $5, $6)
+		returning *",
+		post_id,
+		user.uuid,
+		order_item_id,
+		input.rating,
+		input.content.trim(),
+		input.photos.as_deref()
+	)
+	.fetch_one(&mut *tx)
+	.await
+	.map_err(|err| {
+		conflict_on_unique(
+			err,
+			"You have already reviewed this product",
+		)
+	})?;
+
+	sync_post_rating(&mut tx, post_id).await?;
+	tx.commit().await?;
+
+	Ok(review)
+}
+
+pub async fn update_review(
+	pool: &PgPool,
+	review_id: Uuid,
+	user: &AuthUser,
+	input: &ReviewInput,
+) -> Result<Review, ApiError> {
+	let mut tx = pool.begin().await?;
+	let review = find_review(&mut tx, review_id).await?;
+
+	if review.user_id != user.uuid {
+		return Err(ApiError::Forbidden(
+			"You can only edit your own review",
+		));
+	}
+	lock_post(&mut tx, review.post_id).await?;
+
+	let review = sqlx::query_as!(
+		Review,
+		"update reviews set rating = $2, content = $3, photos = $4, updated_at = now()
+		where uuid = $1
+		returning *",
+		review_id,
+		input.rating,
+		input.content.trim(),
+		input.photos.as_deref()
+	)
+	.fetch_one(&mut *tx)
+	.await?;
+
+	sync_post_rating(&mut tx, review.post_id).await?;
+	tx.commit().await?;
+
+	Ok(review)
+}
+
+pub async fn remove_review(
+	pool: &PgPool,
+	review_id: Uuid,
+	user: &AuthUser,
+) -> Result<(), ApiError> {
+	let mut tx = pool.begin().await?;
+	let review = find_review(&mut tx, review_id).await?;
+
+	if review.user_id != user.uuid && !user.is_admin {
+		return Err(ApiError::Forbidden(
+			"You can only delete your own review",
+		));
+	}
+	lock_post(&mut tx, review.post_id).await?;
+
+	sqlx::query!(
+		"delete from reviews where uuid = $1",
+		review_id
+	)
+	.execute(&mut *tx)
+	.await?;
+
+	sync_post_rating(&mut tx, review.post_id).await?;
+	tx.commit().await?;
+
+	Ok(())
+}
+
+#[get("/post/{post_id}")]
+pub async fn get_post_reviews(
+	path: web::Path<Uuid>,
+	query: web::Query<ReviewListQuery>,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let post_id = path.into_inner();
+	let page = query.page.unwrap_or(0).max(0);
+
+	let mut builder = QueryBuilder::<Postgres>::new(
+		"select * from reviews where post_id = ",
+	);
+	builder.push_bind(post_id);
+	builder.push(" and status = ").push_bind(REVIEW_VISIBLE);
+
+	if let Some(rating) = query.rating {
+		builder.push(" and rating = ").push_bind(rating);
+	}
+
+	builder.push(match query.sort.unwrap_or_default() {
+		ReviewSort::Newest => " order by created_at desc, id desc",
+		ReviewSort::Helpful => " order by helpful_count desc, created_at desc, id desc",
+	});
+	builder
+		.push(" limit ")
+		.push_bind(LIMIT)
+		.push(" offset ")
+		.push_bind(page * LIMIT);
+
+	let query_result = builder
+		.build_query_as::<Review>()
+		.fetch_all(&data.db)
+		.await;
+
+	match query_result {
+		Ok(reviews) => HttpResponse::Ok().json(reviews),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[post("/post/{post_id}")]
+pub async fn create_review(
+	path: web::Path<Uuid>,
+	body: web::Json<ReviewInput>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	if let Some(message) = body.validate() {
+		return HttpResponse::BadRequest()
+			.json(json!({"status": "fail", "message": message}));
+	}
+
+	match insert_review(
+		&data.db,
+		path.into_inner(),
+		&user,
+		&body,
+	)
+	.await
+	{
+		Ok(review) => HttpResponse::Created().json(review),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[put("/{review_id}")]
+pub async fn edit_review(
+	path: web::Path<Uuid>,
+	body: web::Json<ReviewInput>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	if let Some(message) = body.validate() {
+		return HttpResponse::BadRequest()
+			.json(json!({"status": "fail", "message": message}));
+	}
+
+	match update_review(
+		&data.db,
+		path.into_inner(),
+		&user,
+		&body,
+	)
+	.await
+	{
+		Ok(review) => HttpResponse::Ok().json(review),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[delete("/{review_id}")]
+pub async fn delete_review(
+	path: web::Path<Uuid>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match remove_review(&data.db, path.into_inner(), &user)
+		.await
+	{
+		Ok(()) => HttpResponse::NoContent().finish(),
+		Err(err) => err.into_response(),
+	}
+}
@@ -0,0 +1,26 @@
+use actix_web::web;
+
+use super::moderation::{
+	admin_delete_review, approve_review,
+	get_moderation_queue, hide_review, report_review,
+	unvote_helpful, vote_helpful,
+};
+use super::repo::{
+	create_review, delete_review, edit_review,
+	get_post_reviews,
+};
+
+pub fn review_routes() -> actix_web::Scope {
+	web::scope("")
+		.service(get_moderation_queue)
+		.service(approve_review)
+		.service(hide_review)
+		.service(admin_delete_review)
+		.service(get_post_reviews)
+		.service(create_review)
+		.service(edit_review)
+		.service(delete_review)
+		.service(vote_helpful)
+		.service(unvote_helpful)
+		.service(report_review)
+}
@@ -0,0 +1,161 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+// sellers.status 값
+pub const SELLER_PENDING: &str = "pending";
+pub const SELLER_APPROVED: &str = "approved";
+pub const SELLER_SUSPENDED: &str = "suspended";
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct Seller {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub user_id: Uuid,
+	pub store_name: String,
+	pub business_number: Option<String>,
+	pub payout_bank: Option<String>,
+	pub payout_account: Option<String>,
+	pub payout_holder: Option<String>,
+	pub status: String,
+	pub created_at: Option<DateTime<Utc>>,
+	pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SellerInput {
+	pub store_name: String,
+	pub business_number: String,
+	pub payout_bank: String,
+	pub payout_account: String,
+	pub payout_holder: String,
+}
+
+impl SellerInput {
+	pub fn validate(&self) -> Option<&'static str> {
+		if self.store_name.trim().is_empty()
+			|| self.store_name.chars().count() > 100
+		{
+			return Some("store_name is required and must be at most 100 characters");
+		}
+		if normalize_business_number(&self.business_number)
+			.is_none()
+		{
+			return Some("business_number is not a valid business registration number");
+		}
+		if [
+			&self.payout_bank,
+			&self.payout_account,
+			&self.payout_holder,
+		]
+		.iter()
+		.any(|value| {
+			value.trim().is_empty() || value.chars().count() > 50
+		}) {
+			return Some("payout_bank, payout_account and payout_holder are required");
+		}
+		None
+	}
+}
+
+// 사업자등록번호 검증, 통과하면 "123-45-67890" 형태로 돌려준다
+pub fn normalize_business_number(
+	value: &str,
+) -> Option<String> {
+	let digits: Vec<u32> = value
+		.chars()
+		.filter(|c| *c != '-')
+		.map(|c| c.to_digit(10))
+		.collect::<Option<_>>()?;
+	if digits.len() != 10 {
+		return None;
+	}
+
+	const WEIGHTS: [u32; 9] = [1, 3, 7, 1, 3, 7, 1, 3, 5];
+	let mut sum: u32 = digits
+		.iter()
+		.zip(WEIGHTS.iter())
+		.map(|(d, w)| d * w)
+		.sum();
+	sum += digits[8] * 5 / 10;
+
+	if (10 - sum % 10) % 10 != digits[9] {
+		return None;
+	}
+
+	let digits: String = digits
+		.iter()
+		.map(|d| char::from_digit(*d, 10).unwrap_or('0'))
+		.collect();
+	Some(format!(
+		"{}-{}-{}",
+		&digits[..3],
+		&digits[3..5],
+		&digits[5..]
+	))
+}
+
+// 판매자 주문 목록, 다른 판매자의 상품은 포함하지 않는다
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct SellerOrderItem {
+	pub order_id: Uuid,
+	pub order_item_id: Uuid,
+	pub buyer_id: Uuid,
+	pub post_id: Uuid,
+	pub title: String,
+	pub size: String,
+	pub quantity: i64,
+	pub price: i64,
+	pub status: String,
+	pub delivered_at: Option<DateTime<Utc>>,
+	pub ordered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SellerPageQuery {
+	pub page: Option<i64>,
+	pub status: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn business_number_is_formatted() {
+		assert_eq!(
+			normalize_business_number("1248100998").as_deref(),
+			Some("124-81-00998")
+		);
+		assert_eq!(
+			normalize_business_number("220-81-62517").as_deref(),
+			Some("220-81-62517")
+		);
+	}
+
+	#[test]
+	fn wrong_check_digit_is_rejected() {
+		assert_eq!(
+			normalize_business_number("124-81-00999"),
+			None
+		);
+	}
+
+	#[test]
+	fn wrong_length_or_letters_are_rejected() {
+		assert_eq!(
+			normalize_business_number("124-81-0099"),
+			None
+		);
+		assert_eq!(
+			normalize_business_number("124-81-009981"),
+			None
+		);
+		assert_eq!(
+			normalize_business_number("124-8a-00998"),
+			None
+		);
+		assert_eq!(normalize_business_number(""), None);
+	}
+}
@@ -0,0 +1,250 @@
+use actix_web::{
+	get, post, put, web, HttpResponse, Responder,
+};
+use uuid::Uuid;
+
+use super::model::{
+	normalize_business_number, Seller, SellerInput,
+	SellerOrderItem, SellerPageQuery, SELLER_APPROVED,
+	SELLER_PENDING, SELLER_SUSPENDED,
+};
+use crate::entities::{
+	post::model::Post,
+	user::auth::{AdminUser, AuthUser, SellerUser},
+};
+use crate::error::{conflict_on_unique, ApiError};
+use crate::AppState;
+
+const LIMIT: i64 = 20;
+const SELLER_CONFLICT: &str =
+	"Seller profile or business number already exists";
+
+#[post("/apply")]
+pub async fn apply_seller(
+	body: web::Json<SellerInput>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	if let Some(message) = body.validate() {
+		return ApiError::BadRequest(message.to_string())
+			.into_response();
+	}
+
+	let query_result = sqlx::query_as!(
+		Seller,
+		"insert into sellers (user_id, store_name, business_number, payout_bank, payout_account, payout_holder, status)
+		values ($1, $2, $3, $4, $5, $6, $7)
+		returning *",
+		user.uuid,
+		body.store_name.trim(),
+		normalize_business_number(&body.business_number),
+		body.payout_bank.trim(),
+		body.payout_account.trim(),
+		body.payout_holder.trim(),
+		SELLER_PENDING
+	)
+	.fetch_one(&data.db)
+	.await;
+
+	match query_result {
+		Ok(seller) => HttpResponse::Created().json(seller),
+		Err(err) => conflict_on_unique(err, SELLER_CONFLICT)
+			.into_response(),
+	}
+}
+
+#[get("/me")]
+pub async fn get_my_seller(
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let query_result = sqlx::query_as!(
+		Seller,
+		"select * from sellers where user_id = $1",
+		user.uuid
+	)
+	.fetch_optional(&data.db)
+	.await;
+
+	match query_result {
+		Ok(Some(seller)) => HttpResponse::Ok().json(seller),
+		Ok(None) => {
+			ApiError::NotFound("Seller profile not found")
+				.into_response()
+		}
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+// 상점 정보와 정산 계좌만 바꾼다, 승인 상태는 관리자만 바꿀 수 있다
+#[put("/me")]
+pub async fn edit_my_seller(
+	body: web::Json<SellerInput>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	if let Some(message) = body.validate() {
+		return ApiError::BadRequest(message.to_string())
+			.into_response();
+	}
+
+	let query_result = sqlx::query_as!(
+		Seller,
+		"update sellers set store_name = $2, business_number = $3, payout_bank = $4,
+			payout_account = $5, payout_holder = $6, updated_at = now()
+		where user_id = $1
+		returning *",
+		user.uuid,
+		body.store_name.trim(),
+		normalize_business_number(&body.business_number),
+		body.payout_bank.trim(),
+		body.payout_account.trim(),
+		body.payout_holder.trim()
+	)
+	.fetch_optional(&data.db)
+	.await;
+
+	match query_result {
+		Ok(Some(seller)) => HttpResponse::Ok().json(seller),
+		Ok(None) => {
+			ApiError::NotFound("Seller profile not found")
+				.into_response()
+		}
+		Err(err) => conflict_on_unique(err, SELLER_CONFLICT)
+			.into_response(),
+	}
+}
+
+#[get("/products")]
+pub async fn get_my_products(
+	query: web::Query<SellerPageQuery>,
+	seller: SellerUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let offset = query.page.unwrap_or(0).max(0) * LIMIT;
+
+	let query_result = sqlx::query_as!(
+		Post,
+		"select * from posts where user_id = $1 order by id limit $2 offset $3",
+		seller.0.uuid,
+		LIMIT,
+		offset
+	)
+	.fetch_all(&data.db)
+	.await;
+
+	match query_result {
+		Ok(posts) => HttpResponse::Ok().json(posts),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+// 판매자의 상품이 들어있는 주문, 같은 주문의 다른 판매자 상품은 보여주지 않는다
+#[get("/orders")]
+pub async fn get_my_orders(
+	query: web::Query<SellerPageQuery>,
+	seller: SellerUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let offset = query.page.unwrap_or(0).max(0) * LIMIT;
+
+	let query_result = sqlx::query_as!(
+		SellerOrderItem,
+		"select o.uuid as order_id, oi.uuid as order_item_id, o.user_id as buyer_id,
+			p.uuid as post_id, p.title, oi.size, oi.quantity, oi.price, oi.status,
+			oi.delivered_at, o.created_at as ordered_at
+		from order_items oi
+		join orders o on o.uuid = oi.order_id
+		join posts p on p.uuid = oi.post_id
+		where p.user_id = $1 and ($2::varchar is null or oi.status = $2)
+		order by o.created_at desc, oi.id desc
+		limit $3 offset $4",
+		seller.0.uuid,
+		query.status.as_deref(),
+		LIMIT,
+		offset
+	)
+	.fetch_all(&data.db)
+	.await;
+
+	match query_result {
+		Ok(items) => HttpResponse::Ok().json(items),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[get("/admin")]
+pub async fn get_sellers(
+	query: web::Query<SellerPageQuery>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let offset = query.page.unwrap_or(0).max(0) * LIMIT;
+
+	let query_result = sqlx::query_as!(
+		Seller,
+		"select * from sellers where $1::varchar is null or status = $1
+		order by id desc limit $2 offset $3",
+		query.status.as_deref(),
+		LIMIT,
+		offset
+	)
+	.fetch_all(&data.db)
+	.await;
+
+	match query_result {
+		Ok(sellers) => HttpResponse::Ok().json(sellers),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+async fn set_seller_status(
+	data: &AppState,
+	seller_id: Uuid,
+	status: &str,
+) -> HttpResponse {
+	let query_result = sqlx::query_as!(
+		Seller,
+		"update sellers set status = $2, updated_at = now() where uuid = $1 returning *",
+		seller_id,
+		status
+	)
+	.fetch_optional(&data.db)
+	.await;
+
+	match query_result {
+		Ok(Some(seller)) => HttpResponse::Ok().json(seller),
+		Ok(None) => {
+			ApiError::NotFound("Seller not found").into_response()
+		}
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[post("/admin/{seller_id}/approve")]
+pub async fn approve_seller(
+	path: web::Path<Uuid>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	set_seller_status(
+		&data,
+		path.into_inner(),
+		SELLER_APPROVED,
+	)
+	.await
+}
+
+#[post("/admin/{seller_id}/suspend")]
+pub async fn suspend_seller(
+	path: web::Path<Uuid>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	set_seller_status(
+		&data,
+		path.into_inner(),
+		SELLER_SUSPENDED,
+	)
+	.await
+}
@@ -0,0 +1,19 @@
+use actix_web::web;
+
+use super::repo::{
+	apply_seller, approve_seller, edit_my_seller,
+	get_my_orders, get_my_products, get_my_seller,
+	get_sellers, suspend_seller,
+};
+
+pub fn seller_routes() -> actix_web::Scope {
+	web::scope("")
+		.service(apply_seller)
+		.service(get_my_seller)
+		.service(edit_my_seller)
+		.service(get_my_products)
+		.service(get_my_orders)
+		.service(get_sellers)
+		.service(approve_seller)
+		.service(suspend_seller)
+}
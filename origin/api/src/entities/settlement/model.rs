@@ -0,0 +1,117 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+// settlements.status 값
+pub const SETTLEMENT_PENDING: &str = "pending";
+pub const SETTLEMENT_PAID: &str = "paid";
+
+// settlement_lines.kind 값
+pub const LINE_SALE: &str = "sale";
+pub const LINE_REFUND: &str = "refund";
+
+// 정산 기간(period_start ~ period_end)은 이 시간대의 날짜로 나눈다
+pub const SETTLEMENT_TIME_ZONE: &str = "Asia/Seoul";
+
+// 수수료율은 basis point(1/10000) 단위, 기본 10%
+pub fn commission_rate_bps() -> i64 {
+	std::env::var("SETTLEMENT_COMMISSION_RATE_BPS")
+		.ok()
+		.and_then(|value| value.parse().ok())
+		.unwrap_or(1000)
+}
+
+// 환불 라인은 금액이 음수라서 수수료도 음수로 돌려받는다
+pub fn commission(amount: i64, rate_bps: i64) -> i64 {
+	amount * rate_bps / 10_000
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct Settlement {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub seller_id: Uuid,
+	pub period_start: NaiveDate,
+	pub period_end: NaiveDate,
+	pub gross_amount: i64,
+	pub refund_amount: i64,
+	pub commission_rate_bps: i64,
+	pub commission_amount: i64,
+	pub payout_amount: i64,
+	pub status: String,
+	pub paid_at: Option<DateTime<Utc>>,
+	pub created_at: Option<DateTime<Utc>>,
+	pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct SettlementLine {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub settlement_id: Uuid,
+	pub order_item_id: Uuid,
+	pub post_id: Uuid,
+	pub title: String,
+	pub size: String,
+	pub kind: String,
+	pub quantity: i64,
+	pub amount: i64,
+	pub commission: i64,
+	pub occurred_at: DateTime<Utc>,
+	pub created_at: Option<DateTime<Utc>>,
+}
+
+// 정산 대상이 되는 주문 상품, 아직 settlement에 묶이기 전
+#[derive(FromRow, Clone, Debug)]
+pub struct PendingLine {
+	pub seller_id: Uuid,
+	pub order_item_id: Uuid,
+	pub post_id: Uuid,
+	pub title: String,
+	pub size: String,
+	pub kind: String,
+	pub quantity: i64,
+	pub amount: i64,
+	pub occurred_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SettlementDetail {
+	#[serde(flatten)]
+	pub settlement: Settlement,
+	pub lines: Vec<SettlementLine>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SettlementRunInput {
+	pub period_start: NaiveDate,
+	pub period_end: NaiveDate,
+	pub commission_rate_bps: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SettlementListQuery {
+	pub page: Option<i64>,
+	pub status: Option<String>,
+	pub seller_id: Option<Uuid>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SettlementExportQuery {
+	pub period_start: NaiveDate,
+	pub period_end: NaiveDate,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn commission_is_taken_in_basis_points() {
+		assert_eq!(commission(50_000, 1000), 5_000);
+		assert_eq!(commission(12_345, 250), 308);
+		assert_eq!(commission(-50_000, 1000), -5_000);
+		assert_eq!(commission(50_000, 0), 0);
+	}
+}
@@ -0,0 +1,614 @@
+use std::collections::BTreeMap;
+
+use actix_web::{get, post, web, HttpResponse, Responder};
+use chrono::NaiveDate;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::model::{
+	commission, commission_rate_bps, PendingLine, Settlement,
+	SettlementDetail, SettlementExportQuery, SettlementLine,
+	SettlementListQuery, SettlementRunInput, LINE_REFUND,
+	LINE_SALE, SETTLEMENT_PAID, SETTLEMENT_PENDING,
+	SETTLEMENT_TIME_ZONE,
+};
+use crate::entities::order::model::{
+	ORDER_ITEM_DELIVERED, ORDER_ITEM_REFUNDED,
+};
+use crate::entities::user::auth::{
+	AdminUser, AuthUser, SellerUser,
+};
+use crate::error::{conflict_on_unique, ApiError};
+use crate::AppState;
+
+const LIMIT: i64 = 20;
+
+// 기간 안에 배송 완료된 주문 상품은 sale, 이미 정산된(또는 이번에 정산되는) 상품의 환불은 refund 라인이 된다
+// 환불은 period_end 이전이면 지난 기간 것도 아직 정산 안 됐으면 같이 묶는다
+// 기간의 경계는 세션 시간대와 상관없이 SETTLEMENT_TIME_ZONE 기준 자정이다
+async fn pending_lines(
+	tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+	period_start: NaiveDate,
+	period_end: NaiveDate,
+) -> Result<Vec<PendingLine>, sqlx::Error> {
+	sqlx::query_as!(
+		PendingLine,
+		r#"select p.user_id as "seller_id!", oi.uuid as "order_item_id!", p.uuid as "post_id!",
+			p.title as "title!", oi.size as "size!", $3 as "kind!", oi.quantity as "quantity!",
+			oi.price * oi.quantity as "amount!", oi.delivered_at as "occurred_at!"
+		from order_items oi
+		join posts p on p.uuid = oi.post_id
+		where oi.status in ($5, $6)
+			and oi.delivered_at >= ($1::date)::timestamp at time zone $7
+			and oi.delivered_at < ($2::date + 1)::timestamp at time zone $7
+			and not exists (
+				select 1 from settlement_lines sl where sl.order_item_id = oi.uuid and sl.kind = $3
+			)
+		union all
+		select p.user_id, oi.uuid, p.uuid, p.title, oi.size, $4, oi.quantity,
+			-(oi.price * oi.quantity), oi.refunded_at
+		from order_items oi
+		join posts p on p.uuid = oi.post_id
+		where oi.status = $6
+			and oi.refunded_at < ($2::date + 1)::timestamp at time zone $7
+			and (
+				exists (
+					select 1 from settlement_lines sl where sl.order_item_id = oi.uuid and sl.kind = $3
+				)
+				or (
+					oi.delivered_at >= ($1::date)::timestamp at time zone $7
+					and oi.delivered_at < ($2::date + 1)::timestamp at time zone $7
+				)
+			)
+			and not exists (
+				select 1 from settlement_lines sl where sl.order_item_id = oi.uuid and sl.kind = $4
+			)
+		order by 1, 9"#,
+		period_start,
+		period_end,
+		LINE_SALE,
+		LINE_REFUND,
+		ORDER_ITEM_DELIVERED,
+		ORDER_ITEM_REFUNDED,
+		SETTLEMENT_TIME_ZONE
+	)
+	.fetch_all(&mut **tx)
+	.await
+}
+
+// 판매자별로 정산서를 만들고 아직 정산 안 된 라인을 묶는다
+pub async fn run_settlement(
+	pool: &PgPool,
+	input: &SettlementRunInput,
+) -> Result<Vec<Settlement>, ApiError> {
+	let rate_bps = input
+		.commission_rate_bps
+		.unwrap_or_else(commission_rate_bps);
+	if !(0..=10_000).contains(&rate_bps) {
+		return Err(ApiError::BadRequest(
+			"commission_rate_bps must be between 0 and 10000"
+				.to_string(),
+		));
+	}
+	if input.period_start > input.period_end {
+		return Err(ApiError::BadRequest(
+			"period_start must not be after period_end"
+				.to_string(),
+		));
+	}
+
+	let mut tx = pool.begin().await?;
+
+	let mut by_seller: BTreeMap<Uuid, Vec<PendingLine>> =
+		BTreeMap::new();
+	for line in pending_lines(
+		&mut tx,
+		input.period_start,
+		input.period_end,
+	)
+	.await?
+	{
+		by_seller.entry(line.seller_id).or_default().push(line);
+	}
+
+	let mut settlements = Vec::new();
+
+	for (seller_id, lines) in by_seller {
+		// 같은 기간 정산서가 지급 전이면 새로 생긴 라인을 이어 붙이고, 지급 후면 Conflict
+		let settlement = sqlx::query_as!(
+			Settlement,
+			"insert into settlements (seller_id, period_start, period_end, commission_rate_bps, status)
+			values ($1, $2, $3, $4, $5)
+			on conflict (seller_id, period_start, period_end) do update set updated_at = now()
+			where settlements.status = $5
+			returning *",
+			seller_id,
+			input.period_start,
+			input.period_end,
+			rate_bps,
+			SETTLEMENT_PENDING
+		)
+		.fetch_optional(&mut *tx)
+		.await?
+		.ok_or(ApiError::Conflict(
+			"Settlement for this period is already paid",
+		))?;
+
+		// 이어 붙일 때는 처음 정산서의 수수료율을 그대로 쓴다
+		let rate_bps = settlement.commission_rate_bps;
+
+		for line in &lines {
+			sqlx::query!(
+				"insert into settlement_lines (settlement_id, order_item_id, post_id, title, size, kind,
+					quantity, amount, commission, occurred_at)
+				values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+				settlement.uuid,
+				line.order_item_id,
+				line.post_id,
+				line.title,
+				line.size,
+				line.kind,
+				line.quantity,
+				line.amount,
+				commission(line.amount, rate_bps),
+				line.occurred_at
+			)
+			.execute(&mut *tx)
+			.await
+			.map_err(|err| {
+				conflict_on_unique(err, "Order item is already settled")
+			})?;
+		}
+
+		let settlement = sqlx::query_as!(
+			Settlement,
+			"with totals as (
+				select coalesce(sum(amount) filter (where amount > 0), 0) as gross,
+					coalesce(-sum(amount) filter (where amount < 0), 0) as refund,
+					coalesce(sum(commission), 0) as commission
+				from settlement_lines where settlement_id = $1
+			)
+			update settlements s set gross_amount = t.gross, refund_amount = t.refund,
+				commission_amount = t.commission, payout_amount = t.gross - t.refund - t.commission,
+				updated_at = now()
+			from totals t
+			where s.uuid = $1
+			returning s.*",
+			settlement.uuid
+		)
+		.fetch_one(&mut *tx)
+		.await?;
+
+		settlements.push(settlement);
+	}
+
+	tx.commit().await?;
+	Ok(settlements)
+}
+
+// 판매자 본인이나 관리자만 정산서를 볼 수 있다
+async fn load_settlement(
+	pool: &PgPool,
+	settlement_id: Uuid,
+	user: &AuthUser,
+) -> Result<SettlementDetail, ApiError> {
+	let settlement = sqlx::query_as!(
+		Settlement,
+		"select * from settlements where uuid = $1",
+		settlement_id
+	)
+	.fetch_optional(pool)
+	.await?
+	.ok_or(ApiError::NotFound("Settlement not found"))?;
+
+	if settlement.seller_id != user.uuid && !user.is_admin {
+		return Err(ApiError::Forbidden(
+			"You can only view your own settlements",
+		));
+	}
+
+	let lines = sqlx::query_as!(
+		SettlementLine,
+		"select * from settlement_lines where settlement_id = $1 order by occurred_at, id",
+		settlement_id
+	)
+	.fetch_all(pool)
+	.await?;
+
+	Ok(SettlementDetail { settlement, lines })
+}
+
+// 쉼표, 따옴표, 줄바꿈이 있으면 따옴표로 감싼다
+fn csv_field(value: &str) -> String {
+	if value.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", value.replace('"', "\"\""))
+	} else {
+		value.to_string()
+	}
+}
+
+fn csv_row(fields: &[String]) -> String {
+	let row: Vec<String> =
+		fields.iter().map(|field| csv_field(field)).collect();
+	row.join(",") + "\r\n"
+}
+
+// 엑셀에서 한글이 깨지지 않도록 BOM을 붙인다
+fn csv_response(
+	filename: &str,
+	body: String,
+) -> HttpResponse {
+	HttpResponse::Ok()
+		.content_type("text/csv; charset=utf-8")
+		.insert_header((
+			"Content-Disposition",
+			format!("attachment; filename=\"{}\"", filename),
+		))
+		.body(format!("\u{feff}{}", body))
+}
+
+const LINE_HEADER: [&str; 13] = [
+	"settlement_id",
+	"seller_id",
+	"period_start",
+	"period_end",
+	"order_item_id",
+	"post_id",
+	"title",
+	"size",
+	"kind",
+	"quantity",
+	"amount",
+	"commission",
+	"occurred_at",
+];
+
+fn line_row(
+	settlement: &Settlement,
+	line: &SettlementLine,
+) -> String {
+	csv_row(&[
+		settlement.uuid.to_string(),
+		settlement.seller_id.to_string(),
+		settlement.period_start.to_string(),
+		settlement.period_end.to_string(),
+		line.order_item_id.to_string(),
+		line.post_id.to_string(),
+		line.title.clone(),
+		line.size.clone(),
+		line.kind.clone(),
+		line.quantity.to_string(),
+		line.amount.to_string(),
+		line.commission.to_string(),
+		line.occurred_at.to_rfc3339(),
+	])
+}
+
+fn header_row(header: &[&str]) -> String {
+	csv_row(
+		&header
+			.iter()
+			.map(|name| name.to_string())
+			.collect::<Vec<_>>(),
+	)
+}
+
+#[post("/admin/run")]
+pub async fn create_settlements(
+	body: web::Json<SettlementRunInput>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match run_settlement(&data.db, &body).await {
+		Ok(settlements) => {
+			HttpResponse::Created().json(settlements)
+		}
+		Err(err) => err.into_response(),
+	}
+}
+
+#[get("/admin")]
+pub async fn get_settlements(
+	query: web::Query<SettlementListQuery>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let offset = query.page.unwrap_or(0).max(0) * LIMIT;
+
+	let query_result = sqlx::query_as!(
+		Settlement,
+		"select * from settlements
+		where ($1::varchar is null or status = $1) and ($2::uuid is null or seller_id = $2)
+		order by period_end desc, id desc
+		limit $3 offset $4",
+		query.status.as_deref(),
+		query.seller_id,
+		LIMIT,
+		offset
+	)
+	.fetch_all(&data.db)
+	.await;
+
+	match query_result {
+		Ok(settlements) => HttpResponse::Ok().json(settlements),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[post("/admin/{settlement_id}/paid")]
+pub async fn mark_settlement_paid(
+	path: web::Path<Uuid>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let settlement_id = path.into_inner();
+
+	let query_result = sqlx::query_as!(
+		Settlement,
+		"update settlements set status = $2, paid_at = now(), updated_at = now()
+		where uuid = $1 and status = $3
+		returning *",
+		settlement_id,
+		SETTLEMENT_PAID,
+		SETTLEMENT_PENDING
+	)
+	.fetch_optional(&data.db)
+	.await;
+
+	match query_result {
+		Ok(Some(settlement)) => {
+			HttpResponse::Ok().json(settlement)
+		}
+		Ok(None) => {
+			let exists = sqlx::query_scalar!(
+				r#"select exists (select 1 from settlements where uuid = $1) as "exists!""#,
+				settlement_id
+			)
+			.fetch_one(&data.db)
+			.await;
+
+			match exists {
+				Ok(true) => {
+					ApiError::Conflict("Settlement is already paid")
+						.into_response()
+				}
+				Ok(false) => {
+					ApiError::NotFound("Settlement not found")
+						.into_response()
+				}
+				Err(err) => ApiError::Database(err).into_response(),
+			}
+		}
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+// 회계용, 기간이 겹치는 모든 정산서의 라인을 한 파일로 내려준다
+#[get("/admin/export")]
+pub async fn export_settlements(
+	query: web::Query<SettlementExportQuery>,
+	_admin: AdminUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let settlements = match sqlx::query_as!(
+		Settlement,
+		"select * from settlements where period_start <= $2 and period_end >= $1
+		order by period_start, seller_id",
+		query.period_start,
+		query.period_end
+	)
+	.fetch_all(&data.db)
+	.await
+	{
+		Ok(settlements) => settlements,
+		Err(err) => return ApiError::Database(err).into_response(),
+	};
+
+	let settlement_ids: Vec<Uuid> =
+		settlements.iter().map(|s| s.uuid).collect();
+
+	let lines = match sqlx::query_as!(
+		SettlementLine,
+		"select * from settlement_lines where settlement_id = any($1) order by occurred_at, id",
+		&settlement_ids
+	)
+	.fetch_all(&data.db)
+	.await
+	{
+		Ok(lines) => lines,
+		Err(err) => return ApiError::Database(err).into_response(),
+	};
+
+	let mut body = header_row(&LINE_HEADER);
+	for settlement in &settlements {
+		for line in lines
+			.iter()
+			.filter(|line| line.settlement_id == settlement.uuid)
+		{
+			body.push_str(&line_row(settlement, line));
+		}
+	}
+
+	csv_response(
+		&format!(
+			"settlements_{}_{}.csv",
+			query.period_start, query.period_end
+		),
+		body,
+	)
+}
+
+#[get("/me")]
+pub async fn get_my_settlements(
+	query: web::Query<SettlementListQuery>,
+	seller: SellerUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let offset = query.page.unwrap_or(0).max(0) * LIMIT;
+
+	let query_result = sqlx::query_as!(
+		Settlement,
+		"select * from settlements
+		where seller_id = $1 and ($2::varchar is null or status = $2)
+		order by period_end desc, id desc
+		limit $3 offset $4",
+		seller.0.uuid,
+		query.status.as_deref(),
+		LIMIT,
+		offset
+	)
+	.fetch_all(&data.db)
+	.await;
+
+	match query_result {
+		Ok(settlements) => HttpResponse::Ok().json(settlements),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[get("/{settlement_id}")]
+pub async fn get_settlement(
+	path: web::Path<Uuid>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	match load_settlement(&data.db, path.into_inner(), &user)
+		.await
+	{
+		Ok(detail) => HttpResponse::Ok().json(detail),
+		Err(err) => err.into_response(),
+	}
+}
+
+#[get("/{settlement_id}/csv")]
+pub async fn get_settlement_csv(
+	path: web::Path<Uuid>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let detail = match load_settlement(
+		&data.db,
+		path.into_inner(),
+		&user,
+	)
+	.await
+	{
+		Ok(detail) => detail,
+		Err(err) => return err.into_response(),
+	};
+
+	let mut body = header_row(&LINE_HEADER);
+	for line in &detail.lines {
+		body.push_str(&line_row(&detail.settlement, line));
+	}
+	// 합계 행
+	body.push_str(&csv_row(&[
+		"gross_amount".to_string(),
+		detail.settlement.gross_amount.to_string(),
+		"refund_amount".to_string(),
+		detail.settlement.refund_amount.to_string(),
+		"commission_amount".to_string(),
+		detail.settlement.commission_amount.to_string(),
+		"payout_amount".to_string(),
+		detail.settlement.payout_amount.to_string(),
+	]));
+
+	csv_response(
+		&format!("settlement_{}.csv", detail.settlement.uuid),
+		body,
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::{insert_post, insert_user};
+
+	#[test]
+	fn csv_fields_are_quoted_when_needed() {
+		assert_eq!(csv_field("셔츠"), "셔츠");
+		assert_eq!(csv_field("a,b"), "\"a,b\"");
+		assert_eq!(csv_field("5\" 인치"), "\"5\"\" 인치\"");
+		assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+	}
+
+	#[test]
+	fn csv_row_ends_with_crlf() {
+		let row =
+			csv_row(&["a".to_string(), "b,c".to_string()]);
+		assert_eq!(row, "a,\"b,c\"\r\n");
+	}
+
+	async fn insert_delivered_item(
+		pool: &PgPool,
+		post_id: Uuid,
+		buyer: Uuid,
+		delivered_at: &str,
+	) -> Uuid {
+		let order_id: Uuid = sqlx::query_scalar(
+			"insert into orders (user_id) values ($1) returning uuid",
+		)
+		.bind(buyer)
+		.fetch_one(pool)
+		.await
+		.unwrap();
+
+		sqlx::query_scalar(
+			"insert into order_items (order_id, post_id, size, price, status, delivered_at)
+			values ($1, $2, '260', 10000, $3, $4::timestamptz) returning uuid",
+		)
+		.bind(order_id)
+		.bind(post_id)
+		.bind(ORDER_ITEM_DELIVERED)
+		.bind(delivered_at)
+		.fetch_one(pool)
+		.await
+		.unwrap()
+	}
+
+	#[sqlx::test(
+		migrator = "crate::entities::migration::repo::MIGRATOR"
+	)]
+	async fn period_is_split_at_seoul_midnight(pool: PgPool) {
+		let seller = insert_user(&pool, "seller").await;
+		let buyer = insert_user(&pool, "buyer").await;
+		let post_id = insert_post(&pool, seller, "shoes").await;
+
+		let last_day = insert_delivered_item(
+			&pool,
+			post_id,
+			buyer,
+			"2024-01-31 23:30:00+09",
+		)
+		.await;
+		insert_delivered_item(
+			&pool,
+			post_id,
+			buyer,
+			"2024-02-01 00:30:00+09",
+		)
+		.await;
+
+		// 세션 시간대가 달라도 결과가 같아야 한다
+		let mut tx = pool.begin().await.unwrap();
+		sqlx::query(
+			"set local time zone 'America/Los_Angeles'",
+		)
+		.execute(&mut *tx)
+		.await
+		.unwrap();
+		let lines = pending_lines(
+			&mut tx,
+			NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+			NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+		)
+		.await
+		.unwrap();
+
+		let items: Vec<Uuid> =
+			lines.iter().map(|line| line.order_item_id).collect();
+		assert_eq!(items, [last_day]);
+		assert_eq!(lines[0].kind, LINE_SALE);
+		assert_eq!(lines[0].amount, 10_000);
+	}
+}
@@ -0,0 +1,18 @@
+use actix_web::web;
+
+use super::repo::{
+	create_settlements, export_settlements,
+	get_my_settlements, get_settlement, get_settlement_csv,
+	get_settlements, mark_settlement_paid,
+};
+
+pub fn settlement_routes() -> actix_web::Scope {
+	web::scope("")
+		.service(create_settlements)
+		.service(get_settlements)
+		.service(mark_settlement_paid)
+		.service(export_settlements)
+		.service(get_my_settlements)
+		.service(get_settlement)
+		.service(get_settlement_csv)
+}
@@ -0,0 +1,31 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+// 원본보다 작은 너비만 만든다
+pub const THUMBNAIL_WIDTHS: [u32; 2] = [320, 640];
+pub const MAX_IMAGE_DIMENSION: u32 = 8000;
+pub const MAX_UPLOAD_FILES: usize = 10;
+
+pub fn max_upload_bytes() -> usize {
+	std::env::var("UPLOAD_MAX_BYTES")
+		.ok()
+		.and_then(|value| value.parse().ok())
+		.unwrap_or(10 * 1024 * 1024)
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ImageVariant {
+	pub width: u32,
+	pub height: u32,
+	pub format: &'static str,
+	pub url: String,
+}
+
+// image_src, thumbnail_src는 원본 포맷, webp 주소는 variants에 있다
+#[derive(Clone, Debug, Serialize)]
+pub struct UploadedImage {
+	pub uuid: Uuid,
+	pub image_src: String,
+	pub thumbnail_src: Vec<String>,
+	pub variants: Vec<ImageVariant>,
+}
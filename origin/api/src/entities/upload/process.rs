@@ -0,0 +1,201 @@
+use std::io::Cursor;
+
+use image::{
+	codecs::{jpeg::JpegEncoder, webp::WebPEncoder},
+	imageops::FilterType,
+	metadata::Orientation,
+	DynamicImage, ImageDecoder, ImageFormat, ImageReader,
+	Limits,
+};
+
+use super::model::{MAX_IMAGE_DIMENSION, THUMBNAIL_WIDTHS};
+
+const JPEG_QUALITY: u8 = 85;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageKind {
+	Jpeg,
+	Png,
+	WebP,
+}
+
+impl ImageKind {
+	pub fn from_format(format: ImageFormat) -> Option<Self> {
+		match format {
+			ImageFormat::Jpeg => Some(ImageKind::Jpeg),
+			ImageFormat::Png => Some(ImageKind::Png),
+			ImageFormat::WebP => Some(ImageKind::WebP),
+			_ => None,
+		}
+	}
+
+	pub fn from_extension(ext: &str) -> Option<Self> {
+		match ext {
+			"jpg" | "jpeg" => Some(ImageKind::Jpeg),
+			"png" => Some(ImageKind::Png),
+			"webp" => Some(ImageKind::WebP),
+			_ => None,
+		}
+	}
+
+	pub fn extension(self) -> &'static str {
+		match self {
+			ImageKind::Jpeg => "jpg",
+			ImageKind::Png => "png",
+			ImageKind::WebP => "webp",
+		}
+	}
+
+	pub fn content_type(self) -> &'static str {
+		match self {
+			ImageKind::Jpeg => "image/jpeg",
+			ImageKind::Png => "image/png",
+			ImageKind::WebP => "image/webp",
+		}
+	}
+}
+
+pub struct EncodedImage {
+	// 저장할 파일 이름, "original.jpg", "w320.webp"
+	pub name: String,
+	pub kind: ImageKind,
+	pub width: u32,
+	pub height: u32,
+	pub bytes: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum ProcessError {
+	Unsupported,
+	Invalid(String),
+}
+
+// 파일 내용으로 포맷을 판단하고, EXIF 회전을 적용한 뒤 디코딩한다
+pub fn decode_image(
+	bytes: &[u8],
+) -> Result<(ImageKind, DynamicImage), ProcessError> {
+	let mut reader = ImageReader::new(Cursor::new(bytes))
+		.with_guessed_format()
+		.map_err(|err| {
+			ProcessError::Invalid(err.to_string())
+		})?;
+
+	let kind = reader
+		.format()
+		.and_then(ImageKind::from_format)
+		.ok_or(ProcessError::Unsupported)?;
+
+	let mut limits = Limits::default();
+	limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+	limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+	reader.limits(limits);
+
+	let invalid = |err: image::ImageError| {
+		ProcessError::Invalid(err.to_string())
+	};
+	let mut decoder =
+		reader.into_decoder().map_err(invalid)?;
+	let orientation = decoder
+		.orientation()
+		.unwrap_or(Orientation::NoTransforms);
+	let mut image =
+		DynamicImage::from_decoder(decoder).map_err(invalid)?;
+	image.apply_orientation(orientation);
+
+	Ok((kind, image))
+}
+
+// 픽셀만 다시 인코딩하므로 EXIF 같은 메타데이터는 남지 않는다
+pub fn encode_image(
+	image: &DynamicImage,
+	kind: ImageKind,
+) -> Result<Vec<u8>, image::ImageError> {
+	let mut bytes = Vec::new();
+
+	match kind {
+		ImageKind::Jpeg => {
+			let encoder = JpegEncoder::new_with_quality(
+				&mut bytes,
+				JPEG_QUALITY,
+			);
+			DynamicImage::ImageRgb8(image.to_rgb8())
+				.write_with_encoder(encoder)?;
+		}
+		ImageKind::Png => {
+			image.write_to(
+				&mut Cursor::new(&mut bytes),
+				ImageFormat::Png,
+			)?;
+		}
+		// image 크레이트는 무손실 webp만 인코딩한다
+		ImageKind::WebP => {
+			let encoder = WebPEncoder::new_lossless(&mut bytes);
+			if image.color().has_alpha() {
+				DynamicImage::ImageRgba8(image.to_rgba8())
+					.write_with_encoder(encoder)?;
+			} else {
+				DynamicImage::ImageRgb8(image.to_rgb8())
+					.write_with_encoder(encoder)?;
+			}
+		}
+	}
+
+	Ok(bytes)
+}
+
+fn encode_pair(
+	image: &DynamicImage,
+	kind: ImageKind,
+	prefix: &str,
+	images: &mut Vec<EncodedImage>,
+) -> Result<(), image::ImageError> {
+	let mut kinds = vec![kind];
+	if kind != ImageKind::WebP {
+		kinds.push(ImageKind::WebP);
+	}
+
+	for kind in kinds {
+		images.push(EncodedImage {
+			name: format!("{}.{}", prefix, kind.extension()),
+			kind,
+			width: image.width(),
+			height: image.height(),
+			bytes: encode_image(image, kind)?,
+		});
+	}
+	Ok(())
+}
+
+// 원본과 썸네일을 원본 포맷 + webp로 만든다, 첫번째가 원본
+pub fn process_image(
+	bytes: &[u8],
+) -> Result<Vec<EncodedImage>, ProcessError> {
+	let (kind, image) = decode_image(bytes)?;
+	let invalid = |err: image::ImageError| {
+		ProcessError::Invalid(err.to_string())
+	};
+
+	let mut images = Vec::new();
+	encode_pair(&image, kind, "original", &mut images)
+		.map_err(invalid)?;
+
+	for width in THUMBNAIL_WIDTHS {
+		if width >= image.width() {
+			continue;
+		}
+		let thumbnail = image.resize(
+			width,
+			image.height(),
+			FilterType::Lanczos3,
+		);
+		encode_pair(
+			&thumbnail,
+			kind,
+			&format!("w{}", width),
+			&mut images,
+		)
+		.map_err(invalid)?;
+	}
+
+	Ok(images)
+}
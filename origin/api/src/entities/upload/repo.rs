@@ -0,0 +1,218 @@
+use actix_multipart::{Field, Multipart};
+use actix_web::{get, post, web, HttpResponse, Responder};
+use futures_util::TryStreamExt;
+use serde_json::json;
+use uuid::Uuid;
+
+use super::model::{
+	max_upload_bytes, ImageVariant, UploadedImage,
+	MAX_UPLOAD_FILES,
+};
+use super::process::{
+	process_image, ImageKind, ProcessError,
+};
+use crate::entities::user::auth::SellerUser;
+use crate::error::ApiError;
+use crate::storage::backend::Storage;
+use crate::AppState;
+
+fn storage_error(
+	err: impl std::fmt::Display,
+) -> HttpResponse {
+	println!("🔥 image upload failed: {}", err);
+	HttpResponse::InternalServerError().json(json!({
+		"status": "error",
+		"message": "Something bad happened while storing the image"
+	}))
+}
+
+// 제한을 넘으면 끝까지 읽지 않고 바로 멈춘다
+async fn read_field(
+	field: &mut Field,
+	limit: usize,
+) -> Result<Vec<u8>, ApiError> {
+	let mut bytes = Vec::new();
+
+	while let Some(chunk) = field
+		.try_next()
+		.await
+		.map_err(|err| ApiError::BadRequest(err.to_string()))?
+	{
+		if bytes.len() + chunk.len() > limit {
+			return Err(ApiError::PayloadTooLarge(format!(
+				"image must be at most {} bytes",
+				limit
+			)));
+		}
+		bytes.extend_from_slice(&chunk);
+	}
+
+	Ok(bytes)
+}
+
+// 저장한 key는 stored에 쌓는다, 실패하면 upload_images가 요청 전체의 파일을 지운다
+async fn store_image(
+	storage: &dyn Storage,
+	bytes: Vec<u8>,
+	stored: &mut Vec<String>,
+) -> Result<UploadedImage, HttpResponse> {
+	let images = web::block(move || process_image(&bytes))
+		.await
+		.map_err(storage_error)?
+		.map_err(|err| match err {
+			ProcessError::Unsupported => {
+				ApiError::UnsupportedMediaType(
+					"Only JPEG, PNG and WebP images are allowed",
+				)
+				.into_response()
+			}
+			ProcessError::Invalid(message) => {
+				ApiError::BadRequest(format!(
+					"invalid image: {}",
+					message
+				))
+				.into_response()
+			}
+		})?;
+
+	let uuid = Uuid::new_v4();
+	let mut variants = Vec::new();
+
+	for image in images {
+		let key = format!("{}/{}", uuid, image.name);
+
+		storage
+			.put(&key, image.bytes, image.kind.content_type())
+			.await
+			.map_err(storage_error)?;
+
+		variants.push(ImageVariant {
+			width: image.width,
+			height: image.height,
+			format: image.kind.extension(),
+			url: storage.url(&key),
+		});
+		stored.push(key);
+	}
+
+	// 첫번째는 원본, 그 뒤로 원본 포맷 썸네일과 webp가 번갈아 있다
+	let original_format = variants[0].format;
+	let image_src = variants[0].url.clone();
+	let thumbnail_src = variants
+		.iter()
+		.skip(1)
+		.filter(|variant| variant.format == original_format)
+		.map(|variant| variant.url.clone())
+		.collect();
+
+	Ok(UploadedImage {
+		uuid,
+		image_src,
+		thumbnail_src,
+		variants,
+	})
+}
+
+async fn receive_images(
+	payload: &mut Multipart,
+	storage: &dyn Storage,
+	stored: &mut Vec<String>,
+) -> Result<Vec<UploadedImage>, HttpResponse> {
+	let limit = max_upload_bytes();
+	let mut uploaded = Vec::new();
+
+	while let Some(mut field) =
+		payload.try_next().await.map_err(|err| {
+			ApiError::BadRequest(err.to_string()).into_response()
+		})? {
+		// 파일이 아닌 필드는 건너뛴다
+		let is_file = field
+			.content_disposition()
+			.and_then(|disposition| disposition.get_filename())
+			.is_some();
+		if !is_file {
+			continue;
+		}
+
+		if uploaded.len() >= MAX_UPLOAD_FILES {
+			return Err(
+				ApiError::BadRequest(format!(
+					"at most {} images can be uploaded at once",
+					MAX_UPLOAD_FILES
+				))
+				.into_response(),
+			);
+		}
+
+		let bytes = read_field(&mut field, limit)
+			.await
+			.map_err(ApiError::into_response)?;
+		uploaded
+			.push(store_image(storage, bytes, stored).await?);
+	}
+
+	if uploaded.is_empty() {
+		return Err(
+			ApiError::BadRequest(
+				"no image file in request".to_string(),
+			)
+			.into_response(),
+		);
+	}
+	Ok(uploaded)
+}
+
+// multipart의 파일 필드마다 원본, 썸네일, webp를 만들어 저장한다
+// 중간에 하나라도 실패하면 앞에서 저장한 파일까지 모두 지운다
+#[post("")]
+pub async fn upload_images(
+	mut payload: Multipart,
+	_seller: SellerUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let storage = data.storage.as_ref();
+	let mut stored = Vec::new();
+
+	match receive_images(&mut payload, storage, &mut stored)
+		.await
+	{
+		Ok(uploaded) => HttpResponse::Created().json(uploaded),
+		Err(response) => {
+			for key in &stored {
+				let _ = storage.delete(key).await;
+			}
+			response
+		}
+	}
+}
+
+// 로컬 저장소의 파일을 내려준다, key에 uuid가 들어가서 내용이 바뀌지 않는다
+#[get("/files/{key:.*}")]
+pub async fn get_upload_file(
+	path: web::Path<String>,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let key = path.into_inner();
+
+	let Some(kind) = key
+		.rsplit_once('.')
+		.and_then(|(_, ext)| ImageKind::from_extension(ext))
+	else {
+		return ApiError::NotFound("File not found")
+			.into_response();
+	};
+
+	match data.storage.get(&key).await {
+		Ok(Some(bytes)) => HttpResponse::Ok()
+			.content_type(kind.content_type())
+			.insert_header((
+				"Cache-Control",
+				"public, max-age=31536000, immutable",
+			))
+			.body(bytes),
+		Ok(None) => {
+			ApiError::NotFound("File not found").into_response()
+		}
+		Err(err) => storage_error(err),
+	}
+}
@@ -0,0 +1,9 @@
+use actix_web::web;
+
+use super::repo::{get_upload_file, upload_images};
+
+pub fn upload_routes() -> actix_web::Scope {
+	web::scope("")
+		.service(upload_images)
+		.service(get_upload_file)
+}
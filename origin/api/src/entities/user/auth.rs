@@ -0,0 +1,277 @@
+use std::{future::Future, pin::Pin};
+
+use actix_web::{
+	dev::Payload, error::InternalError, http::header, web,
+	FromRequest, HttpRequest, HttpResponse,
+};
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+use uuid::Uuid;
+
+use crate::entities::seller::model::SELLER_APPROVED;
+use crate::AppState;
+
+// 로그인 처리는 gateway에서 하고, api에는 AUTH_SECRET으로 서명한 토큰을
+// "Authorization: Bearer {uuid}.{만료 unix 초}.{hex hmac-sha256}" 으로 넘겨준다
+pub const AUTH_SECRET_ENV: &str = "AUTH_SECRET";
+
+fn token_mac(secret: &[u8], payload: &str) -> Hmac<Sha256> {
+	let mut mac = Hmac::<Sha256>::new_from_slice(secret)
+		.expect("hmac accepts keys of any length");
+	mac.update(payload.as_bytes());
+	mac
+}
+
+pub fn sign_token(
+	secret: &[u8],
+	uuid: Uuid,
+	expires: i64,
+) -> String {
+	let payload = format!("{}.{}", uuid, expires);
+	let signature =
+		token_mac(secret, &payload).finalize().into_bytes();
+	format!("{}.{}", payload, hex::encode(signature))
+}
+
+// 서명이 맞고 만료되지 않은 토큰이면 사용자 uuid를 돌려준다
+pub fn verify_token(
+	secret: &[u8],
+	token: &str,
+	now: i64,
+) -> Result<Uuid, &'static str> {
+	let Some((payload, signature)) = token.rsplit_once('.')
+	else {
+		return Err("Invalid token");
+	};
+	let signature =
+		hex::decode(signature).map_err(|_| "Invalid token")?;
+	// verify_slice는 상수 시간으로 비교한다
+	token_mac(secret, payload)
+		.verify_slice(&signature)
+		.map_err(|_| "Invalid token")?;
+
+	let Some((uuid, expires)) = payload.split_once('.')
+	else {
+		return Err("Invalid token");
+	};
+	let uuid =
+		Uuid::parse_str(uuid).map_err(|_| "Invalid token")?;
+	let expires: i64 =
+		expires.parse().map_err(|_| "Invalid token")?;
+	if expires < now {
+		return Err("Token expired");
+	}
+	Ok(uuid)
+}
+
+fn bearer_user(
+	req: &HttpRequest,
+) -> Result<Uuid, &'static str> {
+	let Some(token) = req
+		.headers()
+		.get(header::AUTHORIZATION)
+		.and_then(|value| value.to_str().ok())
+		.and_then(|value| value.strip_prefix("Bearer "))
+	else {
+		return Err("Login required");
+	};
+	// 비밀 키가 없으면 누구도 인증하지 않는다
+	let secret = std::env::var(AUTH_SECRET_ENV)
+		.ok()
+		.filter(|secret| !secret.is_empty())
+		.ok_or("Authentication is not configured")?;
+
+	verify_token(
+		secret.as_bytes(),
+		token.trim(),
+		chrono::Utc::now().timestamp(),
+	)
+}
+
+#[derive(Clone, Debug)]
+pub struct AuthUser {
+	pub uuid: Uuid,
+	pub is_admin: bool,
+}
+
+fn unauthorized(message: &str) -> actix_web::Error {
+	InternalError::from_response(
+		message.to_string(),
+		HttpResponse::Unauthorized()
+			.json(json!({"status": "fail", "message": message})),
+	)
+	.into()
+}
+
+impl FromRequest for AuthUser {
+	type Error = actix_web::Error;
+	type Future = Pin<
+		Box<dyn Future<Output = Result<Self, Self::Error>>>,
+	>;
+
+	fn from_request(
+		req: &HttpRequest,
+		_payload: &mut Payload,
+	) -> Self::Future {
+		let data =
+			req.app_data::<web::Data<AppState>>().cloned();
+		let user_id = bearer_user(req);
+
+		Box::pin(async move {
+			let user_id = user_id.map_err(unauthorized)?;
+			let Some(data) = data else {
+				return Err(unauthorized("Login required"));
+			};
+
+			let query_result = sqlx::query_as!(
+				AuthUser,
+				"select uuid, is_admin from users where uuid = $1",
+				user_id
+			)
+			.fetch_optional(&data.db)
+			.await;
+
+			match query_result {
+				Ok(Some(user)) => Ok(user),
+				Ok(None) => Err(unauthorized("User not found")),
+				Err(_) => Err(unauthorized("Failed to load user")),
+			}
+		})
+	}
+}
+
+// 관리자만 접근할 수 있는 핸들러에서 사용한다
+#[derive(Clone, Debug)]
+pub struct AdminUser(pub AuthUser);
+
+impl FromRequest for AdminUser {
+	type Error = actix_web::Error;
+	type Future = Pin<
+		Box<dyn Future<Output = Result<Self, Self::Error>>>,
+	>;
+
+	fn from_request(
+		req: &HttpRequest,
+		payload: &mut Payload,
+	) -> Self::Future {
+		let auth_user = AuthUser::from_request(req, payload);
+
+		Box::pin(async move {
+			let user = auth_user.await?;
+
+			if !user.is_admin {
+				return Err(
+					InternalError::from_response(
+						"admin only",
+						HttpResponse::Forbidden().json(json!({
+							"status": "fail",
+							"message": "Admin only"
+						})),
+					)
+					.into(),
+				);
+			}
+			Ok(AdminUser(user))
+		})
+	}
+}
+
+// 승인된 판매자 또는 관리자만 접근할 수 있는 핸들러에서 사용한다
+#[derive(Clone, Debug)]
+pub struct SellerUser(pub AuthUser);
+
+impl FromRequest for SellerUser {
+	type Error = actix_web::Error;
+	type Future = Pin<
+		Box<dyn Future<Output = Result<Self, Self::Error>>>,
+	>;
+
+	fn from_request(
+		req: &HttpRequest,
+		payload: &mut Payload,
+	) -> Self::Future {
+		let data =
+			req.app_data::<web::Data<AppState>>().cloned();
+		let auth_user = AuthUser::from_request(req, payload);
+
+		Box::pin(async move {
+			let user = auth_user.await?;
+			if user.is_admin {
+				return Ok(SellerUser(user));
+			}
+
+			let Some(data) = data else {
+				return Err(unauthorized("Login required"));
+			};
+
+			let query_result = sqlx::query_scalar!(
+				"select exists (select 1 from sellers where user_id = $1 and status = $2)",
+				user.uuid,
+				SELLER_APPROVED
+			)
+			.fetch_one(&data.db)
+			.await;
+
+			match query_result {
+				Ok(Some(true)) => Ok(SellerUser(user)),
+				Ok(_) => Err(
+					InternalError::from_response(
+						"seller only",
+						HttpResponse::Forbidden().json(json!({
+							"status": "fail",
+							"message": "Approved sellers only"
+						})),
+					)
+					.into(),
+				),
+				Err(_) => {
+					Err(unauthorized("Failed to load seller"))
+				}
+			}
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const SECRET: &[u8] = b"test-secret";
+
+	#[test]
+	fn signed_token_verifies() {
+		let uuid = Uuid::new_v4();
+		let token = sign_token(SECRET, uuid, 1_000);
+		assert_eq!(verify_token(SECRET, &token, 999), Ok(uuid));
+	}
+
+	#[test]
+	fn expired_token_is_rejected() {
+		let token = sign_token(SECRET, Uuid::new_v4(), 1_000);
+		assert_eq!(
+			verify_token(SECRET, &token, 1_001),
+			Err("Token expired")
+		);
+	}
+
+	#[test]
+	fn other_secret_is_rejected() {
+		let token = sign_token(b"other", Uuid::new_v4(), 1_000);
+		assert!(verify_token(SECRET, &token, 0).is_err());
+	}
+
+	#[test]
+	fn swapped_uuid_is_rejected() {
+		let token = sign_token(SECRET, Uuid::new_v4(), 1_000);
+		let (_, rest) = token.split_once('.').unwrap();
+		let forged = format!("{}.{}", Uuid::new_v4(), rest);
+		assert!(verify_token(SECRET, &forged, 0).is_err());
+	}
+
+	#[test]
+	fn bare_uuid_is_rejected() {
+		let uuid = Uuid::new_v4().to_string();
+		assert!(verify_token(SECRET, &uuid, 0).is_err());
+	}
+}
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct Wishlist {
+	pub id: i32,
+	pub uuid: Uuid,
+	pub user_id: Uuid,
+	pub post_id: Uuid,
+	pub size: Option<String>,
+	pub created_at: Option<DateTime<Utc>>,
+	pub updated_at: Option<DateTime<Utc>>,
+}
+
+// 찜 목록 화면에 보여줄 상품 요약
+#[derive(FromRow, Clone, Debug, Serialize, Deserialize)]
+pub struct WishlistItem {
+	pub uuid: Uuid,
+	pub post_id: Uuid,
+	pub size: Option<String>,
+	pub title: String,
+	pub image_src: String,
+	pub price: i64,
+	pub sale: i64,
+	pub count_in_stock: i64,
+	pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct WishlistInput {
+	pub size: Option<String>,
+}
@@ -0,0 +1,159 @@
+use actix_web::{
+	delete, get, post, web, HttpResponse, Responder,
+};
+use uuid::Uuid;
+
+use super::model::{Wishlist, WishlistInput, WishlistItem};
+use crate::entities::user::auth::AuthUser;
+use crate::error::ApiError;
+use crate::AppState;
+
+#[get("")]
+pub async fn get_wishlist(
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let query_result = sqlx::query_as!(
+		WishlistItem,
+		"select w.uuid, w.post_id, w.size, p.title, p.image_src, p.price, p.sale, p.count_in_stock, w.created_at
+		from wishlists w
+		join posts p on p.uuid = w.post_id
+		where w.user_id = $1
+		order by w.created_at desc, w.id desc",
+		user.uuid
+	)
+	.fetch_all(&data.db)
+	.await;
+
+	match query_result {
+		Ok(items) => HttpResponse::Ok().json(items),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+// 본문 없이 찜할 수 있게 빈 본문은 기본값으로 보고, 잘못된 json은 400으로 돌려준다
+fn wishlist_input(
+	body: &[u8],
+) -> Result<WishlistInput, ApiError> {
+	if body.iter().all(u8::is_ascii_whitespace) {
+		return Ok(WishlistInput::default());
+	}
+	serde_json::from_slice(body).map_err(|err| {
+		ApiError::BadRequest(format!("Invalid body: {}", err))
+	})
+}
+
+#[post("/{post_id}")]
+pub async fn add_wishlist(
+	path: web::Path<Uuid>,
+	body: web::Bytes,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let post_id = path.into_inner();
+	let size = match wishlist_input(&body) {
+		Ok(input) => input.size,
+		Err(err) => return err.into_response(),
+	};
+
+	let query_result = sqlx::query_scalar!(
+		"select size from posts where uuid = $1",
+		post_id
+	)
+	.fetch_optional(&data.db)
+	.await;
+
+	// 사이즈를 지정했다면 상품에 있는 사이즈인지 확인한다
+	match query_result {
+		Ok(Some(post_size)) => {
+			if let Some(size) = &size {
+				if post_size.get(size).is_none() {
+					return ApiError::BadRequest(format!(
+						"Unknown size: {}",
+						size
+					))
+					.into_response();
+				}
+			}
+		}
+		Ok(None) => {
+			return ApiError::NotFound("Product not found")
+				.into_response()
+		}
+		Err(err) => {
+			return ApiError::Database(err).into_response()
+		}
+	}
+
+	let query_result = sqlx::query_as!(
+		Wishlist,
+		"insert into wishlists (user_id, post_id, size) values ($1, $2, $3)
+		on conflict (user_id, post_id) do update set size = excluded.size, updated_at = now()
+		returning *",
+		user.uuid,
+		post_id,
+		size
+	)
+	.fetch_one(&data.db)
+	.await;
+
+	match query_result {
+		Ok(wishlist) => HttpResponse::Created().json(wishlist),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[delete("/{post_id}")]
+pub async fn remove_wishlist(
+	path: web::Path<Uuid>,
+	user: AuthUser,
+	data: web::Data<AppState>,
+) -> impl Responder {
+	let query_result = sqlx::query!(
+		"delete from wishlists where user_id = $1 and post_id = $2",
+		user.uuid,
+		path.into_inner()
+	)
+	.execute(&data.db)
+	.await;
+
+	match query_result {
+		Ok(result) if result.rows_affected() == 0 => {
+			ApiError::NotFound("Product is not in the wishlist")
+				.into_response()
+		}
+		Ok(_) => HttpResponse::NoContent().finish(),
+		Err(err) => ApiError::Database(err).into_response(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn empty_body_means_whole_product() {
+		assert_eq!(wishlist_input(b"").unwrap().size, None);
+		assert_eq!(wishlist_input(b" \n").unwrap().size, None);
+		assert_eq!(wishlist_input(b"{}").unwrap().size, None);
+	}
+
+	#[test]
+	fn size_is_read_from_body() {
+		let input =
+			wishlist_input(br#"{"size": "270"}"#).unwrap();
+		assert_eq!(input.size.as_deref(), Some("270"));
+	}
+
+	#[test]
+	fn malformed_body_is_rejected() {
+		assert!(matches!(
+			wishlist_input(br#"{"size": 270"#),
+			Err(ApiError::BadRequest(_))
+		));
+		assert!(matches!(
+			wishlist_input(br#"{"size": 270}"#),
+			Err(ApiError::BadRequest(_))
+		));
+	}
+}
@@ -0,0 +1,12 @@
+use actix_web::web;
+
+use super::repo::{
+	add_wishlist, get_wishlist, remove_wishlist,
+};
+
+pub fn wishlist_routes() -> actix_web::Scope {
+	web::scope("")
+		.service(get_wishlist)
+		.service(add_wishlist)
+		.service(remove_wishlist)
+}
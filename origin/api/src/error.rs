@@ -0,0 +1,85 @@
+use actix_web::HttpResponse;
+use serde_json::json;
+
+// 핸들러에서 공통으로 사용하는 에러, into_response로 json 응답을 만든다
+#[derive(Debug)]
+pub enum ApiError {
+	BadRequest(String),
+	NotFound(&'static str),
+	Forbidden(&'static str),
+	Conflict(&'static str),
+	PayloadTooLarge(String),
+	UnsupportedMediaType(&'static str),
+	BadGateway(&'static str),
+	Database(sqlx::Error),
+}
+
+impl From<sqlx::Error> for ApiError {
+	fn from(err: sqlx::Error) -> Self {
+		ApiError::Database(err)
+	}
+}
+
+impl ApiError {
+	pub fn into_response(self) -> HttpResponse {
+		match self {
+			ApiError::BadRequest(message) => {
+				HttpResponse::BadRequest().json(
+					json!({"status": "fail", "message": message}),
+				)
+			}
+			ApiError::NotFound(message) => {
+				HttpResponse::NotFound().json(
+					json!({"status": "fail", "message": message}),
+				)
+			}
+			ApiError::Forbidden(message) => {
+				HttpResponse::Forbidden().json(
+					json!({"status": "fail", "message": message}),
+				)
+			}
+			ApiError::Conflict(message) => {
+				HttpResponse::Conflict().json(
+					json!({"status": "fail", "message": message}),
+				)
+			}
+			ApiError::PayloadTooLarge(message) => {
+				HttpResponse::PayloadTooLarge().json(
+					json!({"status": "fail", "message": message}),
+				)
+			}
+			ApiError::UnsupportedMediaType(message) => {
+				HttpResponse::UnsupportedMediaType().json(
+					json!({"status": "fail", "message": message}),
+				)
+			}
+			ApiError::BadGateway(message) => {
+				HttpResponse::BadGateway().json(
+					json!({"status": "fail", "message": message}),
+				)
+			}
+			ApiError::Database(err) => {
+				println!("🔥 query failed: {:?}", err);
+				HttpResponse::InternalServerError().json(json!({
+					"status": "error",
+					"message": "Something bad happened while handling the request"
+				}))
+			}
+		}
+	}
+}
+
+// unique 제약 위반이면 Conflict, 나머지는 Database 에러로 바꾼다
+pub fn conflict_on_unique(
+	err: sqlx::Error,
+	message: &'static str,
+) -> ApiError {
+	match &err {
+		sqlx::Error::Database(db_err)
+			if db_err.is_unique_violation() =>
+		{
+			ApiError::Conflict(message)
+		}
+		_ => ApiError::Database(err),
+	}
+}
@@ -0,0 +1,377 @@
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::entities::{
+	notification::model::{
+		NOTIFY_PRICE_DROP, NOTIFY_RESTOCK,
+	},
+	post::model::effective_price,
+};
+
+// 서버가 여러 대 떠 있어도 한 곳에서만 비교 작업을 하도록 advisory lock을 잡는다
+const WATCH_LOCK_ID: i64 = 29_001;
+
+struct StockRow {
+	uuid: Uuid,
+	title: String,
+	price: i64,
+	sale: i64,
+	count_in_stock: i64,
+	size: Value,
+	updated_at: Option<DateTime<Utc>>,
+}
+
+struct SnapshotRow {
+	post_id: Uuid,
+	effective_price: i64,
+	count_in_stock: i64,
+	size: Value,
+}
+
+fn watch_interval() -> Duration {
+	let secs = std::env::var("WISHLIST_WATCH_INTERVAL_SECS")
+		.ok()
+		.and_then(|value| value.parse().ok())
+		.unwrap_or(300);
+	Duration::from_secs(secs)
+}
+
+// 찜한 상품의 가격 인하와 재입고를 주기적으로 확인해서 알림을 쌓아둔다
+pub fn spawn_wishlist_watch(pool: PgPool) {
+	actix_web::rt::spawn(async move {
+		let mut interval =
+			actix_web::rt::time::interval(watch_interval());
+
+		loop {
+			interval.tick().await;
+
+			match check_wishlist_changes(&pool).await {
+				Ok(0) => {}
+				Ok(queued) => println!(
+					"🔔 {} wishlist notifications queued",
+					queued
+				),
+				Err(err) => {
+					println!("🔥 Wishlist watch failed: {:?}", err)
+				}
+			}
+		}
+	});
+}
+
+// 이전 확인때 0이었다가 다시 재고가 생긴 사이즈
+fn restocked_sizes(
+	before: &Value,
+	after: &Value,
+) -> Vec<String> {
+	let (Some(before), Some(after)) =
+		(before.as_object(), after.as_object())
+	else {
+		return Vec::new();
+	};
+
+	after
+		.iter()
+		.filter(|(size, count)| {
+			let was_empty = before
+				.get(*size)
+				.and_then(Value::as_i64)
+				.map(|c| c <= 0);
+			was_empty == Some(true)
+				&& count.as_i64().unwrap_or(0) > 0
+		})
+		.map(|(size, _)| size.clone())
+		.collect()
+}
+
+async fn enqueue_price_drop(
+	tx: &mut Transaction<'_, Postgres>,
+	post: &StockRow,
+	before: i64,
+	after: i64,
+) -> Result<u64, sqlx::Error> {
+	let message =
+		format!("찜한 상품 '{}'의 가격이 {}원에서 {}원으로 내려갔습니다.", post.title, before, after);
+
+	let result = sqlx::query!(
+		"insert into notifications (user_id, post_id, kind, message, payload)
+		select user_id, post_id, $2, $3, $4 from wishlists where post_id = $1",
+		post.uuid,
+		NOTIFY_PRICE_DROP,
+		message,
+		json!({"before": before, "after": after})
+	)
+	.execute(&mut **tx)
+	.await?;
+
+	Ok(result.rows_affected())
+}
+
+// 사이즈를 지정하지 않은 찜은 상품 전체 재입고, 사이즈를 지정한 찜은 해당 사이즈 재입고때만 알린다
+async fn enqueue_restock(
+	tx: &mut Transaction<'_, Postgres>,
+	post: &StockRow,
+	whole_restock: bool,
+	sizes: &[String],
+) -> Result<u64, sqlx::Error> {
+	let message = format!(
+		"찜한 상품 '{}'이(가) 재입고되었습니다.",
+		post.title
+	);
+
+	let result = sqlx::query!(
+		"insert into notifications (user_id, post_id, kind, message, payload)
+		select user_id, post_id, $2, $3, jsonb_build_object('size', size)
+		from wishlists
+		where post_id = $1 and ((size is null and $4) or size = any($5))",
+		post.uuid,
+		NOTIFY_RESTOCK,
+		message,
+		whole_restock,
+		sizes
+	)
+	.execute(&mut **tx)
+	.await?;
+
+	Ok(result.rows_affected())
+}
+
+pub async fn check_wishlist_changes(
+	pool: &PgPool,
+) -> Result<u64, sqlx::Error> {
+	let mut tx = pool.begin().await?;
+
+	let locked = sqlx::query_scalar!(
+		"select pg_try_advisory_xact_lock($1)",
+		WATCH_LOCK_ID
+	)
+	.fetch_one(&mut *tx)
+	.await?;
+	if locked != Some(true) {
+		return Ok(0);
+	}
+
+	// 찜한 사람이 있고 마지막 확인 이후 수정된 상품만 비교한다
+	let posts = sqlx::query_as!(
+		StockRow,
+		"select p.uuid, p.title, p.price, p.sale, p.count_in_stock, p.size, p.updated_at
+		from posts p
+		where exists (select 1 from wishlists w where w.post_id = p.uuid)
+			and not exists (
+				select 1 from post_stock_snapshots s
+				where s.post_id = p.uuid and s.post_updated_at is not distinct from p.updated_at
+			)"
+	)
+	.fetch_all(&mut *tx)
+	.await?;
+
+	let post_ids: Vec<Uuid> =
+		posts.iter().map(|post| post.uuid).collect();
+	let snapshots: HashMap<Uuid, SnapshotRow> = sqlx::query_as!(
+		SnapshotRow,
+		"select post_id, effective_price, count_in_stock, size from post_stock_snapshots
+		where post_id = any($1)",
+		&post_ids
+	)
+	.fetch_all(&mut *tx)
+	.await?
+	.into_iter()
+	.map(|snapshot| (snapshot.post_id, snapshot))
+	.collect();
+
+	let mut queued = 0;
+
+	for post in &posts {
+		let price = effective_price(post.price, post.sale);
+
+		// 처음 보는 상품은 기록만 하고 다음 확인부터 비교한다
+		if let Some(before) = snapshots.get(&post.uuid) {
+			if price < before.effective_price {
+				queued += enqueue_price_drop(
+					&mut tx,
+					post,
+					before.effective_price,
+					price,
+				)
+				.await?;
+			}
+
+			let whole_restock = before.count_in_stock <= 0
+				&& post.count_in_stock > 0;
+			let sizes = restocked_sizes(&before.size, &post.size);
+
+			if whole_restock || !sizes.is_empty() {
+				queued += enqueue_restock(
+					&mut tx,
+					post,
+					whole_restock,
+					&sizes,
+				)
+				.await?;
+			}
+		}
+
+		sqlx::query!(
+			"insert into post_stock_snapshots (post_id, effective_price, count_in_stock, size, post_updated_at)
+			values ($1, $2, $3, $4, $5)
+			on conflict (post_id) do update set
+				effective_price = excluded.effective_price,
+				count_in_stock = excluded.count_in_stock,
+				size = excluded.size,
+				post_updated_at = excluded.post_updated_at,
+				checked_at = now()",
+			post.uuid,
+			price,
+			post.count_in_stock,
+			post.size,
+			post.updated_at
+		)
+		.execute(&mut *tx)
+		.await?;
+	}
+
+	tx.commit().await?;
+	Ok(queued)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing::{insert_post, insert_user};
+
+	#[test]
+	fn sizes_that_were_sold_out_are_restocked() {
+		let before = json!({"250": 0, "260": 3, "270": 0});
+		let after =
+			json!({"250": 2, "260": 0, "270": 0, "280": 1});
+		assert_eq!(
+			restocked_sizes(&before, &after),
+			vec!["250"]
+		);
+	}
+
+	#[test]
+	fn non_object_sizes_are_ignored() {
+		assert!(restocked_sizes(
+			&json!([]),
+			&json!({"250": 1})
+		)
+		.is_empty());
+		assert!(restocked_sizes(
+			&json!({"250": 0}),
+			&Value::Null
+		)
+		.is_empty());
+	}
+
+	async fn notifications(pool: &PgPool, kind: &str) -> i64 {
+		sqlx::query_scalar(
+			"select count(*) from notifications where kind = $1",
+		)
+		.bind(kind)
+		.fetch_one(pool)
+		.await
+		.unwrap()
+	}
+
+	#[sqlx::test(
+		migrator = "crate::entities::migration::repo::MIGRATOR"
+	)]
+	async fn price_drop_and_restock_are_notified(
+		pool: PgPool,
+	) {
+		let seller = insert_user(&pool, "seller").await;
+		let buyer = insert_user(&pool, "buyer").await;
+		let post_id = insert_post(&pool, seller, "shoes").await;
+		sqlx::query(
+			"update posts set price = 10000, size = '{\"260\": 0}' where uuid = $1",
+		)
+		.bind(post_id)
+		.execute(&pool)
+		.await
+		.unwrap();
+		sqlx::query(
+			"insert into wishlists (user_id, post_id, size) values ($1, $2, '260')",
+		)
+		.bind(buyer)
+		.bind(post_id)
+		.execute(&pool)
+		.await
+		.unwrap();
+
+		// 처음 확인때는 스냅샷만 남긴다
+		assert_eq!(
+			check_wishlist_changes(&pool).await.unwrap(),
+			0
+		);
+
+		sqlx::query(
+			"update posts set sale = 10, size = '{\"260\": 2}' where uuid = $1",
+		)
+		.bind(post_id)
+		.execute(&pool)
+		.await
+		.unwrap();
+
+		assert_eq!(
+			check_wishlist_changes(&pool).await.unwrap(),
+			2
+		);
+		assert_eq!(
+			notifications(&pool, NOTIFY_PRICE_DROP).await,
+			1
+		);
+		assert_eq!(
+			notifications(&pool, NOTIFY_RESTOCK).await,
+			1
+		);
+	}
+
+	#[sqlx::test(
+		migrator = "crate::entities::migration::repo::MIGRATOR"
+	)]
+	async fn unchanged_posts_are_not_compared_again(
+		pool: PgPool,
+	) {
+		let seller = insert_user(&pool, "seller").await;
+		let buyer = insert_user(&pool, "buyer").await;
+		let post_id = insert_post(&pool, seller, "shoes").await;
+		sqlx::query(
+			"insert into wishlists (user_id, post_id) values ($1, $2)",
+		)
+		.bind(buyer)
+		.bind(post_id)
+		.execute(&pool)
+		.await
+		.unwrap();
+		check_wishlist_changes(&pool).await.unwrap();
+
+		// 상품이 그대로면 스냅샷 가격이 달라도 다시 비교하지 않는다
+		sqlx::query(
+			"update post_stock_snapshots set effective_price = 99999",
+		)
+		.execute(&pool)
+		.await
+		.unwrap();
+		assert_eq!(
+			check_wishlist_changes(&pool).await.unwrap(),
+			0
+		);
+
+		sqlx::query(
+			"update posts set title = 'shoes 2' where uuid = $1",
+		)
+		.bind(post_id)
+		.execute(&pool)
+		.await
+		.unwrap();
+		assert_eq!(
+			check_wishlist_changes(&pool).await.unwrap(),
+			1
+		);
+	}
+}
@@ -1,6 +1,7 @@
 pub mod entities {
 
 	pub mod user {
+		pub mod auth;
 		pub mod model;
 		pub mod repo;
 	}
@@ -9,20 +10,124 @@ pub mod entities {
 		pub mod repo;
 		pub mod routes;
 	}
+	pub mod brand {
+		pub mod model;
+		pub mod repo;
+		pub mod routes;
+	}
+	pub mod category {
+		pub mod model;
+		pub mod repo;
+		pub mod routes;
+	}
+	pub mod migration {
+		pub mod model;
+		pub mod repo;
+		pub mod routes;
+	}
+	pub mod notification {
+		pub mod model;
+		pub mod repo;
+		pub mod routes;
+	}
+	pub mod order {
+		pub mod model;
+	}
+	pub mod proxy {
+		pub mod model;
+		pub mod repo;
+		pub mod routes;
+	}
+	pub mod qna {
+		pub mod model;
+		pub mod repo;
+		pub mod routes;
+	}
+	pub mod review {
+		pub mod model;
+		pub mod moderation;
+		pub mod repo;
+		pub mod routes;
+	}
+	pub mod seller {
+		pub mod model;
+		pub mod repo;
+		pub mod routes;
+	}
+	pub mod settlement {
+		pub mod model;
+		pub mod repo;
+		pub mod routes;
+	}
+	pub mod upload {
+		pub mod model;
+		pub mod process;
+		pub mod repo;
+		pub mod routes;
+	}
+	pub mod wishlist {
+		pub mod model;
+		pub mod repo;
+		pub mod routes;
+	}
+}
+
+pub mod error;
+
+pub mod jobs {
+	pub mod wishlist_watch;
+}
+
+pub mod storage {
+	pub mod backend;
+	pub mod local;
+	pub mod s3;
 }
 
+#[cfg(test)]
+mod testing;
+
 pub mod seeders {
+	pub mod bind;
+	pub mod export;
+	pub mod fake;
+	pub mod insert;
+	pub mod order;
+	pub mod resolve;
+	pub mod schema;
+	pub mod source;
 	pub mod sqlx_seeder;
+	pub mod transform;
+	pub mod validate;
 }
 
-use crate::entities::post::routes::post_routes;
+use crate::entities::{
+	brand::routes::brand_routes, category::routes::category_routes,
+	migration::{
+		model::MigrateStep,
+		repo::{check_not_ahead, migrate},
+		routes::migration_routes,
+	},
+	notification::routes::notification_routes,
+	post::routes::post_routes,
+	proxy::{repo::ImageProxy, routes::proxy_routes},
+	qna::routes::qna_routes,
+	review::routes::review_routes,
+	seller::routes::seller_routes,
+	settlement::routes::settlement_routes,
+	upload::routes::upload_routes,
+	wishlist::routes::wishlist_routes,
+};
+use crate::jobs::wishlist_watch::spawn_wishlist_watch;
+use crate::seeders::sqlx_seeder::seeder;
+use crate::storage::backend::{storage_from_env, Storage};
 use actix_web::{
 	guard, middleware::Logger, web, App, HttpResponse,
 	HttpServer,
 };
 use dotenv::dotenv;
 use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
-use sqlx_pg_seeder::seeder;
+use std::sync::Arc;
 
 #[allow(unused)]
 pub async fn get_root() -> &'static str {
@@ -31,6 +136,8 @@ pub async fn get_root() -> &'static str {
 
 pub struct AppState {
 	pub db: Pool<Postgres>,
+	pub storage: Arc<dyn Storage>,
+	pub image_proxy: Arc<ImageProxy>,
 }
 
 pub async fn run() -> std::io::Result<()> {
@@ -66,7 +173,55 @@ pub async fn run() -> std::io::Result<()> {
 		}
 	};
 
-	let _seeder = seeder(&pool).await;
+	// DB가 바이너리보다 앞서 있으면 모르는 테이블을 쓰게 되므로 시작하지 않는다
+	let status = match check_not_ahead(&pool).await {
+		Ok(status) => status,
+		Err(err) => {
+			println!("🔥 {}", err);
+			std::process::exit(1);
+		}
+	};
+
+	// 마이그레이션은 server-rs migrate 명령으로 돌린다, MIGRATE_ON_STARTUP=true 일 때만 서버 시작시 돌리고
+	// 실패하면 서버를 띄우지 않는다
+	let migrate_on_startup = std::env::var("MIGRATE_ON_STARTUP")
+		.ok()
+		.and_then(|value| value.parse().ok())
+		.unwrap_or(false);
+	if migrate_on_startup {
+		match migrate(&pool, MigrateStep::Up).await {
+			Ok(run) => println!(
+				"✅ {} migration(s) applied",
+				run.applied.len()
+			),
+			Err(err) => {
+				println!("🔥 Migration failed: {}", err);
+				std::process::exit(1);
+			}
+		}
+	} else if !status.pending.is_empty() {
+		println!(
+			"⚠️ {} pending migration(s), run `server-rs migrate up` or set MIGRATE_ON_STARTUP=true",
+			status.pending.len()
+		);
+	}
+
+	// 시드는 seed 바이너리로 넣는다, SEED_ON_STARTUP=true 일 때만 서버 시작시 넣고
+	// 실패해도 서버는 그대로 띄운다
+	let seed_on_startup = std::env::var("SEED_ON_STARTUP")
+		.ok()
+		.and_then(|value| value.parse().ok())
+		.unwrap_or(false);
+	if seed_on_startup {
+		if let Err(err) = seeder(&pool).await {
+			println!("🔥 Seeder failed: {}", err);
+		}
+	}
+
+	spawn_wishlist_watch(pool.clone());
+
+	let storage = storage_from_env();
+	let image_proxy = Arc::new(ImageProxy::from_env());
 
 	println!(
 		"🚀 Actix server is running at http://{:?}",
@@ -82,11 +237,43 @@ pub async fn run() -> std::io::Result<()> {
 			.wrap(Logger::default())
 			.app_data(web::Data::new(AppState {
 				db: pool.clone(),
+				storage: storage.clone(),
+				image_proxy: image_proxy.clone(),
 			}))
 			.route("/api", web::get().to(get_root))
 			.service(
 				web::scope("/api/post").service(post_routes()),
 			)
+			.service(
+				web::scope("/api/review").service(review_routes()),
+			)
+			.service(
+				web::scope("/api/category").service(category_routes()),
+			)
+			.service(web::scope("/api/brand").service(brand_routes()))
+			.service(web::scope("/api/qna").service(qna_routes()))
+			.service(
+				web::scope("/api/seller").service(seller_routes()),
+			)
+			.service(
+				web::scope("/api/settlement")
+					.service(settlement_routes()),
+			)
+			.service(web::scope("/api/image").service(proxy_routes()))
+			.service(
+				web::scope("/api/upload").service(upload_routes()),
+			)
+			.service(
+				web::scope("/api/wishlist").service(wishlist_routes()),
+			)
+			.service(
+				web::scope("/api/notification")
+					.service(notification_routes()),
+			)
+			.service(
+				web::scope("/api/migration")
+					.service(migration_routes()),
+			)
 			.default_service(
 				web::route()
 					.guard(guard::Not(guard::Get()))
@@ -1,6 +1,126 @@
-use api::run;
+use std::error::Error;
+
+use api::{
+	entities::migration::{
+		model::{MigrateStep, MigrationStatus},
+		repo::{migrate, migration_status},
+	},
+	run,
+};
+use clap::{Parser, Subcommand};
+use dotenv::dotenv;
+use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
+
+// 하위 명령이 없으면 서버를 띄운다
+#[derive(Parser)]
+#[command(name = "server-rs")]
+struct Cli {
+	#[command(subcommand)]
+	command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// Apply or revert the migrations in src/migrations
+	Migrate {
+		#[command(subcommand)]
+		action: MigrateAction,
+	},
+}
+
+#[derive(Subcommand)]
+enum MigrateAction {
+	/// Apply all pending migrations
+	Up,
+	/// Revert the latest applied migration
+	Down,
+	/// Apply or revert until VERSION is the latest, 0 reverts everything
+	To { version: i64 },
+	/// Show applied and pending migrations
+	Status,
+}
+
+type CommandResult = Result<(), Box<dyn Error>>;
+
+async fn connect() -> Result<Pool<Postgres>, Box<dyn Error>>
+{
+	let database_url = std::env::var("DATABASE_URL")
+		.map_err(|_| "DATABASE_URL must be set")?;
+
+	PgPoolOptions::new()
+		.max_connections(2)
+		.connect(&database_url)
+		.await
+		.map_err(|err| {
+			format!(
+				"Failed to connect to the database: {:?}",
+				err
+			)
+			.into()
+		})
+}
+
+fn print_status(status: &MigrationStatus) {
+	for migration in &status.applied {
+		println!(
+			"{} {:<24} {} at {}",
+			migration.version,
+			migration.description,
+			migration.state,
+			migration.installed_on
+		);
+	}
+	for migration in &status.pending {
+		println!(
+			"{} {:<24} pending",
+			migration.version, migration.description
+		);
+	}
+}
+
+async fn migrate_command(
+	action: MigrateAction,
+) -> CommandResult {
+	let pool = connect().await?;
+	let step = match action {
+		MigrateAction::Up => MigrateStep::Up,
+		MigrateAction::Down => MigrateStep::Down,
+		MigrateAction::To { version } => {
+			MigrateStep::To(version)
+		}
+		MigrateAction::Status => {
+			let mut conn = pool.acquire().await?;
+			print_status(&migration_status(&mut conn).await?);
+			return Ok(());
+		}
+	};
+
+	let run = migrate(&pool, step).await?;
+	for version in &run.applied {
+		println!("✅ applied {}", version);
+	}
+	for version in &run.reverted {
+		println!("↩️ reverted {}", version);
+	}
+	if run.applied.is_empty() && run.reverted.is_empty() {
+		println!("✅ Nothing to migrate");
+	}
+	Ok(())
+}
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-	run().await
+	dotenv().ok();
+	let cli = Cli::parse();
+
+	match cli.command {
+		None => run().await,
+		Some(Command::Migrate { action }) => {
+			if let Err(err) = migrate_command(action).await {
+				eprintln!("🔥 {}", err);
+				std::process::exit(1);
+			}
+			Ok(())
+		}
+	}
 }
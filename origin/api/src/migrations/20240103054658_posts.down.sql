@@ -1,2 +1,2 @@
 -- Add down migration script here
-drop table users cascade;
\ No newline at end of file
+drop table if exists posts cascade;
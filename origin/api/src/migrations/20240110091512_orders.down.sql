@@ -0,0 +1,3 @@
+-- Add down migration script here
+drop table if exists order_items cascade;
+drop table if exists orders cascade;
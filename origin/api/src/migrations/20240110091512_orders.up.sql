@@ -0,0 +1,29 @@
+-- Add up migration script here
+create extension if not exists "uuid-ossp";
+
+create table if not exists orders (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "user_id" uuid not null references "users"("uuid") on delete cascade,
+    "total_price" bigint not null default 0,
+    "delivery_fee" bigint not null default 0,
+    created_at timestamp with time zone default now(),
+    updated_at timestamp with time zone default now()
+);
+
+-- status : pending, shipped, delivered, refunded, cancelled
+create table if not exists order_items (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "order_id" uuid not null references "orders"("uuid") on delete cascade,
+    "post_id" uuid not null references "posts"("uuid") on delete restrict,
+    "size" varchar(20) not null,
+    "quantity" bigint not null default 1,
+    "price" bigint not null default 0,
+    "status" varchar(20) not null default 'pending',
+    "delivered_at" timestamp with time zone,
+    created_at timestamp with time zone default now(),
+    updated_at timestamp with time zone default now()
+);
+
+create index if not exists order_items_post_id_idx on order_items ("post_id");
@@ -0,0 +1,20 @@
+-- Add up migration script here
+create extension if not exists "uuid-ossp";
+
+-- 한 사용자는 상품(post)당 한번만 리뷰를 작성할 수 있다
+create table if not exists reviews (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "post_id" uuid not null references "posts"("uuid") on delete cascade,
+    "user_id" uuid not null references "users"("uuid") on delete cascade,
+    "order_item_id" uuid references "order_items"("uuid") on delete set null,
+    "rating" bigint not null check ("rating" between 1 and 5),
+    "content" text not null,
+    "photos" text[],
+    "helpful_count" bigint not null default 0,
+    created_at timestamp with time zone default now(),
+    updated_at timestamp with time zone default now(),
+    unique ("post_id", "user_id")
+);
+
+create index if not exists reviews_post_id_idx on reviews ("post_id");
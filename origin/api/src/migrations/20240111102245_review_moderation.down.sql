@@ -0,0 +1,5 @@
+-- Add down migration script here
+drop table if exists review_reports cascade;
+drop table if exists review_votes cascade;
+alter table reviews drop column if exists "report_count";
+alter table reviews drop column if exists "status";
@@ -0,0 +1,26 @@
+-- Add up migration script here
+-- status : visible, pending(신고 누적으로 자동 숨김, 관리자 확인 대기), hidden(관리자 숨김)
+alter table reviews add column if not exists "status" varchar(20) not null default 'visible';
+alter table reviews add column if not exists "report_count" bigint not null default 0;
+
+create table if not exists review_votes (
+    "review_id" uuid not null references "reviews"("uuid") on delete cascade,
+    "user_id" uuid not null references "users"("uuid") on delete cascade,
+    created_at timestamp with time zone default now(),
+    primary key ("review_id", "user_id")
+);
+
+-- status : open, resolved
+create table if not exists review_reports (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "review_id" uuid not null references "reviews"("uuid") on delete cascade,
+    "user_id" uuid not null references "users"("uuid") on delete cascade,
+    "reason" text not null,
+    "status" varchar(20) not null default 'open',
+    created_at timestamp with time zone default now(),
+    updated_at timestamp with time zone default now(),
+    unique ("review_id", "user_id")
+);
+
+create index if not exists review_reports_review_id_idx on review_reports ("review_id");
@@ -0,0 +1,3 @@
+-- Add down migration script here
+drop table if exists answers cascade;
+drop table if exists questions cascade;
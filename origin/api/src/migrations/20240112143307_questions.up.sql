@@ -0,0 +1,27 @@
+-- Add up migration script here
+create extension if not exists "uuid-ossp";
+
+create table if not exists questions (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "post_id" uuid not null references "posts"("uuid") on delete cascade,
+    "user_id" uuid not null references "users"("uuid") on delete cascade,
+    "content" text not null,
+    "is_private" boolean not null default false,
+    created_at timestamp with time zone default now(),
+    updated_at timestamp with time zone default now()
+);
+
+-- 답변은 판매자(posts.user_id) 또는 관리자만 작성한다
+create table if not exists answers (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "question_id" uuid not null references "questions"("uuid") on delete cascade,
+    "user_id" uuid not null references "users"("uuid") on delete cascade,
+    "content" text not null,
+    created_at timestamp with time zone default now(),
+    updated_at timestamp with time zone default now()
+);
+
+create index if not exists questions_post_id_idx on questions ("post_id");
+create index if not exists answers_question_id_idx on answers ("question_id");
@@ -0,0 +1,6 @@
+-- Add down migration script here
+drop trigger if exists posts_touch_updated_at on posts;
+drop function if exists posts_touch_updated_at();
+drop table if exists notifications cascade;
+drop table if exists post_stock_snapshots cascade;
+drop table if exists wishlists cascade;
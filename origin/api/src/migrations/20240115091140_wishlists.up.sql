@@ -0,0 +1,56 @@
+-- Add up migration script here
+create extension if not exists "uuid-ossp";
+
+-- size가 null이면 상품 전체 재입고 알림, 값이 있으면 해당 사이즈 재입고 알림만 받는다
+create table if not exists wishlists (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "user_id" uuid not null references "users"("uuid") on delete cascade,
+    "post_id" uuid not null references "posts"("uuid") on delete cascade,
+    "size" varchar(20),
+    created_at timestamp with time zone default now(),
+    updated_at timestamp with time zone default now(),
+    unique ("user_id", "post_id")
+);
+
+create index if not exists wishlists_post_id_idx on wishlists ("post_id");
+
+-- 백그라운드 작업이 마지막으로 확인한 가격/재고, 다음 확인때 비교해서 알림을 만든다
+create table if not exists post_stock_snapshots (
+    "post_id" uuid primary key references "posts"("uuid") on delete cascade,
+    "effective_price" bigint not null,
+    "count_in_stock" bigint not null,
+    "size" jsonb not null,
+    "post_updated_at" timestamp with time zone,
+    checked_at timestamp with time zone default now()
+);
+
+-- 마지막 확인 이후 수정된 상품만 다시 비교할 수 있도록 posts가 바뀔 때마다 updated_at을 갱신한다
+create or replace function posts_touch_updated_at() returns trigger as $$
+begin
+    new.updated_at := now();
+    return new;
+end;
+$$ language plpgsql;
+
+drop trigger if exists posts_touch_updated_at on posts;
+create trigger posts_touch_updated_at before update on posts
+    for each row when (old.* is distinct from new.*)
+    execute function posts_touch_updated_at();
+
+-- kind : price_drop, restock
+-- sent_at이 null이면 아직 발송되지 않은 알림이다
+create table if not exists notifications (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "user_id" uuid not null references "users"("uuid") on delete cascade,
+    "post_id" uuid references "posts"("uuid") on delete cascade,
+    "kind" varchar(30) not null,
+    "message" text not null,
+    "payload" jsonb not null default '{}',
+    "is_read" boolean not null default false,
+    "sent_at" timestamp with time zone,
+    created_at timestamp with time zone default now()
+);
+
+create index if not exists notifications_user_id_idx on notifications ("user_id");
@@ -0,0 +1,6 @@
+-- Add down migration script here
+drop trigger if exists posts_assign_category on posts;
+drop function if exists posts_assign_category();
+alter table posts drop column if exists "category_id";
+drop function if exists category_slug(text);
+drop table if exists categories cascade;
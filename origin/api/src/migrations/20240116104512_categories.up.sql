@@ -0,0 +1,58 @@
+-- Add up migration script here
+create extension if not exists "uuid-ossp";
+
+-- names : 언어별 이름 {"ko": "셔츠", "en": "Shirts"}, 없으면 name을 사용한다
+create table if not exists categories (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "parent_id" uuid references "categories"("uuid") on delete restrict,
+    "slug" varchar(100) not null unique,
+    "name" varchar(100) not null,
+    "names" jsonb not null default '{}',
+    "display_order" bigint not null default 0,
+    created_at timestamp with time zone default now(),
+    updated_at timestamp with time zone default now()
+);
+
+create index if not exists categories_parent_id_idx on categories ("parent_id");
+
+-- "남자 정장" -> "남자-정장"
+create or replace function category_slug(value text) returns text as $$
+    select trim(both '-' from regexp_replace(lower(trim(value)), '[^[:alnum:]가-힣]+', '-', 'g'))
+$$ language sql immutable;
+
+alter table posts add column if not exists "category_id" uuid references "categories"("uuid") on delete set null;
+create index if not exists posts_category_id_idx on posts ("category_id");
+
+-- 기존 category 문자열을 최상위 카테고리로 옮긴다
+insert into categories ("name", "slug")
+select distinct on (category_slug(category)) trim(category), category_slug(category)
+from posts
+where trim(category) <> ''
+order by category_slug(category), trim(category)
+on conflict ("slug") do nothing;
+
+update posts p set category_id = c.uuid
+from categories c
+where p.category_id is null and c.slug = category_slug(p.category);
+
+-- seeder 등으로 category 문자열만 넣은 상품도 카테고리에 연결한다
+-- 카테고리가 삭제되어 on delete set null로 category_id만 비워진 경우에는 다시 만들지 않는다
+create or replace function posts_assign_category() returns trigger as $$
+begin
+    if (tg_op = 'INSERT' or new.category is distinct from old.category)
+        and new.category_id is null and trim(new.category) <> '' then
+        insert into categories ("name", "slug")
+        values (trim(new.category), category_slug(new.category))
+        on conflict ("slug") do nothing;
+
+        select uuid into new.category_id from categories where slug = category_slug(new.category);
+    end if;
+    return new;
+end;
+$$ language plpgsql;
+
+drop trigger if exists posts_assign_category on posts;
+create trigger posts_assign_category
+    before insert or update on posts
+    for each row execute function posts_assign_category();
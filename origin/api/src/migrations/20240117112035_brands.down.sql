@@ -0,0 +1,6 @@
+-- Add down migration script here
+drop trigger if exists posts_assign_brand on posts;
+drop function if exists posts_assign_brand();
+alter table posts drop column if exists "brand_id";
+drop table if exists brand_aliases cascade;
+drop table if exists brands cascade;
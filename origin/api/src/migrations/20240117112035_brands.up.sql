@@ -0,0 +1,64 @@
+-- Add up migration script here
+create extension if not exists "uuid-ossp";
+
+create table if not exists brands (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "slug" varchar(100) not null unique,
+    "name" varchar(100) not null,
+    "logo_src" text,
+    "description" text,
+    created_at timestamp with time zone default now(),
+    updated_at timestamp with time zone default now()
+);
+
+-- 병합된 브랜드의 예전 표기, 같은 표기로 상품이 들어오면 병합된 브랜드로 연결한다
+create table if not exists brand_aliases (
+    "alias_slug" varchar(100) primary key,
+    "brand_id" uuid not null references "brands"("uuid") on delete cascade
+);
+
+alter table posts add column if not exists "brand_id" uuid references "brands"("uuid") on delete set null;
+create index if not exists posts_brand_id_idx on posts ("brand_id");
+
+-- 기존 brand 문자열을 정리한다, slug는 category_slug 함수로 만들어서 대소문자/공백 차이를 합친다
+insert into brands ("name", "slug")
+select distinct on (category_slug(brand)) trim(brand), category_slug(brand)
+from posts
+where trim(brand) <> ''
+order by category_slug(brand), trim(brand)
+on conflict ("slug") do nothing;
+
+update posts p set brand_id = b.uuid, brand = b.name
+from brands b
+where p.brand_id is null and b.slug = category_slug(p.brand);
+
+-- 브랜드가 삭제되어 on delete set null로 brand_id만 비워진 경우에는 다시 만들지 않는다
+create or replace function posts_assign_brand() returns trigger as $$
+declare
+    brand_slug text;
+begin
+    if (tg_op = 'INSERT' or new.brand is distinct from old.brand)
+        and new.brand_id is null and trim(new.brand) <> '' then
+        brand_slug := category_slug(new.brand);
+
+        select brand_id into new.brand_id from brand_aliases where alias_slug = brand_slug;
+
+        if new.brand_id is null then
+            insert into brands ("name", "slug") values (trim(new.brand), brand_slug)
+            on conflict ("slug") do nothing;
+
+            select uuid into new.brand_id from brands where slug = brand_slug;
+        end if;
+
+        -- 표기가 달라도 브랜드의 정식 이름으로 맞춘다
+        select name into new.brand from brands where uuid = new.brand_id;
+    end if;
+    return new;
+end;
+$$ language plpgsql;
+
+drop trigger if exists posts_assign_brand on posts;
+create trigger posts_assign_brand
+    before insert or update on posts
+    for each row execute function posts_assign_brand();
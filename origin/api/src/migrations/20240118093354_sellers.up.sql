@@ -0,0 +1,24 @@
+-- Add up migration script here
+create extension if not exists "uuid-ossp";
+
+-- status : pending(신청), approved(판매 가능), suspended(판매 정지)
+create table if not exists sellers (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "user_id" uuid not null unique references "users"("uuid") on delete cascade,
+    "store_name" varchar(100) not null,
+    "business_number" varchar(12) unique,
+    "payout_bank" varchar(50),
+    "payout_account" varchar(50),
+    "payout_holder" varchar(50),
+    "status" varchar(20) not null default 'pending',
+    created_at timestamp with time zone default now(),
+    updated_at timestamp with time zone default now()
+);
+
+-- 이미 상품을 올린 사용자는 판매자로 등록해둔다
+insert into sellers ("user_id", "store_name", "status")
+select distinct u.uuid, u.name, 'approved'
+from users u
+join posts p on p.user_id = u.uuid
+on conflict do nothing;
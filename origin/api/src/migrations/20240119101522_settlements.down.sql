@@ -0,0 +1,6 @@
+-- Add down migration script here
+drop table if exists settlement_lines cascade;
+drop table if exists settlements cascade;
+drop trigger if exists order_items_status_time on order_items;
+drop function if exists order_items_status_time();
+alter table order_items drop column if exists "refunded_at";
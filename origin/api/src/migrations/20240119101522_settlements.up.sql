@@ -0,0 +1,67 @@
+-- Add up migration script here
+create extension if not exists "uuid-ossp";
+
+-- 환불 시점으로 정산 기간을 나누기 위해 환불 시각을 따로 남긴다
+alter table order_items add column if not exists "refunded_at" timestamp with time zone;
+
+update order_items set "refunded_at" = "updated_at"
+where "status" = 'refunded' and "refunded_at" is null;
+
+create or replace function order_items_status_time() returns trigger as $$
+begin
+    if new.status = 'delivered' and new.delivered_at is null then
+        new.delivered_at := now();
+    end if;
+    if new.status = 'refunded' and new.refunded_at is null then
+        new.refunded_at := now();
+    end if;
+    return new;
+end;
+$$ language plpgsql;
+
+drop trigger if exists order_items_status_time on order_items;
+create trigger order_items_status_time
+    before insert or update on order_items
+    for each row execute function order_items_status_time();
+
+-- seller_id는 posts.user_id, 기간은 period_start ~ period_end (양쪽 포함)
+-- status : pending(지급 대기), paid(지급 완료)
+create table if not exists settlements (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "seller_id" uuid not null references "users"("uuid") on delete restrict,
+    "period_start" date not null,
+    "period_end" date not null,
+    "gross_amount" bigint not null default 0,
+    "refund_amount" bigint not null default 0,
+    "commission_rate_bps" bigint not null,
+    "commission_amount" bigint not null default 0,
+    "payout_amount" bigint not null default 0,
+    "status" varchar(20) not null default 'pending',
+    "paid_at" timestamp with time zone,
+    created_at timestamp with time zone default now(),
+    updated_at timestamp with time zone default now(),
+    unique ("seller_id", "period_start", "period_end"),
+    check ("period_start" <= "period_end")
+);
+
+-- kind : sale(배송 완료), refund(배송 후 환불, 금액은 음수)
+-- 같은 주문 상품이 두 번 정산되지 않도록 (order_item_id, kind)를 unique로 둔다
+create table if not exists settlement_lines (
+    "id"  serial primary key,
+    "uuid" uuid default uuid_generate_v4() not null unique,
+    "settlement_id" uuid not null references "settlements"("uuid") on delete cascade,
+    "order_item_id" uuid not null references "order_items"("uuid") on delete restrict,
+    "post_id" uuid not null,
+    "title" varchar(100) not null,
+    "size" varchar(20) not null,
+    "kind" varchar(20) not null,
+    "quantity" bigint not null,
+    "amount" bigint not null,
+    "commission" bigint not null,
+    "occurred_at" timestamp with time zone not null,
+    created_at timestamp with time zone default now(),
+    unique ("order_item_id", "kind")
+);
+
+create index if not exists settlement_lines_settlement_id_idx on settlement_lines ("settlement_id");
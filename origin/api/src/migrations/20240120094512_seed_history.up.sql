@@ -0,0 +1,8 @@
+-- Add up migration script here
+create table if not exists "_seed_history" (
+  id serial primary key,
+  file_name varchar(255) not null unique,
+  checksum varchar(64) not null,
+  row_count bigint not null,
+  applied_at timestamptz default now() not null
+);
@@ -0,0 +1,5 @@
+-- Add down migration script here
+delete from "_seed_history" where seed_set <> 'dev';
+alter table "_seed_history" drop constraint if exists "_seed_history_set_file_key";
+alter table "_seed_history" add constraint "_seed_history_file_name_key" unique (file_name);
+alter table "_seed_history" drop column if exists seed_set;
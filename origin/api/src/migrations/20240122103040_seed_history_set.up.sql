@@ -0,0 +1,6 @@
+-- Add up migration script here
+-- 시드 세트(dev, test, demo)마다 기록을 따로 둔다, 기존 기록은 dev 세트로 본다
+alter table "_seed_history" add column if not exists seed_set varchar(64) not null default 'dev';
+alter table "_seed_history" drop constraint if exists "_seed_history_file_name_key";
+alter table "_seed_history" drop constraint if exists "_seed_history_set_file_key";
+alter table "_seed_history" add constraint "_seed_history_set_file_key" unique (seed_set, file_name);
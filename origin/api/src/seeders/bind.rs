@@ -0,0 +1,607 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use serde_json::Value;
+use sqlx::postgres::PgArguments;
+use sqlx::query::Query;
+use sqlx::types::Uuid;
+use sqlx::Postgres;
+
+use super::schema::{ColumnInfo, PgType};
+
+// 컬럼 타입에 맞게 변환한 값, 배열은 원소 타입별로 나눈다
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bound {
+  Null,
+  Uuid(Uuid),
+  Timestamptz(DateTime<FixedOffset>),
+  Timestamp(NaiveDateTime),
+  Date(NaiveDate),
+  Time(NaiveTime),
+  Int2(i16),
+  Int4(i32),
+  Int8(i64),
+  Float4(f32),
+  Float8(f64),
+  Bool(bool),
+  Json(Value),
+  Text(String),
+  UuidArray(Vec<Option<Uuid>>),
+  Int2Array(Vec<Option<i16>>),
+  Int4Array(Vec<Option<i32>>),
+  Int8Array(Vec<Option<i64>>),
+  Float4Array(Vec<Option<f32>>),
+  Float8Array(Vec<Option<f64>>),
+  BoolArray(Vec<Option<bool>>),
+  // 나머지 배열은 원소를 검사한 뒤 문자열 배열로 보내고 postgres에서 캐스트한다,
+  // 배열 원소의 null은 None
+  TextArray(Vec<Option<String>>),
+}
+
+impl Bound {
+  pub fn bind<'q>(
+    self,
+    query: Query<'q, Postgres, PgArguments>,
+  ) -> Query<'q, Postgres, PgArguments> {
+    match self {
+      Bound::Null => query.bind(None::<String>),
+      Bound::Uuid(value) => query.bind(value),
+      Bound::Timestamptz(value) => query.bind(value),
+      Bound::Timestamp(value) => query.bind(value),
+      Bound::Date(value) => query.bind(value),
+      Bound::Time(value) => query.bind(value),
+      Bound::Int2(value) => query.bind(value),
+      Bound::Int4(value) => query.bind(value),
+      Bound::Int8(value) => query.bind(value),
+      Bound::Float4(value) => query.bind(value),
+      Bound::Float8(value) => query.bind(value),
+      Bound::Bool(value) => query.bind(value),
+      Bound::Json(value) => query.bind(value),
+      Bound::Text(value) => query.bind(value),
+      Bound::UuidArray(value) => query.bind(value),
+      Bound::Int2Array(value) => query.bind(value),
+      Bound::Int4Array(value) => query.bind(value),
+      Bound::Int8Array(value) => query.bind(value),
+      Bound::Float4Array(value) => query.bind(value),
+      Bound::Float8Array(value) => query.bind(value),
+      Bound::BoolArray(value) => query.bind(value),
+      Bound::TextArray(value) => query.bind(value),
+    }
+  }
+}
+
+fn quote_array_item(item: String) -> String {
+  format!("\"{}\"", item.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn array_literal<T: ToString>(items: &[Option<T>]) -> String {
+  let items: Vec<String> = items
+    .iter()
+    .map(|item| match item {
+      Some(item) => quote_array_item(item.to_string()),
+      None => "NULL".to_string(),
+    })
+    .collect();
+  format!("{{{}}}", items.join(","))
+}
+
+impl Bound {
+  // COPY ... (format csv)의 필드 하나, null은 따옴표 없는 빈 값이다
+  pub fn copy_field(&self) -> String {
+    let text = match self {
+      Bound::Null => return String::new(),
+      Bound::Uuid(value) => value.to_string(),
+      Bound::Timestamptz(value) => value.to_rfc3339(),
+      Bound::Timestamp(value) => value.format("%Y-%m-%d %H:%M:%S%.f").to_string(),
+      Bound::Date(value) => value.to_string(),
+      Bound::Time(value) => value.to_string(),
+      Bound::Int2(value) => value.to_string(),
+      Bound::Int4(value) => value.to_string(),
+      Bound::Int8(value) => value.to_string(),
+      Bound::Float4(value) => value.to_string(),
+      Bound::Float8(value) => value.to_string(),
+      Bound::Bool(value) => value.to_string(),
+      Bound::Json(value) => value.to_string(),
+      Bound::Text(value) => value.clone(),
+      Bound::UuidArray(items) => array_literal(items),
+      Bound::Int2Array(items) => array_literal(items),
+      Bound::Int4Array(items) => array_literal(items),
+      Bound::Int8Array(items) => array_literal(items),
+      Bound::Float4Array(items) => array_literal(items),
+      Bound::Float8Array(items) => array_literal(items),
+      Bound::BoolArray(items) => array_literal(items),
+      Bound::TextArray(items) => array_literal(items),
+    };
+    format!("\"{}\"", text.replace('"', "\"\""))
+  }
+}
+
+// "$1::int8" 처럼 항상 컬럼 타입으로 캐스트해서 null도 같은 방식으로 넣는다
+pub fn placeholder(column: &ColumnInfo, n: usize) -> String {
+  format!("${}::{}", n, column.pg_type.sql_name())
+}
+
+fn type_error(expected: &str, value: &Value) -> String {
+  format!("expected {}, got {}", expected, value)
+}
+
+fn to_i64(value: &Value) -> Result<i64, String> {
+  match value {
+    Value::Number(number) => number
+      .as_i64()
+      .ok_or_else(|| type_error("an integer", value)),
+    Value::String(text) => text
+      .trim()
+      .parse()
+      .map_err(|_| type_error("an integer", value)),
+    _ => Err(type_error("an integer", value)),
+  }
+}
+
+fn to_int<T: TryFrom<i64>>(value: &Value, type_name: &str) -> Result<T, String> {
+  let number = to_i64(value)?;
+  T::try_from(number).map_err(|_| format!("{} is out of range for {}", number, type_name))
+}
+
+fn to_f64(value: &Value) -> Result<f64, String> {
+  match value {
+    Value::Number(number) => number.as_f64().ok_or_else(|| type_error("a number", value)),
+    Value::String(text) => text
+      .trim()
+      .parse()
+      .map_err(|_| type_error("a number", value)),
+    _ => Err(type_error("a number", value)),
+  }
+}
+
+fn to_bool(value: &Value) -> Result<bool, String> {
+  match value {
+    Value::Bool(flag) => Ok(*flag),
+    Value::String(text) if text.trim().eq_ignore_ascii_case("true") => Ok(true),
+    Value::String(text) if text.trim().eq_ignore_ascii_case("false") => Ok(false),
+    _ => Err(type_error("true or false", value)),
+  }
+}
+
+fn to_str<'a>(value: &'a Value, expected: &str) -> Result<&'a str, String> {
+  value.as_str().ok_or_else(|| type_error(expected, value))
+}
+
+// 숫자나 bool은 문자열 컬럼에 넣을 때 그대로 문자열로 바꾼다
+fn to_text(value: &Value) -> Result<String, String> {
+  match value {
+    Value::String(text) => Ok(text.clone()),
+    Value::Number(_) | Value::Bool(_) => Ok(value.to_string()),
+    _ => Err(type_error("a string", value)),
+  }
+}
+
+fn parse_timestamptz(text: &str) -> Option<DateTime<FixedOffset>> {
+  DateTime::parse_from_rfc3339(text).ok().or_else(|| {
+    // 날짜만 있으면 UTC 자정으로 본다
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+      .ok()
+      .and_then(|date| date.and_hms_opt(0, 0, 0))
+      .map(|datetime| datetime.and_utc().fixed_offset())
+  })
+}
+
+fn parse_timestamp(text: &str) -> Option<NaiveDateTime> {
+  ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    .or_else(|| {
+      DateTime::parse_from_rfc3339(text)
+        .ok()
+        .map(|datetime| datetime.naive_utc())
+    })
+}
+
+fn parse_time(text: &str) -> Option<NaiveTime> {
+  ["%H:%M:%S%.f", "%H:%M"]
+    .iter()
+    .find_map(|format| NaiveTime::parse_from_str(text, format).ok())
+}
+
+fn convert_scalar(pg_type: &PgType, value: &Value) -> Result<Bound, String> {
+  let bound = match pg_type {
+    PgType::Uuid => {
+      let text = to_str(value, "a UUID string")?;
+      Bound::Uuid(Uuid::parse_str(text).map_err(|_| type_error("a UUID", value))?)
+    }
+    PgType::Timestamptz => {
+      let text = to_str(value, "an RFC 3339 timestamp")?;
+      Bound::Timestamptz(
+        parse_timestamptz(text).ok_or_else(|| type_error("an RFC 3339 timestamp", value))?,
+      )
+    }
+    PgType::Timestamp => {
+      let text = to_str(value, "a timestamp")?;
+      Bound::Timestamp(parse_timestamp(text).ok_or_else(|| type_error("a timestamp", value))?)
+    }
+    PgType::Date => {
+      let text = to_str(value, "a YYYY-MM-DD date")?;
+      Bound::Date(
+        NaiveDate::parse_from_str(text, "%Y-%m-%d")
+          .map_err(|_| type_error("a YYYY-MM-DD date", value))?,
+      )
+    }
+    PgType::Time => {
+      let text = to_str(value, "a HH:MM:SS time")?;
+      Bound::Time(parse_time(text).ok_or_else(|| type_error("a HH:MM:SS time", value))?)
+    }
+    PgType::Int2 => Bound::Int2(to_int(value, "int2")?),
+    PgType::Int4 => Bound::Int4(to_int(value, "int4")?),
+    PgType::Int8 => Bound::Int8(to_i64(value)?),
+    PgType::Numeric => {
+      to_f64(value)?;
+      Bound::Text(to_text(value)?.trim().to_string())
+    }
+    PgType::Float4 => Bound::Float4(to_f64(value)? as f32),
+    PgType::Float8 => Bound::Float8(to_f64(value)?),
+    PgType::Bool => Bound::Bool(to_bool(value)?),
+    PgType::Json | PgType::Jsonb => Bound::Json(value.clone()),
+    PgType::Text => Bound::Text(to_text(value)?),
+    PgType::Enum { name, labels } => {
+      let text = to_str(value, "a string")?;
+      if !labels.iter().any(|label| label == text) {
+        return Err(format!(
+          "{:?} is not a value of enum {} ({})",
+          text,
+          name,
+          labels.join(", ")
+        ));
+      }
+      Bound::Text(text.to_string())
+    }
+    PgType::Other(_) => match value {
+      Value::String(text) => Bound::Text(text.clone()),
+      other => Bound::Text(other.to_string()),
+    },
+    PgType::Array(_) => return Err("nested arrays are not supported".to_string()),
+  };
+
+  Ok(bound)
+}
+
+fn convert_array(element: &PgType, value: &Value) -> Result<Bound, String> {
+  let items = value
+    .as_array()
+    .ok_or_else(|| type_error("an array", value))?;
+
+  let converted = items
+    .iter()
+    .enumerate()
+    .map(|(index, item)| {
+      if item.is_null() {
+        return Ok(Bound::Null);
+      }
+      convert_scalar(element, item).map_err(|err| format!("array element {}: {}", index, err))
+    })
+    .collect::<Result<Vec<Bound>, String>>()?;
+
+  macro_rules! collect {
+    ($variant:ident, $array:ident) => {
+      Bound::$array(
+        converted
+          .into_iter()
+          .filter_map(|bound| match bound {
+            Bound::$variant(value) => Some(Some(value)),
+            Bound::Null => Some(None),
+            _ => None,
+          })
+          .collect(),
+      )
+    };
+  }
+
+  let bound = match element {
+    PgType::Uuid => collect!(Uuid, UuidArray),
+    PgType::Int2 => collect!(Int2, Int2Array),
+    PgType::Int4 => collect!(Int4, Int4Array),
+    PgType::Int8 => collect!(Int8, Int8Array),
+    PgType::Float4 => collect!(Float4, Float4Array),
+    PgType::Float8 => collect!(Float8, Float8Array),
+    PgType::Bool => collect!(Bool, BoolArray),
+    _ => Bound::TextArray(
+      items
+        .iter()
+        .map(|item| match item {
+          Value::Null => None,
+          Value::String(text) => Some(text.clone()),
+          other => Some(other.to_string()),
+        })
+        .collect(),
+    ),
+  };
+
+  Ok(bound)
+}
+
+// JSON 값을 컬럼의 실제 postgres 타입에 맞게 검사하고 변환한다
+pub fn convert(column: &ColumnInfo, value: &Value) -> Result<Bound, String> {
+  if value.is_null() {
+    if !column.nullable {
+      return Err("column is NOT NULL".to_string());
+    }
+    return Ok(Bound::Null);
+  }
+
+  let bound = match &column.pg_type {
+    PgType::Array(element) => convert_array(element, value)?,
+    pg_type => convert_scalar(pg_type, value)?,
+  };
+
+  if let (Bound::Text(text), Some(max_length)) = (&bound, column.max_length) {
+    if text.chars().count() > max_length as usize {
+      return Err(format!("value is longer than {} characters", max_length));
+    }
+  }
+
+  Ok(bound)
+}
+
+// {a,b,"c,d"} 형태의 postgres 배열 문자열, 따옴표 안의 \" \\ 만 처리하고 따옴표 없는 NULL은 null
+fn parse_array_literal(text: &str) -> Option<Vec<Value>> {
+  let inner = text.strip_prefix('{')?.strip_suffix('}')?;
+  let mut items = Vec::new();
+  if inner.trim().is_empty() {
+    return Some(items);
+  }
+
+  let mut chars = inner.chars().peekable();
+  loop {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+
+    let mut item = String::new();
+    let value = if chars.next_if_eq(&'"').is_some() {
+      loop {
+        match chars.next()? {
+          '\\' => item.push(chars.next()?),
+          '"' => break,
+          c => item.push(c),
+        }
+      }
+      while chars.next_if(|c| c.is_whitespace()).is_some() {}
+      Value::String(item)
+    } else {
+      while let Some(c) = chars.next_if(|c| *c != ',') {
+        item.push(c);
+      }
+      let item = item.trim();
+      if item.eq_ignore_ascii_case("null") {
+        Value::Null
+      } else {
+        Value::String(item.to_string())
+      }
+    };
+    items.push(value);
+
+    match chars.next() {
+      Some(',') => continue,
+      None => return Some(items),
+      Some(_) => return None,
+    }
+  }
+}
+
+// csv 셀을 json 값으로 바꾼다, json/jsonb 컬럼은 셀을 JSON으로 읽고 배열 컬럼은
+// ["a","b"] 나 {a,b} 둘 다 받는다. 빈 셀은 null, NOT NULL 문자열 컬럼만 빈 문자열로 둔다
+pub fn from_text(column: &ColumnInfo, text: &str) -> Result<Value, String> {
+  if text.is_empty() {
+    if column.pg_type == PgType::Text && !column.nullable {
+      return Ok(Value::String(String::new()));
+    }
+    return Ok(Value::Null);
+  }
+
+  match &column.pg_type {
+    PgType::Json | PgType::Jsonb => {
+      serde_json::from_str(text).map_err(|err| format!("invalid JSON in cell: {}", err))
+    }
+    PgType::Array(_) if text.starts_with('[') => {
+      serde_json::from_str(text).map_err(|err| format!("invalid JSON array in cell: {}", err))
+    }
+    PgType::Array(_) => parse_array_literal(text)
+      .map(Value::Array)
+      .ok_or_else(|| format!("expected [\"a\", \"b\"] or {{a,b}}, got {:?}", text)),
+    _ => Ok(Value::String(text.to_string())),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn column(pg_type: PgType) -> ColumnInfo {
+    ColumnInfo {
+      name: "value".to_string(),
+      pg_type,
+      nullable: true,
+      has_default: false,
+      max_length: None,
+      serial: false,
+      generated: false,
+    }
+  }
+
+  #[test]
+  fn integers_accept_numbers_and_numeric_strings() {
+    assert_eq!(
+      convert(&column(PgType::Int8), &json!(12)),
+      Ok(Bound::Int8(12))
+    );
+    assert_eq!(
+      convert(&column(PgType::Int8), &json!(" 12 ")),
+      Ok(Bound::Int8(12))
+    );
+    assert_eq!(
+      convert(&column(PgType::Int4), &json!(7)),
+      Ok(Bound::Int4(7))
+    );
+    assert!(convert(&column(PgType::Int8), &json!(3.3)).is_err());
+    assert!(convert(&column(PgType::Int8), &json!("three")).is_err());
+    assert_eq!(
+      convert(&column(PgType::Int2), &json!(40_000)),
+      Err("40000 is out of range for int2".to_string())
+    );
+  }
+
+  #[test]
+  fn floats_bools_and_text_are_coerced() {
+    assert_eq!(
+      convert(&column(PgType::Float8), &json!(3)),
+      Ok(Bound::Float8(3.0))
+    );
+    assert_eq!(
+      convert(&column(PgType::Float8), &json!("4.5")),
+      Ok(Bound::Float8(4.5))
+    );
+    assert_eq!(
+      convert(&column(PgType::Bool), &json!("true")),
+      Ok(Bound::Bool(true))
+    );
+    assert!(convert(&column(PgType::Bool), &json!(1)).is_err());
+    assert_eq!(
+      convert(&column(PgType::Text), &json!(270)),
+      Ok(Bound::Text("270".to_string()))
+    );
+    assert_eq!(
+      convert(&column(PgType::Numeric), &json!(" 1.50 ")),
+      Ok(Bound::Text("1.50".to_string()))
+    );
+    assert!(convert(&column(PgType::Text), &json!({"a": 1})).is_err());
+  }
+
+  #[test]
+  fn dates_and_uuids_are_parsed() {
+    let uuid = Uuid::new_v4();
+    assert_eq!(
+      convert(&column(PgType::Uuid), &json!(uuid.to_string())),
+      Ok(Bound::Uuid(uuid))
+    );
+    assert!(convert(&column(PgType::Uuid), &json!("not-a-uuid")).is_err());
+
+    let Ok(Bound::Timestamptz(value)) = convert(&column(PgType::Timestamptz), &json!("2024-01-02"))
+    else {
+      panic!("date only timestamptz was not parsed");
+    };
+    assert_eq!(value.to_rfc3339(), "2024-01-02T00:00:00+00:00");
+
+    assert!(matches!(
+      convert(&column(PgType::Timestamp), &json!("2024-01-02 03:04:05")),
+      Ok(Bound::Timestamp(_))
+    ));
+    assert!(convert(&column(PgType::Date), &json!("2024/01/02")).is_err());
+    assert!(matches!(
+      convert(&column(PgType::Time), &json!("09:30")),
+      Ok(Bound::Time(_))
+    ));
+  }
+
+  #[test]
+  fn null_and_length_follow_the_column() {
+    let mut name = column(PgType::Text);
+    assert_eq!(convert(&name, &Value::Null), Ok(Bound::Null));
+
+    name.nullable = false;
+    name.max_length = Some(3);
+    assert_eq!(
+      convert(&name, &Value::Null),
+      Err("column is NOT NULL".to_string())
+    );
+    assert!(convert(&name, &json!("가나다")).is_ok());
+    assert!(convert(&name, &json!("가나다라")).is_err());
+  }
+
+  #[test]
+  fn enum_values_must_be_labels() {
+    let status = column(PgType::Enum {
+      name: "status".to_string(),
+      labels: vec!["open".to_string(), "closed".to_string()],
+    });
+    assert_eq!(
+      convert(&status, &json!("open")),
+      Ok(Bound::Text("open".to_string()))
+    );
+    assert!(convert(&status, &json!("pending")).is_err());
+  }
+
+  #[test]
+  fn arrays_are_converted_per_element() {
+    let ints = column(PgType::Array(Box::new(PgType::Int4)));
+    assert_eq!(
+      convert(&ints, &json!([1, "2"])),
+      Ok(Bound::Int4Array(vec![Some(1), Some(2)]))
+    );
+    assert_eq!(
+      convert(&ints, &json!([1, "x"])),
+      Err("array element 1: expected an integer, got \"x\"".to_string())
+    );
+    assert_eq!(
+      convert(&ints, &json!([1, null])),
+      Ok(Bound::Int4Array(vec![Some(1), None]))
+    );
+    assert!(convert(&ints, &json!(1)).is_err());
+
+    let texts = column(PgType::Array(Box::new(PgType::Text)));
+    assert_eq!(
+      convert(&texts, &json!(["a", 2])),
+      Ok(Bound::TextArray(vec![
+        Some("a".to_string()),
+        Some("2".to_string())
+      ]))
+    );
+
+    // COPY에서는 원소의 null을 따옴표 없는 NULL로 쓴다
+    assert_eq!(
+      Bound::TextArray(vec![Some("a b".to_string()), None]).copy_field(),
+      "\"{\"\"a b\"\",NULL}\""
+    );
+  }
+
+  #[test]
+  fn placeholder_casts_to_column_type() {
+    assert_eq!(placeholder(&column(PgType::Int8), 1), "$1::int8");
+    assert_eq!(
+      placeholder(&column(PgType::Array(Box::new(PgType::Uuid))), 2),
+      "$2::uuid[]"
+    );
+  }
+  #[test]
+  fn csv_cells_are_read_by_column_type() {
+    let mut name = column(PgType::Text);
+    assert_eq!(from_text(&name, ""), Ok(Value::Null));
+    name.nullable = false;
+    assert_eq!(from_text(&name, ""), Ok(json!("")));
+    assert_eq!(from_text(&column(PgType::Int8), "12"), Ok(json!("12")));
+
+    assert_eq!(
+      from_text(&column(PgType::Jsonb), r#"{"a": [1]}"#),
+      Ok(json!({"a": [1]}))
+    );
+    assert!(from_text(&column(PgType::Jsonb), "{a").is_err());
+
+    let tags = column(PgType::Array(Box::new(PgType::Text)));
+    assert_eq!(from_text(&tags, r#"["a", "b"]"#), Ok(json!(["a", "b"])));
+    assert_eq!(from_text(&tags, "{}"), Ok(json!([])));
+    assert_eq!(
+      from_text(&tags, r#"{a, "b,c", "d\"e"}"#),
+      Ok(json!(["a", "b,c", "d\"e"]))
+    );
+    assert!(from_text(&tags, "{a,\"b}").is_err());
+    assert!(from_text(&tags, "a,b").is_err());
+  }
+
+  #[test]
+  fn bools_ignore_case() {
+    assert_eq!(
+      convert(&column(PgType::Bool), &json!("TRUE")),
+      Ok(Bound::Bool(true))
+    );
+    assert_eq!(
+      convert(&column(PgType::Bool), &json!(" False ")),
+      Ok(Bound::Bool(false))
+    );
+    assert!(convert(&column(PgType::Bool), &json!("yes")).is_err());
+  }
+}
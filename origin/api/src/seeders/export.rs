@@ -0,0 +1,374 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+use sqlx::{PgConnection, Pool, Postgres};
+
+use super::schema::{
+  load_foreign_keys, load_table, load_unique_columns, quote_ident, ForeignKey, PgType, TableSchema,
+  UniqueColumn,
+};
+use super::source::{write_rows, Row};
+
+// 내보낼 테이블과 where 절, where 절은 개발용 DB에서 직접 적는 SQL이다
+#[derive(Debug, Clone)]
+pub struct ExportTable {
+  pub table: String,
+  pub filter: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct ExportedFile {
+  pub table: String,
+  pub path: PathBuf,
+  pub rows: usize,
+}
+
+// 번호 키를 가리키는 외래키 컬럼, 다시 넣으면 번호가 바뀌므로 부모의 unique 컬럼 값으로 $ref를 쓴다
+#[derive(Debug, Clone, PartialEq)]
+struct ParentRef {
+  column: String,
+  parent_table: String,
+  parent_key: String,
+  unique: String,
+}
+
+fn literal(text: &str) -> String {
+  format!("'{}'", text.replace('\'', "''"))
+}
+
+fn is_numeric(pg_type: &PgType) -> bool {
+  match pg_type {
+    PgType::Numeric => true,
+    PgType::Array(element) => is_numeric(element),
+    _ => false,
+  }
+}
+
+// to_jsonb(t)로 row를 읽는다, jsonb, 배열, 시각은 시더가 다시 읽는 형태 그대로 나온다.
+// numeric은 f64를 거치면 자리수가 바뀌므로 문자열로 꺼내고, 번호 키를 가리키는 컬럼은 $ref로 바꾼다
+fn select_statement(schema: &TableSchema, refs: &[ParentRef], filter: Option<&str>) -> String {
+  let numeric = schema
+    .columns
+    .iter()
+    .filter(|column| is_numeric(&column.pg_type))
+    .filter(|column| !refs.iter().any(|parent| parent.column == column.name))
+    .map(|column| {
+      let cast = match column.pg_type {
+        PgType::Array(_) => "text[]",
+        _ => "text",
+      };
+      format!(
+        "{}, t.{}::{}",
+        literal(&column.name),
+        quote_ident(&column.name),
+        cast
+      )
+    });
+
+  // 부모 row가 없거나 unique 값이 null이면 원래 값을 그대로 쓴다
+  let references = refs.iter().map(|parent| {
+    let column = quote_ident(&parent.column);
+    format!(
+      "{}, coalesce((select jsonb_build_object('$ref', {} || p.{}::text) from {} p where p.{} = t.{}), to_jsonb(t.{}))",
+      literal(&parent.column),
+      literal(&format!("{}.{}:", parent.parent_table, parent.unique)),
+      quote_ident(&parent.unique),
+      quote_ident(&parent.parent_table),
+      quote_ident(&parent.parent_key),
+      column,
+      column
+    )
+  });
+  let overrides: Vec<String> = numeric.chain(references).collect();
+
+  let mut row = "to_jsonb(t)".to_string();
+  // jsonb_build_object는 인자를 100개까지 받는다
+  for chunk in overrides.chunks(50) {
+    row.push_str(&format!(" || jsonb_build_object({})", chunk.join(", ")));
+  }
+
+  // 번호 컬럼이 있으면 넣은 순서대로, 자기 자신을 참조하는 row도 부모가 먼저 나온다
+  let order = schema
+    .columns
+    .iter()
+    .find(|column| column.serial)
+    .map(|column| format!("t.{}", quote_ident(&column.name)))
+    .unwrap_or_else(|| "1".to_string());
+
+  format!(
+    "select {} from {} t{} order by {}",
+    row,
+    quote_ident(&schema.name),
+    filter
+      .map(|filter| format!(" where {}", filter))
+      .unwrap_or_default(),
+    order
+  )
+}
+
+// 번호 컬럼과 generated 컬럼은 다시 넣을 때 postgres가 만들므로 뺀다
+fn seed_row(schema: &TableSchema, mut exported: Map<String, Value>) -> Row {
+  schema
+    .columns
+    .iter()
+    .filter(|column| !column.serial && !column.generated)
+    .filter_map(|column| {
+      let value = exported.remove(&column.name)?;
+      Some((column.name.clone(), value))
+    })
+    .collect()
+}
+
+// 컬럼 하나짜리 외래키 중 부모의 번호 컬럼을 가리키는 것, 부모에 unique 컬럼이 없으면 번호를 그대로 쓰고 알린다
+async fn parent_refs(
+  conn: &mut PgConnection,
+  schema: &TableSchema,
+  foreign_keys: &[ForeignKey],
+  unique: &[UniqueColumn],
+) -> Result<Vec<ParentRef>, Box<dyn Error>> {
+  let mut refs = Vec::new();
+
+  for key in foreign_keys
+    .iter()
+    .filter(|key| key.table == schema.name && !key.columns.contains(','))
+  {
+    let serial = load_table(&mut *conn, &key.parent_table)
+      .await?
+      .and_then(|parent| {
+        parent
+          .column(&key.parent_columns)
+          .map(|column| column.serial)
+      })
+      .unwrap_or(false);
+    if !serial {
+      continue;
+    }
+
+    match unique.iter().find(|column| column.table == key.parent_table) {
+      Some(column) => refs.push(ParentRef {
+        column: key.columns.clone(),
+        parent_table: key.parent_table.clone(),
+        parent_key: key.parent_columns.clone(),
+        unique: column.column.clone(),
+      }),
+      None => println!(
+        "⚠️ {}: {} has no unique column, {} is exported as is and may point at another row after reseeding",
+        key, key.parent_table, key.columns
+      ),
+    }
+  }
+
+  Ok(refs)
+}
+
+async fn export_table(
+  pool: &Pool<Postgres>,
+  export: &ExportTable,
+  foreign_keys: &[ForeignKey],
+  unique: &[UniqueColumn],
+) -> Result<Vec<Row>, Box<dyn Error>> {
+  let mut conn = pool.acquire().await?;
+  let schema = load_table(&mut conn, &export.table)
+    .await?
+    .ok_or_else(|| format!("table {} does not exist", export.table))?;
+  let refs = parent_refs(&mut conn, &schema, foreign_keys, unique).await?;
+
+  let statement = select_statement(&schema, &refs, export.filter.as_deref());
+  let rows: Vec<Value> = sqlx::query_scalar(&statement)
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|err| format!("{}: {}", export.table, err))?;
+
+  Ok(
+    rows
+      .into_iter()
+      .filter_map(|row| match row {
+        Value::Object(row) => Some(seed_row(&schema, row)),
+        _ => None,
+      })
+      .collect(),
+  )
+}
+
+// 테이블마다 <폴더>/<테이블>.json 을 {"테이블": [...]} 형태로 쓴다,
+// 이미 있는 파일은 force일 때만 덮어쓴다
+pub async fn export(
+  pool: &Pool<Postgres>,
+  tables: &[ExportTable],
+  folder: &Path,
+  force: bool,
+) -> Result<Vec<ExportedFile>, Box<dyn Error>> {
+  for export in tables {
+    let path = folder.join(format!("{}.json", export.table));
+    if path.exists() && !force {
+      return Err(
+        format!(
+          "{} already exists, pass --force to overwrite",
+          path.display()
+        )
+        .into(),
+      );
+    }
+  }
+
+  let (foreign_keys, unique) = {
+    let mut conn = pool.acquire().await?;
+    (
+      load_foreign_keys(&mut conn).await?,
+      load_unique_columns(&mut conn).await?,
+    )
+  };
+  let mut exported = Vec::new();
+
+  for export in tables {
+    let rows = export_table(pool, export, &foreign_keys, &unique).await?;
+    let count = rows.len();
+    let path = write_rows(folder, &export.table, rows, false)?;
+
+    exported.push(ExportedFile {
+      table: export.table.clone(),
+      path,
+      rows: count,
+    });
+  }
+
+  Ok(exported)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::seeders::insert::{seed_rows, BatchOptions, SeedInput};
+  use crate::seeders::resolve::Resolver;
+  use crate::seeders::source::{load_rows, SeedFormat, SeedRows};
+  use sqlx::{Executor, PgPool};
+  use std::fs;
+
+  fn parent_ref() -> ParentRef {
+    ParentRef {
+      column: "parent_id".to_string(),
+      parent_table: "parent".to_string(),
+      parent_key: "id".to_string(),
+      unique: "slug".to_string(),
+    }
+  }
+
+  #[test]
+  fn serial_references_are_selected_as_refs() {
+    let schema = TableSchema {
+      name: "child".to_string(),
+      columns: Vec::new(),
+    };
+    let statement = select_statement(&schema, &[parent_ref()], Some("note <> ''"));
+    assert_eq!(
+      statement,
+      "select to_jsonb(t) || jsonb_build_object('parent_id', coalesce((select jsonb_build_object('$ref', 'parent.slug:' || p.\"slug\"::text) from \"parent\" p where p.\"id\" = t.\"parent_id\"), to_jsonb(t.\"parent_id\"))) from \"child\" t where note <> '' order by 1"
+    );
+  }
+
+  async fn notes(pool: &PgPool) -> Vec<(String, Option<String>)> {
+    sqlx::query_as(
+      "select c.note, p.slug from child c left join parent p on p.id = c.parent_id order by c.note",
+    )
+    .fetch_all(pool)
+    .await
+    .unwrap()
+  }
+
+  // 내보낸 파일을 번호가 바뀐 DB에 다시 넣어도 같은 부모를 가리킨다
+  #[sqlx::test(migrations = false)]
+  async fn exported_references_survive_new_ids(pool: PgPool) {
+    pool
+      .execute(
+        "create table parent (id serial primary key, slug text unique, name text);
+        create table child (id serial primary key, parent_id int references parent (id), note text);
+        insert into parent (slug, name) values ('a', 'A'), ('b', 'B');
+        insert into child (parent_id, note) values (2, 'to b'), (1, 'to a'), (null, 'orphan');",
+      )
+      .await
+      .unwrap();
+    let before = notes(&pool).await;
+
+    let folder = std::env::temp_dir().join(format!("seed-export-{}", uuid::Uuid::new_v4()));
+    let tables = ["parent", "child"].map(|table| ExportTable {
+      table: table.to_string(),
+      filter: None,
+    });
+    export(&pool, &tables, &folder, false).await.unwrap();
+
+    let child: Value =
+      serde_json::from_str(&fs::read_to_string(folder.join("child.json")).unwrap()).unwrap();
+    assert_eq!(
+      child["child"][0]["parent_id"],
+      serde_json::json!({"$ref": "parent.slug:b"})
+    );
+    assert_eq!(child["child"][2]["parent_id"], Value::Null);
+
+    pool
+      .execute("truncate child, parent; alter sequence parent_id_seq restart with 100;")
+      .await
+      .unwrap();
+
+    let mut conn = pool.acquire().await.unwrap();
+    let foreign_keys = load_foreign_keys(&mut conn).await.unwrap();
+    drop(conn);
+    let mut tx = pool.begin().await.unwrap();
+    for table in ["parent", "child"] {
+      let file = format!("{}.json", table);
+      let schema = load_table(&mut tx, table).await.unwrap().unwrap();
+      let SeedRows::Loaded(rows) =
+        load_rows(&folder.join(&file), SeedFormat::Json, table, &file).unwrap()
+      else {
+        panic!("{} is not loaded", file);
+      };
+      let input = SeedInput {
+        total: rows.len(),
+        rows: Box::new(rows.into_iter().map(Ok)),
+        text_cells: false,
+      };
+      seed_rows(
+        &mut tx,
+        &schema,
+        input,
+        &file,
+        None,
+        &BatchOptions {
+          batch_size: 500,
+          copy: false,
+        },
+        &mut Resolver::new("dev", &foreign_keys, None),
+      )
+      .await
+      .unwrap();
+    }
+    tx.commit().await.unwrap();
+
+    let ids: Vec<i32> = sqlx::query_scalar("select id from parent order by id")
+      .fetch_all(&pool)
+      .await
+      .unwrap();
+    assert_eq!(ids, [100, 101]);
+    assert_eq!(notes(&pool).await, before);
+  }
+
+  #[sqlx::test(migrations = false)]
+  async fn parents_without_unique_columns_keep_the_raw_id(pool: PgPool) {
+    pool
+      .execute(
+        "create table parent (id serial primary key, name text);
+        create table child (id serial primary key, parent_id int references parent (id));",
+      )
+      .await
+      .unwrap();
+
+    let mut conn = pool.acquire().await.unwrap();
+    let foreign_keys = load_foreign_keys(&mut conn).await.unwrap();
+    let unique = load_unique_columns(&mut conn).await.unwrap();
+    let schema = load_table(&mut conn, "child").await.unwrap().unwrap();
+    let refs = parent_refs(&mut conn, &schema, &foreign_keys, &unique)
+      .await
+      .unwrap();
+    assert!(refs.is_empty());
+  }
+}
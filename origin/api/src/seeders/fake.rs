@@ -0,0 +1,1095 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::entities::order::model::{
+  ORDER_ITEM_CANCELLED, ORDER_ITEM_DELIVERED, ORDER_ITEM_PENDING, ORDER_ITEM_REFUNDED,
+  ORDER_ITEM_SHIPPED,
+};
+use crate::entities::post::model::effective_price;
+
+use super::source::Row;
+
+// 만든 시각은 실행한 날짜가 아니라 이 시각 기준이라 같은 seed면 같은 파일이 나온다
+const ANCHOR: i64 = 1_704_067_200; // 2024-01-01T00:00:00Z
+
+const DAY: i64 = 86_400;
+
+// 가짜 사용자 비밀번호, [seeders.transforms.users] password = "bcrypt" 로 해시된다
+pub const FAKE_PASSWORD: &str = "password123";
+
+// 만들 row 수와 seed, seed가 같으면 같은 데이터가 나온다
+#[derive(Debug, Clone, Copy)]
+pub struct FakeOptions {
+  pub seed: u64,
+  pub users: usize,
+  pub posts: usize,
+  pub orders: usize,
+  pub reviews: usize,
+}
+
+// 테이블 하나에 넣을 row, 넣는 순서대로 온다
+#[derive(Debug)]
+pub struct FakeTable {
+  pub table: &'static str,
+  pub rows: Vec<Row>,
+}
+
+// splitmix64, 테이블마다 따로 만들어서 사용자 수를 늘려도 앞쪽 사용자는 그대로다
+pub struct FakeRng {
+  state: u64,
+}
+
+impl FakeRng {
+  pub fn new(seed: u64, stream: &str) -> Self {
+    let hash = Sha256::digest(format!("{}/{}", seed, stream));
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&hash[..8]);
+    FakeRng {
+      state: u64::from_be_bytes(bytes),
+    }
+  }
+
+  pub fn next_u64(&mut self) -> u64 {
+    self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = self.state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+  }
+
+  // 0..n
+  pub fn below(&mut self, n: usize) -> usize {
+    ((self.next_u64() as u128 * n as u128) >> 64) as usize
+  }
+
+  // min..=max
+  pub fn range(&mut self, min: i64, max: i64) -> i64 {
+    min + self.below((max - min + 1) as usize) as i64
+  }
+
+  pub fn chance(&mut self, probability: f64) -> bool {
+    ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+  }
+
+  pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+    &items[self.below(items.len())]
+  }
+
+  // (값, 가중치) 중에서 가중치 비율로 고른다
+  pub fn weighted<T: Copy>(&mut self, items: &[(T, u32)]) -> T {
+    let total: u32 = items.iter().map(|(_, weight)| weight).sum();
+    let mut ticket = self.below(total as usize) as u32;
+    for (item, weight) in items {
+      if ticket < *weight {
+        return *item;
+      }
+      ticket -= weight;
+    }
+    items[items.len() - 1].0
+  }
+
+  pub fn uuid(&mut self) -> String {
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&self.next_u64().to_be_bytes());
+    bytes[8..].copy_from_slice(&self.next_u64().to_be_bytes());
+    uuid::Builder::from_random_bytes(bytes)
+      .into_uuid()
+      .to_string()
+  }
+}
+
+// (성, 로마자, 비율)
+const SURNAMES: [(&str, &str, u32); 20] = [
+  ("김", "kim", 21),
+  ("이", "lee", 15),
+  ("박", "park", 8),
+  ("최", "choi", 5),
+  ("정", "jung", 5),
+  ("강", "kang", 2),
+  ("조", "cho", 2),
+  ("윤", "yoon", 2),
+  ("장", "jang", 2),
+  ("임", "lim", 2),
+  ("한", "han", 1),
+  ("오", "oh", 1),
+  ("서", "seo", 1),
+  ("신", "shin", 1),
+  ("권", "kwon", 1),
+  ("황", "hwang", 1),
+  ("안", "ahn", 1),
+  ("송", "song", 1),
+  ("류", "ryu", 1),
+  ("홍", "hong", 1),
+];
+
+const GIVEN_NAMES: [(&str, &str); 40] = [
+  ("민준", "minjun"),
+  ("서준", "seojun"),
+  ("도윤", "doyun"),
+  ("예준", "yejun"),
+  ("시우", "siwoo"),
+  ("하준", "hajun"),
+  ("주원", "juwon"),
+  ("지호", "jiho"),
+  ("지후", "jihoo"),
+  ("준우", "junwoo"),
+  ("현우", "hyunwoo"),
+  ("건우", "gunwoo"),
+  ("우진", "woojin"),
+  ("선우", "sunwoo"),
+  ("연우", "yeonwoo"),
+  ("서연", "seoyeon"),
+  ("서윤", "seoyun"),
+  ("지우", "jiwoo"),
+  ("서현", "seohyun"),
+  ("민서", "minseo"),
+  ("하은", "haeun"),
+  ("하윤", "hayun"),
+  ("윤서", "yunseo"),
+  ("지유", "jiyu"),
+  ("지민", "jimin"),
+  ("채원", "chaewon"),
+  ("수아", "sua"),
+  ("지아", "jia"),
+  ("다은", "daeun"),
+  ("은서", "eunseo"),
+  ("영호", "youngho"),
+  ("정훈", "junghoon"),
+  ("성민", "sungmin"),
+  ("미영", "miyoung"),
+  ("수진", "sujin"),
+  ("혜진", "hyejin"),
+  ("은지", "eunji"),
+  ("동현", "donghyun"),
+  ("상우", "sangwoo"),
+  ("경희", "kyunghee"),
+];
+
+const EMAIL_DOMAINS: [&str; 3] = ["example.com", "example.net", "example.org"];
+
+struct Region {
+  city: &'static str,
+  district: &'static str,
+  dongs: &'static [&'static str],
+  roads: &'static [&'static str],
+  // 우편번호 앞 두 자리
+  postcode: &'static str,
+}
+
+// (지역, 비율)
+const REGIONS: [(Region, u32); 14] = [
+  (
+    Region {
+      city: "서울특별시",
+      district: "강남구",
+      dongs: &["역삼동", "삼성동", "대치동"],
+      roads: &["테헤란로", "선릉로", "도곡로"],
+      postcode: "06",
+    },
+    5,
+  ),
+  (
+    Region {
+      city: "서울특별시",
+      district: "관악구",
+      dongs: &["신림동", "봉천동"],
+      roads: &["관악로", "신림로", "남부순환로"],
+      postcode: "08",
+    },
+    4,
+  ),
+  (
+    Region {
+      city: "서울특별시",
+      district: "마포구",
+      dongs: &["서교동", "합정동", "공덕동"],
+      roads: &["월드컵로", "양화로", "마포대로"],
+      postcode: "04",
+    },
+    4,
+  ),
+  (
+    Region {
+      city: "서울특별시",
+      district: "송파구",
+      dongs: &["잠실동", "문정동", "가락동"],
+      roads: &["올림픽로", "송파대로", "오금로"],
+      postcode: "05",
+    },
+    5,
+  ),
+  (
+    Region {
+      city: "서울특별시",
+      district: "노원구",
+      dongs: &["상계동", "중계동"],
+      roads: &["동일로", "노원로"],
+      postcode: "01",
+    },
+    3,
+  ),
+  (
+    Region {
+      city: "부산광역시",
+      district: "해운대구",
+      dongs: &["우동", "중동", "좌동"],
+      roads: &["해운대로", "센텀중앙로"],
+      postcode: "48",
+    },
+    3,
+  ),
+  (
+    Region {
+      city: "부산광역시",
+      district: "부산진구",
+      dongs: &["부전동", "전포동"],
+      roads: &["중앙대로", "서면로"],
+      postcode: "47",
+    },
+    2,
+  ),
+  (
+    Region {
+      city: "대구광역시",
+      district: "수성구",
+      dongs: &["범어동", "만촌동"],
+      roads: &["달구벌대로", "동대구로"],
+      postcode: "42",
+    },
+    2,
+  ),
+  (
+    Region {
+      city: "인천광역시",
+      district: "연수구",
+      dongs: &["송도동", "연수동"],
+      roads: &["컨벤시아대로", "송도과학로"],
+      postcode: "21",
+    },
+    2,
+  ),
+  (
+    Region {
+      city: "광주광역시",
+      district: "서구",
+      dongs: &["치평동", "화정동"],
+      roads: &["상무대로", "내방로"],
+      postcode: "61",
+    },
+    1,
+  ),
+  (
+    Region {
+      city: "대전광역시",
+      district: "유성구",
+      dongs: &["봉명동", "궁동"],
+      roads: &["대학로", "유성대로"],
+      postcode: "34",
+    },
+    1,
+  ),
+  (
+    Region {
+      city: "경기도",
+      district: "성남시 분당구",
+      dongs: &["정자동", "서현동"],
+      roads: &["정자일로", "분당로"],
+      postcode: "13",
+    },
+    4,
+  ),
+  (
+    Region {
+      city: "경기도",
+      district: "수원시 영통구",
+      dongs: &["영통동", "매탄동"],
+      roads: &["영통로", "매탄로"],
+      postcode: "16",
+    },
+    3,
+  ),
+  (
+    Region {
+      city: "경기도",
+      district: "고양시 일산동구",
+      dongs: &["장항동", "백석동"],
+      roads: &["중앙로", "일산로"],
+      postcode: "10",
+    },
+    3,
+  ),
+];
+
+const APARTMENTS: [&str; 8] = [
+  "래미안",
+  "자이",
+  "힐스테이트",
+  "푸르지오",
+  "아이파크",
+  "e편한세상",
+  "더샵",
+  "롯데캐슬",
+];
+
+const TOP_SIZES: &[&str] = &["S", "M", "L", "XL"];
+const WOMEN_SIZES: &[&str] = &["44", "55", "66", "77"];
+const FREE_SIZE: &[&str] = &["FREE"];
+
+struct Category {
+  name: &'static str,
+  items: &'static [&'static str],
+  sizes: &'static [&'static str],
+  // 정가 범위(원)
+  price: (i64, i64),
+}
+
+// (카테고리, 비율)
+const CATEGORIES: [(Category, u32); 10] = [
+  (
+    Category {
+      name: "남자정장",
+      items: &["옥스포드 셔츠", "싱글 수트", "슬랙스", "블레이저"],
+      sizes: &["95", "100", "105", "110"],
+      price: (39_000, 590_000),
+    },
+    3,
+  ),
+  (
+    Category {
+      name: "여자정장",
+      items: &["트위드 자켓", "H라인 스커트", "블라우스", "와이드 슬랙스"],
+      sizes: WOMEN_SIZES,
+      price: (29_000, 390_000),
+    },
+    3,
+  ),
+  (
+    Category {
+      name: "티셔츠",
+      items: &["반팔 티셔츠", "긴팔 티셔츠", "피케 셔츠", "맨투맨"],
+      sizes: TOP_SIZES,
+      price: (9_000, 89_000),
+    },
+    6,
+  ),
+  (
+    Category {
+      name: "아우터",
+      items: &["숏패딩", "롱패딩", "트렌치 코트", "바람막이"],
+      sizes: TOP_SIZES,
+      price: (59_000, 890_000),
+    },
+    4,
+  ),
+  (
+    Category {
+      name: "청바지",
+      items: &["와이드 데님", "슬림 데님", "부츠컷 데님"],
+      sizes: &["26", "28", "30", "32", "34"],
+      price: (29_000, 189_000),
+    },
+    4,
+  ),
+  (
+    Category {
+      name: "원피스",
+      items: &["플리츠 원피스", "셔츠 원피스", "니트 원피스"],
+      sizes: WOMEN_SIZES,
+      price: (29_000, 259_000),
+    },
+    3,
+  ),
+  (
+    Category {
+      name: "운동화",
+      items: &["러닝화", "스니커즈", "농구화", "트레이닝화"],
+      sizes: &["230", "240", "250", "260", "270", "280"],
+      price: (49_000, 1_290_000),
+    },
+    5,
+  ),
+  (
+    Category {
+      name: "구두",
+      items: &["로퍼", "첼시 부츠", "더비 슈즈", "펌프스"],
+      sizes: &["225", "235", "245", "255", "265", "275"],
+      price: (59_000, 490_000),
+    },
+    2,
+  ),
+  (
+    Category {
+      name: "가방",
+      items: &["토트백", "백팩", "크로스백", "에코백"],
+      sizes: FREE_SIZE,
+      price: (19_000, 1_590_000),
+    },
+    3,
+  ),
+  (
+    Category {
+      name: "모자",
+      items: &["볼캡", "버킷햇", "비니"],
+      sizes: FREE_SIZE,
+      price: (9_000, 79_000),
+    },
+    2,
+  ),
+];
+
+// 앞쪽 브랜드일수록 상품이 많다
+const BRANDS: [&str; 15] = [
+  "폴라",
+  "나이스",
+  "발렌시아",
+  "지이크파란오비",
+  "플로랄프로란",
+  "바이더웨이텐",
+  "아디도스",
+  "뉴발란트",
+  "컨버즈",
+  "노스페이스트",
+  "구찌오",
+  "프라도",
+  "스파이오",
+  "탑텐스",
+  "에잇써드",
+];
+
+const STYLES: [&str; 10] = [
+  "베이직",
+  "프리미엄",
+  "데일리",
+  "시그니처",
+  "클래식",
+  "빈티지",
+  "미니멀",
+  "에센셜",
+  "오버핏",
+  "슬림핏",
+];
+
+const COLORS: [&str; 6] = ["블랙", "화이트", "네이비", "그레이", "베이지", "카키"];
+
+const DESCRIPTIONS: [&str; 6] = [
+  "옷장에 하나쯤은 있어야 할 머스트 해브 아이템!",
+  "캐주얼 또는 포멀룩 어디에도 매칭할 수 있어요.",
+  "가볍고 편안한 착용감으로 데일리로 입기 좋습니다.",
+  "탄탄한 소재로 세탁 후에도 형태가 잘 유지됩니다.",
+  "시즌 한정 수량으로 준비했습니다.",
+  "정사이즈로 나왔으니 평소 사이즈로 주문해주세요.",
+];
+
+// 할인율(%) 분포, 절반 이상은 할인하지 않는다
+const SALES: [(i64, u32); 8] = [
+  (0, 55),
+  (5, 5),
+  (10, 12),
+  (15, 5),
+  (20, 10),
+  (30, 8),
+  (40, 3),
+  (50, 2),
+];
+
+const RATINGS: [(i64, u32); 5] = [(1, 3), (2, 5), (3, 12), (4, 30), (5, 50)];
+
+const GOOD_REVIEWS: [&str; 5] = [
+  "사이즈 딱 맞고 핏이 예뻐요.",
+  "배송이 빠르고 포장도 꼼꼼했어요.",
+  "가격 대비 품질이 정말 좋습니다. 재구매 의사 있어요!",
+  "사진이랑 색감이 똑같아요.",
+  "선물했는데 너무 좋아하네요.",
+];
+
+const OKAY_REVIEWS: [&str; 3] = [
+  "무난하게 입기 좋아요.",
+  "생각보다 조금 크게 나왔어요. 한 치수 작게 추천합니다.",
+  "재질은 괜찮은데 배송이 조금 늦었어요.",
+];
+
+const BAD_REVIEWS: [&str; 3] = [
+  "사진과 색이 많이 달라요.",
+  "한 번 세탁했는데 보풀이 생겼어요.",
+  "마감이 아쉬워서 교환했습니다.",
+];
+
+fn time(offset: i64) -> Value {
+  let time: DateTime<Utc> = Utc.timestamp_opt(ANCHOR + offset, 0).unwrap();
+  Value::String(time.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+}
+
+fn image(seed: u64, key: &str) -> String {
+  format!("https://picsum.photos/seed/{}-{}/600/800", seed, key)
+}
+
+fn object(value: Value) -> Row {
+  match value {
+    Value::Object(row) => row,
+    _ => Map::new(),
+  }
+}
+
+struct FakeUser {
+  uuid: String,
+  name: String,
+  created: i64,
+}
+
+struct FakePost {
+  uuid: String,
+  sizes: Vec<String>,
+  price: i64,
+  sale: i64,
+  delivery_fee: i64,
+  created: i64,
+}
+
+struct DeliveredItem {
+  uuid: String,
+  post: usize,
+  user: usize,
+  delivered: i64,
+}
+
+// 가입은 2년 전~400일 전, 상품 등록은 400일 전~200일 전, 주문은 최근 200일 안에 한다
+fn users(options: &FakeOptions, users: &mut Vec<FakeUser>) -> Vec<Row> {
+  let mut rng = FakeRng::new(options.seed, "users");
+  let surnames: Vec<(usize, u32)> = SURNAMES
+    .iter()
+    .enumerate()
+    .map(|(index, (_, _, weight))| (index, *weight))
+    .collect();
+  let mut rows = Vec::with_capacity(options.users);
+
+  for index in 0..options.users {
+    let (surname, surname_roman, _) = SURNAMES[rng.weighted(&surnames)];
+    let (given, given_roman) = *rng.pick(&GIVEN_NAMES);
+    let uuid = rng.uuid();
+    let name = format!("{}{}", surname, given);
+    let created = -rng.range(400 * DAY, 730 * DAY);
+
+    rows.push(object(json!({
+      "uuid": uuid,
+      "name": name,
+      "email": format!(
+        "{}.{}{}@{}",
+        given_roman,
+        surname_roman,
+        index + 1,
+        rng.pick(&EMAIL_DOMAINS)
+      ),
+      "password": FAKE_PASSWORD,
+      "is_admin": false,
+      "is_verified": rng.chance(0.8),
+      "created_at": time(created),
+      "updated_at": time(created),
+    })));
+    users.push(FakeUser {
+      uuid,
+      name,
+      created,
+    });
+  }
+
+  rows
+}
+
+fn phone(rng: &mut FakeRng) -> (String, String) {
+  (
+    format!("{:04}", rng.range(2000, 9999)),
+    format!("{:04}", rng.range(0, 9999)),
+  )
+}
+
+// 한 사람당 배송지 0~3개, 대부분 하나다
+fn addresses(options: &FakeOptions, users: &[FakeUser]) -> Vec<Row> {
+  let mut rng = FakeRng::new(options.seed, "address");
+  let regions: Vec<(usize, u32)> = REGIONS
+    .iter()
+    .enumerate()
+    .map(|(index, (_, weight))| (index, *weight))
+    .collect();
+  let mut rows = Vec::new();
+
+  for user in users {
+    let count = rng.weighted(&[(0, 10), (1, 65), (2, 20), (3, 5)]);
+    for _ in 0..count {
+      let region = &REGIONS[rng.weighted(&regions)].0;
+      let address = format!(
+        "{} {} {} {} {}",
+        region.city,
+        region.district,
+        rng.pick(region.dongs),
+        rng.pick(region.roads),
+        rng.range(1, 300)
+      );
+      let (detail1, detail2) = if rng.chance(0.75) {
+        (
+          format!(
+            "{}동 {}호",
+            rng.range(101, 120),
+            rng.range(1, 25) * 100 + rng.range(1, 6)
+          ),
+          format!("{}아파트", rng.pick(&APARTMENTS)),
+        )
+      } else {
+        (format!("{}층", rng.range(1, 5)), String::new())
+      };
+      // 가족 이름으로 받는 배송지도 있다
+      let recipient = if rng.chance(0.8) {
+        user.name.clone()
+      } else {
+        format!(
+          "{}{}",
+          user.name.chars().next().unwrap_or('김'),
+          rng.pick(&GIVEN_NAMES).0
+        )
+      };
+      let (phone2, phone3) = phone(&mut rng);
+      let created = user.created + rng.range(0, 30 * DAY);
+
+      rows.push(object(json!({
+        "recipient": recipient,
+        "shipping_address": format!("{}, {}", address, detail1),
+        "postcode": format!("{}{:03}", region.postcode, rng.range(0, 999)),
+        "address": address,
+        "detail1": detail1,
+        "detail2": detail2,
+        "phone1": "010",
+        "phone2": phone2,
+        "phone3": phone3,
+        "user_id": user.uuid,
+        "created_at": time(created),
+        "updated_at": time(created),
+      })));
+    }
+  }
+
+  rows
+}
+
+// 판매자는 사용자 20명 중 한 명꼴, 평점과 리뷰 수는 리뷰를 만든 뒤에 채운다
+fn posts(options: &FakeOptions, users: &[FakeUser], posts: &mut Vec<FakePost>) -> Vec<Row> {
+  let mut rng = FakeRng::new(options.seed, "posts");
+  let sellers = users.len().div_ceil(20);
+  let categories: Vec<(usize, u32)> = CATEGORIES
+    .iter()
+    .enumerate()
+    .map(|(index, (_, weight))| (index, *weight))
+    .collect();
+  let brands: Vec<(usize, u32)> = (0..BRANDS.len())
+    .map(|index| (index, (BRANDS.len() - index) as u32))
+    .collect();
+  let mut rows = Vec::with_capacity(options.posts);
+  if users.is_empty() {
+    return rows;
+  }
+
+  for index in 0..options.posts {
+    let category = &CATEGORIES[rng.weighted(&categories)].0;
+    let brand = BRANDS[rng.weighted(&brands)];
+    let item = *rng.pick(category.items);
+    let style = *rng.pick(&STYLES);
+    let uuid = rng.uuid();
+    let seller = &users[rng.below(sellers)];
+    let created = -rng.range(200 * DAY, 400 * DAY);
+
+    // 39,000원처럼 천 원 단위, 만 원대는 9,000원으로 끝나는 가격이 많다
+    let mut price = rng.range(category.price.0 / 1000, category.price.1 / 1000) * 1000;
+    if price >= 10_000 && rng.chance(0.6) {
+      price = price / 10_000 * 10_000 + 9_000;
+    }
+    let sale = rng.weighted(&SALES);
+    let free_shipping = rng.chance(if price >= 50_000 { 0.7 } else { 0.3 });
+    let delivery_fee = if free_shipping {
+      0
+    } else {
+      rng.weighted(&[(3_000, 80), (2_500, 10), (5_000, 10)])
+    };
+
+    let mut size = Map::new();
+    let mut stock = 0;
+    for name in category.sizes {
+      // 일부 사이즈는 품절
+      let count = match (*name, rng.chance(0.1)) {
+        (_, true) => 0,
+        ("FREE", false) => rng.range(5, 100),
+        (_, false) => rng.range(1, 30),
+      };
+      stock += count;
+      size.insert(name.to_string(), json!(count));
+    }
+
+    // 서로 다른 두 문장
+    let first = rng.below(DESCRIPTIONS.len());
+    let second = (first + 1 + rng.below(DESCRIPTIONS.len() - 1)) % DESCRIPTIONS.len();
+
+    let thumbnails: Vec<String> = (0..rng.range(1, 4))
+      .map(|number| image(options.seed, &format!("post-{}-{}", index, number)))
+      .collect();
+
+    rows.push(object(json!({
+      "uuid": uuid,
+      "user_id": seller.uuid,
+      "title": format!("{} {} {} {}", brand, style, item, rng.pick(&COLORS)),
+      "image_src": thumbnails[0],
+      "thumbnail_src": thumbnails,
+      "description": format!(
+        "{} 무드의 {}. {} {}",
+        style,
+        item,
+        DESCRIPTIONS[first],
+        DESCRIPTIONS[second]
+      ),
+      "brand": brand,
+      "category": category.name,
+      "size": size,
+      "price": price,
+      "count_in_stock": stock,
+      "rating": 0,
+      "num_reviews": 0,
+      "sale": sale,
+      "free_shipping": free_shipping,
+      "delivery_fee": delivery_fee,
+      "created_at": time(created),
+      "updated_at": time(created),
+    })));
+    posts.push(FakePost {
+      uuid,
+      sizes: category.sizes.iter().map(|size| size.to_string()).collect(),
+      price,
+      sale,
+      delivery_fee,
+      created,
+    });
+  }
+
+  rows
+}
+
+// 주문 하나에 상품 1~3개, 오래된 주문일수록 배송 완료가 많다
+fn orders(
+  options: &FakeOptions,
+  users: &[FakeUser],
+  posts: &[FakePost],
+  delivered: &mut Vec<DeliveredItem>,
+) -> (Vec<Row>, Vec<Row>) {
+  let mut rng = FakeRng::new(options.seed, "orders");
+  let mut orders = Vec::with_capacity(options.orders);
+  let mut items = Vec::new();
+  if users.is_empty() || posts.is_empty() {
+    return (orders, items);
+  }
+
+  for _ in 0..options.orders {
+    let order_uuid = rng.uuid();
+    let user = rng.below(users.len());
+    let created = -rng.range(DAY, 200 * DAY);
+    let age = -created;
+
+    let mut picked: Vec<usize> = Vec::new();
+    for _ in 0..rng.weighted(&[(1, 70), (2, 22), (3, 8)]) {
+      let post = rng.below(posts.len());
+      if !picked.contains(&post) {
+        picked.push(post);
+      }
+    }
+
+    let mut total_price = 0;
+    let mut delivery_fee = 0;
+    for post_index in picked {
+      let post = &posts[post_index];
+      let uuid = rng.uuid();
+      let quantity = rng.weighted(&[(1, 85), (2, 12), (3, 3)]);
+      let price = effective_price(post.price, post.sale);
+      total_price += price * quantity;
+      delivery_fee = delivery_fee.max(post.delivery_fee);
+
+      let status = if age < 3 * DAY {
+        rng.weighted(&[
+          (ORDER_ITEM_PENDING, 60),
+          (ORDER_ITEM_SHIPPED, 30),
+          (ORDER_ITEM_CANCELLED, 10),
+        ])
+      } else if age < 7 * DAY {
+        rng.weighted(&[
+          (ORDER_ITEM_SHIPPED, 40),
+          (ORDER_ITEM_DELIVERED, 50),
+          (ORDER_ITEM_CANCELLED, 10),
+        ])
+      } else {
+        rng.weighted(&[
+          (ORDER_ITEM_DELIVERED, 85),
+          (ORDER_ITEM_REFUNDED, 7),
+          (ORDER_ITEM_CANCELLED, 8),
+        ])
+      };
+      // 배송 완료와 환불 시각을 직접 적어야 트리거가 now()로 채우지 않는다
+      let delivered_at = created + rng.range(DAY, 3 * DAY);
+      let refunded_at = delivered_at + rng.range(DAY, 4 * DAY);
+
+      let mut item = object(json!({
+        "uuid": uuid,
+        "order_id": order_uuid,
+        "post_id": post.uuid,
+        "size": rng.pick(&post.sizes),
+        "quantity": quantity,
+        "price": price,
+        "status": status,
+        "created_at": time(created),
+        "updated_at": time(created),
+      }));
+      if status == ORDER_ITEM_DELIVERED || status == ORDER_ITEM_REFUNDED {
+        item.insert("delivered_at".to_string(), time(delivered_at));
+        item.insert("updated_at".to_string(), time(delivered_at));
+      }
+      if status == ORDER_ITEM_REFUNDED {
+        item.insert("refunded_at".to_string(), time(refunded_at));
+        item.insert("updated_at".to_string(), time(refunded_at));
+      }
+      if status == ORDER_ITEM_DELIVERED {
+        delivered.push(DeliveredItem {
+          uuid,
+          post: post_index,
+          user,
+          delivered: delivered_at,
+        });
+      }
+      items.push(item);
+    }
+
+    orders.push(object(json!({
+      "uuid": order_uuid,
+      "user_id": users[user].uuid,
+      "total_price": total_price + delivery_fee,
+      "delivery_fee": delivery_fee,
+      "created_at": time(created),
+      "updated_at": time(created),
+    })));
+  }
+
+  (orders, items)
+}
+
+fn review_content(rng: &mut FakeRng, rating: i64) -> String {
+  let reviews: &[&str] = match rating {
+    4..=5 => &GOOD_REVIEWS,
+    3 => &OKAY_REVIEWS,
+    _ => &BAD_REVIEWS,
+  };
+  let first = *rng.pick(reviews);
+  if rng.chance(0.4) {
+    let second = *rng.pick(reviews);
+    if second != first {
+      return format!("{} {}", first, second);
+    }
+  }
+  first.to_string()
+}
+
+// 배송 완료된 주문 상품의 리뷰를 먼저 쓰고, 모자라면 주문 없이 쓴 리뷰로 채운다.
+// 상품당 한 사람이 리뷰 하나만 쓸 수 있다
+fn reviews(
+  options: &FakeOptions,
+  users: &[FakeUser],
+  posts: &[FakePost],
+  delivered: &[DeliveredItem],
+  post_rows: &mut [Row],
+) -> Vec<Row> {
+  let mut rng = FakeRng::new(options.seed, "reviews");
+  let mut rows = Vec::with_capacity(options.reviews);
+  let mut written: HashSet<(usize, usize)> = HashSet::new();
+  let mut ratings = vec![(0i64, 0i64); posts.len()];
+
+  let mut candidates: Vec<(usize, usize, Option<&str>, i64)> = delivered
+    .iter()
+    .filter(|item| item.delivered < -DAY)
+    .map(|item| {
+      (
+        item.post,
+        item.user,
+        Some(item.uuid.as_str()),
+        item.delivered,
+      )
+    })
+    .collect();
+  // 주문 없이 쓴 리뷰, 같은 상품과 사람을 너무 많이 뽑지 않도록 시도 횟수를 제한한다
+  if !users.is_empty() && !posts.is_empty() {
+    for _ in 0..options.reviews.saturating_mul(2) {
+      let post = rng.below(posts.len());
+      candidates.push((post, rng.below(users.len()), None, posts[post].created));
+    }
+  }
+
+  for (post, user, order_item, after) in candidates {
+    if rows.len() >= options.reviews {
+      break;
+    }
+    if !written.insert((post, user)) {
+      continue;
+    }
+    let rating = rng.weighted(&RATINGS);
+    let created = (after + rng.range(DAY, 14 * DAY)).min(0);
+    let photos: Option<Vec<String>> = rng.chance(0.2).then(|| {
+      (0..rng.range(1, 3))
+        .map(|number| image(options.seed, &format!("review-{}-{}", rows.len(), number)))
+        .collect()
+    });
+
+    rows.push(object(json!({
+      "uuid": rng.uuid(),
+      "post_id": posts[post].uuid,
+      "user_id": users[user].uuid,
+      "order_item_id": order_item,
+      "rating": rating,
+      "content": review_content(&mut rng, rating),
+      "photos": photos,
+      "helpful_count": rng.weighted(&[(0, 60), (1, 20), (2, 10), (5, 7), (20, 3)]),
+      "created_at": time(created),
+      "updated_at": time(created),
+    })));
+    ratings[post].0 += rating;
+    ratings[post].1 += 1;
+  }
+
+  // 상품 평점은 리뷰 평균을 소수 첫째 자리까지
+  for (row, (sum, count)) in post_rows.iter_mut().zip(ratings) {
+    if count > 0 {
+      let rating = (sum as f64 / count as f64 * 10.0).round() / 10.0;
+      row.insert("rating".to_string(), json!(rating));
+      row.insert("num_reviews".to_string(), json!(count));
+    }
+  }
+
+  rows
+}
+
+// users, address, posts, orders, order_items, reviews 순서로 외래키가 맞는 row를 만든다
+pub fn generate(options: &FakeOptions) -> Vec<FakeTable> {
+  let mut fake_users = Vec::new();
+  let mut fake_posts = Vec::new();
+  let mut delivered = Vec::new();
+
+  let user_rows = users(options, &mut fake_users);
+  let address_rows = addresses(options, &fake_users);
+  let mut post_rows = posts(options, &fake_users, &mut fake_posts);
+  let (order_rows, item_rows) = orders(options, &fake_users, &fake_posts, &mut delivered);
+  let review_rows = reviews(
+    options,
+    &fake_users,
+    &fake_posts,
+    &delivered,
+    &mut post_rows,
+  );
+
+  vec![
+    FakeTable {
+      table: "users",
+      rows: user_rows,
+    },
+    FakeTable {
+      table: "address",
+      rows: address_rows,
+    },
+    FakeTable {
+      table: "posts",
+      rows: post_rows,
+    },
+    FakeTable {
+      table: "orders",
+      rows: order_rows,
+    },
+    FakeTable {
+      table: "order_items",
+      rows: item_rows,
+    },
+    FakeTable {
+      table: "reviews",
+      rows: review_rows,
+    },
+  ]
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn options(seed: u64, users: usize) -> FakeOptions {
+    FakeOptions {
+      seed,
+      users,
+      posts: 30,
+      orders: 40,
+      reviews: 20,
+    }
+  }
+
+  fn rows<'a>(tables: &'a [FakeTable], table: &str) -> &'a [Row] {
+    &tables.iter().find(|fake| fake.table == table).unwrap().rows
+  }
+
+  fn uuids(rows: &[Row], column: &str) -> HashSet<String> {
+    rows
+      .iter()
+      .filter_map(|row| row[column].as_str())
+      .map(str::to_string)
+      .collect()
+  }
+
+  #[test]
+  fn same_seed_generates_the_same_rows() {
+    let first = generate(&options(7, 10));
+    let second = generate(&options(7, 10));
+    for (a, b) in first.iter().zip(&second) {
+      assert_eq!(a.table, b.table);
+      assert_eq!(a.rows, b.rows, "{}", a.table);
+    }
+
+    let other = generate(&options(8, 10));
+    assert_ne!(rows(&first, "users"), rows(&other, "users"));
+  }
+
+  // 사용자 수를 늘려도 앞쪽 사용자는 그대로다
+  #[test]
+  fn more_users_keep_the_first_ones() {
+    let few = generate(&options(7, 5));
+    let many = generate(&options(7, 12));
+    assert_eq!(rows(&few, "users"), &rows(&many, "users")[..5]);
+  }
+
+  #[test]
+  fn generated_rows_point_at_generated_parents() {
+    let tables = generate(&options(1, 10));
+    let users = uuids(rows(&tables, "users"), "uuid");
+    let posts = uuids(rows(&tables, "posts"), "uuid");
+    let orders = uuids(rows(&tables, "orders"), "uuid");
+    let items = uuids(rows(&tables, "order_items"), "uuid");
+    assert_eq!(users.len(), 10);
+    assert_eq!(posts.len(), 30);
+
+    assert!(uuids(rows(&tables, "posts"), "user_id").is_subset(&users));
+    assert!(uuids(rows(&tables, "orders"), "user_id").is_subset(&users));
+    assert!(uuids(rows(&tables, "order_items"), "order_id").is_subset(&orders));
+    assert!(uuids(rows(&tables, "order_items"), "post_id").is_subset(&posts));
+    assert!(uuids(rows(&tables, "reviews"), "post_id").is_subset(&posts));
+    assert!(uuids(rows(&tables, "reviews"), "order_item_id").is_subset(&items));
+  }
+
+  #[test]
+  fn rng_stays_in_range() {
+    let mut rng = FakeRng::new(3, "test");
+    for _ in 0..1000 {
+      assert!(rng.below(7) < 7);
+      assert!((-2..=2).contains(&rng.range(-2, 2)));
+    }
+    assert_eq!(rng.weighted(&[("only", 1)]), "only");
+    assert_ne!(
+      FakeRng::new(3, "users").next_u64(),
+      FakeRng::new(3, "posts").next_u64()
+    );
+  }
+}
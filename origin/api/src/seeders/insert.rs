@@ -0,0 +1,871 @@
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::postgres::PgDatabaseError;
+use sqlx::{Connection, PgConnection, Postgres, Transaction};
+
+use super::bind::{convert, from_text, placeholder, Bound};
+use super::resolve::{Reference, Resolver};
+use super::schema::{quote_ident, ColumnInfo, PgType, TableSchema};
+use super::source::{Row, RowStream};
+use super::sqlx_seeder::SeedError;
+
+// postgres는 한 문장에 bind 파라미터를 65535개까지 받는다
+const MAX_PARAMETERS: usize = 65_535;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+// 충돌 키가 같은 row가 이미 있으면 update 하거나 그대로 둔다
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpsertConfig {
+  pub on_conflict: Vec<String>,
+  #[serde(default)]
+  pub action: ConflictAction,
+  // 비워둔 uuid를 만들 때 쓰는 컬럼, 없으면 uuid를 뺀 충돌 키를 쓴다
+  #[serde(default)]
+  pub key: Vec<String>,
+}
+
+impl UpsertConfig {
+  pub fn uuid_key(&self) -> Vec<&str> {
+    let columns = if self.key.is_empty() {
+      &self.on_conflict
+    } else {
+      &self.key
+    };
+    columns
+      .iter()
+      .map(String::as_str)
+      .filter(|column| *column != "uuid")
+      .collect()
+  }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictAction {
+  #[default]
+  Update,
+  Nothing,
+}
+
+// 한 번에 보내는 row 수, copy면 upsert 설정이 없는 파일은 COPY FROM STDIN으로 넣는다
+#[derive(Debug, Clone, Copy)]
+pub struct BatchOptions {
+  pub batch_size: usize,
+  pub copy: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SeedStats {
+  pub rows: usize,
+  pub elapsed: Duration,
+}
+
+impl SeedStats {
+  pub fn rows_per_sec(&self) -> f64 {
+    self.rows as f64 / self.elapsed.as_secs_f64().max(0.001)
+  }
+}
+
+// 큰 파일은 1초마다 진행 상황을 찍는다
+struct Progress<'a> {
+  file: &'a str,
+  total: usize,
+  rows: usize,
+  started: Instant,
+  reported: Instant,
+}
+
+impl<'a> Progress<'a> {
+  fn new(file: &'a str, total: usize) -> Self {
+    let now = Instant::now();
+    Progress {
+      file,
+      total,
+      rows: 0,
+      started: now,
+      reported: now,
+    }
+  }
+
+  fn advance(&mut self, rows: usize) {
+    self.rows += rows;
+    if self.reported.elapsed() >= PROGRESS_INTERVAL {
+      self.reported = Instant::now();
+      println!(
+        "⏳ {} {}/{} rows ({:.0} rows/s)",
+        self.file,
+        self.rows,
+        self.total,
+        self.stats().rows_per_sec()
+      );
+    }
+  }
+
+  fn stats(&self) -> SeedStats {
+    SeedStats {
+      rows: self.rows,
+      elapsed: self.started.elapsed(),
+    }
+  }
+}
+
+// csv 셀 하나를 json 값으로 바꾼다, 기본값이 있는 컬럼의 빈 셀은 None으로 빼서
+// 기본값이 들어가게 하고 {"$ref": ...} 셀은 그대로 둔다
+pub fn cell_value(column: &ColumnInfo, text: &str) -> Result<Option<Value>, String> {
+  if text.is_empty() && column.has_default {
+    return Ok(None);
+  }
+  if let Some(reference) = cell_reference(text) {
+    return Ok(Some(reference));
+  }
+  from_text(column, text).map(Some)
+}
+
+// csv 셀은 컬럼 타입을 보고 json 값으로 바꾼 뒤 다른 형식과 같은 변환을 거친다
+fn cells_to_values(
+  schema: &TableSchema,
+  row: Row,
+  file: &str,
+  index: usize,
+) -> Result<Row, SeedError> {
+  let mut values = Row::new();
+
+  for (key, value) in row {
+    match (schema.column(&key), value) {
+      (Some(column), Value::String(text)) => {
+        let value = cell_value(column, &text)
+          .map_err(|message| SeedError::row(file, index, Some(&key), message))?;
+        if let Some(value) = value {
+          values.insert(key, value);
+        }
+      }
+      (_, value) => {
+        values.insert(key, value);
+      }
+    }
+  }
+
+  Ok(values)
+}
+
+fn cell_reference(text: &str) -> Option<Value> {
+  if !text.trim_start().starts_with('{') {
+    return None;
+  }
+  let value = serde_json::from_str(text).ok()?;
+  Reference::from_value(&value).is_some().then_some(value)
+}
+
+// 컬럼 이름은 실제 테이블에 있는 것만 받는다
+fn row_columns<'s>(
+  schema: &'s TableSchema,
+  row: &Row,
+  file: &str,
+  index: usize,
+) -> Result<Vec<&'s ColumnInfo>, SeedError> {
+  row
+    .keys()
+    .map(|field_name| {
+      schema.column(field_name).ok_or_else(|| {
+        SeedError::row(
+          file,
+          index,
+          Some(field_name),
+          format!("table {} has no such column", schema.name),
+        )
+      })
+    })
+    .collect()
+}
+
+// 충돌 키 컬럼은 row에 꼭 있어야 하고, 나머지 row 컬럼만 update 한다
+fn on_conflict_clause(upsert: &UpsertConfig, columns: &[&ColumnInfo]) -> String {
+  let keys: Vec<String> = upsert
+    .on_conflict
+    .iter()
+    .map(|key| quote_ident(key))
+    .collect();
+  let updates: Vec<String> = columns
+    .iter()
+    .filter(|column| !upsert.on_conflict.contains(&column.name))
+    .map(|column| format!("{0} = excluded.{0}", quote_ident(&column.name)))
+    .collect();
+
+  let action = match upsert.action {
+    ConflictAction::Update if !updates.is_empty() => {
+      format!("do update set {}", updates.join(", "))
+    }
+    _ => "do nothing".to_string(),
+  };
+
+  format!(" on conflict ({}) {}", keys.join(", "), action)
+}
+
+fn column_list(columns: &[&ColumnInfo]) -> String {
+  columns
+    .iter()
+    .map(|column| quote_ident(&column.name))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+// insert into t (a, b) values ($1::int8, $2::text), ($3::int8, $4::text) ...
+async fn insert_values(
+  conn: &mut PgConnection,
+  schema: &TableSchema,
+  columns: &[&ColumnInfo],
+  rows: &[Vec<Bound>],
+  upsert: Option<&UpsertConfig>,
+) -> Result<(), sqlx::Error> {
+  let mut n = 0;
+  let tuples: Vec<String> = rows
+    .iter()
+    .map(|_| {
+      let placeholders: Vec<String> = columns
+        .iter()
+        .map(|column| {
+          n += 1;
+          placeholder(column, n)
+        })
+        .collect();
+      format!("({})", placeholders.join(", "))
+    })
+    .collect();
+
+  let mut statement = format!(
+    "insert into {} ({}) values {}",
+    quote_ident(&schema.name),
+    column_list(columns),
+    tuples.join(", ")
+  );
+  if let Some(upsert) = upsert {
+    statement.push_str(&on_conflict_clause(upsert, columns));
+  }
+
+  // null은 text로 보내므로 같은 문장을 캐시해두면 다음 row의 배열이나 숫자가
+  // 처음 준비한 파라미터 타입으로 읽힌다, 문장마다 새로 준비한다
+  let mut query = sqlx::query(&statement).persistent(false);
+  for row in rows {
+    for bound in row {
+      query = bound.clone().bind(query);
+    }
+  }
+
+  query.execute(conn).await?;
+  Ok(())
+}
+
+// 변환을 마친 값을 CSV로 만들어 COPY FROM STDIN으로 보낸다
+async fn copy_values(
+  conn: &mut PgConnection,
+  schema: &TableSchema,
+  columns: &[&ColumnInfo],
+  rows: &[Vec<Bound>],
+) -> Result<(), sqlx::Error> {
+  let statement = format!(
+    "copy {} ({}) from stdin (format csv)",
+    quote_ident(&schema.name),
+    column_list(columns)
+  );
+
+  let mut data = String::new();
+  for row in rows {
+    let fields: Vec<String> = row.iter().map(Bound::copy_field).collect();
+    data.push_str(&fields.join(","));
+    data.push('\n');
+  }
+
+  let mut copy = conn.copy_in_raw(&statement).await?;
+  copy.send(data.into_bytes()).await?;
+  copy.finish().await?;
+  Ok(())
+}
+
+// postgres 에러에 컬럼 정보가 없으면 에러 메시지에 나온 값으로 컬럼을 찾는다
+fn db_error(file: &str, index: usize, row: &Row, err: sqlx::Error) -> SeedError {
+  let Some(pg_err) = err
+    .as_database_error()
+    .and_then(|db_err| db_err.try_downcast_ref::<PgDatabaseError>())
+  else {
+    return SeedError::row(file, index, None, err.to_string());
+  };
+
+  let column = pg_err.column().map(str::to_string).or_else(|| {
+    row
+      .iter()
+      .find(|(_, value)| {
+        value
+          .as_str()
+          .is_some_and(|value| pg_err.message().contains(&format!("\"{}\"", value)))
+      })
+      .map(|(key, _)| key.clone())
+  });
+
+  let mut message = pg_err.message().to_string();
+  if let Some(detail) = pg_err.detail() {
+    message.push_str(&format!(" ({})", detail));
+  }
+
+  SeedError::row(file, index, column.as_deref(), message)
+}
+
+// 컬럼 구성이 같은 row 묶음을 한 문장으로 넣는다,
+// 실패하면 어느 row 때문인지 찾으려고 한 row씩 다시 넣어본다 (파일 트랜잭션이 롤백되므로 남지 않는다)
+async fn write_batch(
+  tx: &mut Transaction<'_, Postgres>,
+  schema: &TableSchema,
+  batch: &[(usize, Row)],
+  upsert: Option<&UpsertConfig>,
+  copy: bool,
+  file: &str,
+) -> Result<(), SeedError> {
+  let (first_index, first) = &batch[0];
+  let columns = row_columns(schema, first, file, *first_index)?;
+
+  if let Some(missing) = upsert.and_then(|upsert| {
+    upsert
+      .on_conflict
+      .iter()
+      .find(|key| !first.contains_key(*key))
+  }) {
+    return Err(SeedError::row(
+      file,
+      *first_index,
+      Some(missing),
+      "conflict key column is missing from the row",
+    ));
+  }
+
+  let values = batch
+    .iter()
+    .map(|(index, row)| {
+      columns
+        .iter()
+        .map(|column| {
+          convert(column, &row[&column.name])
+            .map_err(|message| SeedError::row(file, *index, Some(&column.name), message))
+        })
+        .collect::<Result<Vec<Bound>, SeedError>>()
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+
+  let to_seed_error = |err: sqlx::Error| SeedError::file(file, err.to_string());
+
+  let mut savepoint = Connection::begin(&mut **tx).await.map_err(to_seed_error)?;
+  let result = if copy {
+    copy_values(&mut savepoint, schema, &columns, &values).await
+  } else {
+    insert_values(&mut savepoint, schema, &columns, &values, upsert).await
+  };
+
+  let err = match result {
+    Ok(()) => return savepoint.commit().await.map_err(to_seed_error),
+    Err(err) => err,
+  };
+  savepoint.rollback().await.map_err(to_seed_error)?;
+
+  if batch.len() == 1 {
+    return Err(db_error(file, *first_index, first, err));
+  }
+
+  // 한 줄씩 다시 넣어서 실패한 row를 찾는다, 모두 들어가면 배치만의 문제였다
+  // (같은 on_conflict 키가 배치에 두 번 있으면 ON CONFLICT DO UPDATE가 한 문장에서 같은 row를 두 번 바꿀 수 없다)
+  for ((index, row), bound) in batch.iter().zip(&values) {
+    let mut savepoint = Connection::begin(&mut **tx).await.map_err(to_seed_error)?;
+    match insert_values(
+      &mut savepoint,
+      schema,
+      &columns,
+      std::slice::from_ref(bound),
+      upsert,
+    )
+    .await
+    {
+      Ok(()) => savepoint.commit().await.map_err(to_seed_error)?,
+      Err(row_err) => return Err(db_error(file, *index, row, row_err)),
+    }
+  }
+  Ok(())
+}
+
+fn batch_limit(options: &BatchOptions, copy: bool, columns: usize) -> usize {
+  let limit = options.batch_size.max(1);
+  if copy {
+    return limit;
+  }
+  limit.min(MAX_PARAMETERS / columns.max(1)).max(1)
+}
+
+// 넣을 row와 진행 상황에 쓰는 전체 row 수
+pub struct SeedInput {
+  pub rows: RowStream,
+  pub total: usize,
+  pub text_cells: bool,
+}
+
+// uuid 컬럼을 비워두면 채운다, upsert 키가 다른 컬럼이면 이미 있는 row의 uuid를 바꾸지 않도록
+// 채우지 않고 테이블 기본값에 맡긴다
+pub fn needs_uuid(schema: &TableSchema, row: &Row, upsert: Option<&UpsertConfig>) -> bool {
+  let is_uuid = schema
+    .column("uuid")
+    .is_some_and(|column| column.pg_type == PgType::Uuid);
+  let upsert_on_uuid =
+    upsert.is_none_or(|upsert| upsert.on_conflict.iter().any(|key| key == "uuid"));
+  is_uuid && upsert_on_uuid && !row.contains_key("uuid")
+}
+
+struct Batch<'a> {
+  schema: &'a TableSchema,
+  upsert: Option<&'a UpsertConfig>,
+  copy: bool,
+  file: &'a str,
+  rows: Vec<(usize, Row)>,
+}
+
+impl Batch<'_> {
+  async fn flush(
+    &mut self,
+    tx: &mut Transaction<'_, Postgres>,
+    progress: &mut Progress<'_>,
+  ) -> Result<(), SeedError> {
+    if self.rows.is_empty() {
+      return Ok(());
+    }
+    write_batch(
+      tx,
+      self.schema,
+      &self.rows,
+      self.upsert,
+      self.copy,
+      self.file,
+    )
+    .await?;
+    progress.advance(self.rows.len());
+    self.rows.clear();
+    Ok(())
+  }
+}
+
+// row를 읽는 대로 batch_size씩 묶어서 넣는다, 스트리밍 형식도 한 묶음만 메모리에 둔다
+pub async fn seed_rows(
+  tx: &mut Transaction<'_, Postgres>,
+  schema: &TableSchema,
+  input: SeedInput,
+  file: &str,
+  upsert: Option<&UpsertConfig>,
+  options: &BatchOptions,
+  resolver: &mut Resolver<'_>,
+) -> Result<SeedStats, SeedError> {
+  // ON CONFLICT는 COPY로 할 수 없어서 upsert 파일은 insert로 넣는다
+  let copy = options.copy && upsert.is_none();
+  let mut progress = Progress::new(file, input.total);
+  let mut batch = Batch {
+    schema,
+    upsert,
+    copy,
+    file,
+    rows: Vec::new(),
+  };
+
+  for (index, row) in input.rows.enumerate() {
+    let mut row = row?;
+    if input.text_cells {
+      row = cells_to_values(schema, row, file, index)?;
+    }
+
+    // $ref를 바꾸기 전의 값으로 만들어야 다시 넣을 때도 같은 uuid가 나온다
+    if needs_uuid(schema, &row, upsert) {
+      let uuid = resolver.generated_uuid(schema, &row, upsert, file, index)?;
+      row.insert("uuid".to_string(), uuid);
+    }
+    if Resolver::refers_to_self(schema, &row) {
+      batch.flush(tx, &mut progress).await?;
+    }
+    resolver
+      .resolve_row(tx, schema, &mut row, file, index)
+      .await?;
+
+    if let Some((_, first)) = batch.rows.first() {
+      let full = batch.rows.len() >= batch_limit(options, copy, first.len());
+      if full || !first.keys().eq(row.keys()) {
+        batch.flush(tx, &mut progress).await?;
+      }
+    }
+    batch.rows.push((index, row));
+  }
+
+  batch.flush(tx, &mut progress).await?;
+
+  Ok(progress.stats())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::schema::{load_table, PgType};
+  use super::*;
+  use serde_json::json;
+  use sqlx::{Executor, PgPool};
+
+  fn column(name: &str, pg_type: PgType) -> ColumnInfo {
+    ColumnInfo {
+      name: name.to_string(),
+      pg_type,
+      nullable: true,
+      has_default: false,
+      max_length: None,
+      serial: false,
+      generated: false,
+    }
+  }
+
+  fn upsert(keys: &[&str], action: ConflictAction) -> UpsertConfig {
+    UpsertConfig {
+      on_conflict: keys.iter().map(|key| key.to_string()).collect(),
+      action,
+      key: Vec::new(),
+    }
+  }
+
+  fn options(batch_size: usize, copy: bool) -> BatchOptions {
+    BatchOptions { batch_size, copy }
+  }
+
+  fn input(rows: serde_json::Value) -> SeedInput {
+    let rows: Vec<Row> = serde_json::from_value(rows).unwrap();
+    SeedInput {
+      total: rows.len(),
+      rows: Box::new(rows.into_iter().map(Ok)),
+      text_cells: false,
+    }
+  }
+
+  async fn items_table(pool: &PgPool, definition: &str) -> TableSchema {
+    pool
+      .execute(format!("create table items ({})", definition).as_str())
+      .await
+      .unwrap();
+    let mut conn = pool.acquire().await.unwrap();
+    load_table(&mut conn, "items").await.unwrap().unwrap()
+  }
+
+  #[test]
+  fn on_conflict_updates_the_other_columns() {
+    let email = column("email", PgType::Text);
+    let name = column("name", PgType::Text);
+    let is_admin = column("is_admin", PgType::Bool);
+
+    assert_eq!(
+      on_conflict_clause(
+        &upsert(&["email"], ConflictAction::Update),
+        &[&email, &name, &is_admin]
+      ),
+      " on conflict (\"email\") do update set \"name\" = excluded.\"name\", \"is_admin\" = excluded.\"is_admin\""
+    );
+    assert_eq!(
+      on_conflict_clause(
+        &upsert(&["email"], ConflictAction::Nothing),
+        &[&email, &name]
+      ),
+      " on conflict (\"email\") do nothing"
+    );
+    // 키 말고 다른 컬럼이 없으면 바꿀 것이 없다
+    assert_eq!(
+      on_conflict_clause(&upsert(&["email"], ConflictAction::Update), &[&email]),
+      " on conflict (\"email\") do nothing"
+    );
+  }
+
+  #[test]
+  fn unknown_columns_are_reported() {
+    let schema = TableSchema {
+      name: "posts".to_string(),
+      columns: vec![column("title", PgType::Text)],
+    };
+    let row: Row = serde_json::from_value(json!({"titel": "a"})).unwrap();
+    let err = row_columns(&schema, &row, "posts.json", 2).unwrap_err();
+    assert_eq!(err.row, Some(2));
+    assert_eq!(err.column.as_deref(), Some("titel"));
+  }
+
+  #[test]
+  fn batch_limit_respects_the_parameter_limit() {
+    assert_eq!(batch_limit(&options(500, false), false, 10), 500);
+    assert_eq!(batch_limit(&options(10_000, false), false, 20), 3_276);
+    assert_eq!(batch_limit(&options(10_000, false), true, 20), 10_000);
+    assert_eq!(batch_limit(&options(0, false), false, 0), 1);
+    assert_eq!(batch_limit(&options(500, false), false, 100_000), 1);
+  }
+
+  #[sqlx::test(migrations = false)]
+  async fn upsert_updates_existing_rows(pool: PgPool) {
+    let schema = items_table(&pool, "id serial primary key, code text unique, name text").await;
+    let config = upsert(&["code"], ConflictAction::Update);
+
+    for rows in [
+      json!([{"code": "a", "name": "old"}]),
+      json!([{"code": "a", "name": "new"}, {"code": "b", "name": "b"}]),
+    ] {
+      let mut tx = pool.begin().await.unwrap();
+      seed_rows(
+        &mut tx,
+        &schema,
+        input(rows),
+        "items.json",
+        Some(&config),
+        &options(500, false),
+        &mut Resolver::new("dev", &[], None),
+      )
+      .await
+      .unwrap();
+      tx.commit().await.unwrap();
+    }
+
+    let names: Vec<String> = sqlx::query_scalar("select name from items order by code")
+      .fetch_all(&pool)
+      .await
+      .unwrap();
+    assert_eq!(names, ["new", "b"]);
+  }
+
+  // uuid를 비워둔 파일도 key가 같으면 다시 넣을 때 같은 row를 고친다
+  #[sqlx::test(migrations = false)]
+  async fn generated_uuids_upsert_the_same_rows(pool: PgPool) {
+    let schema = items_table(
+      &pool,
+      "id serial primary key, uuid uuid not null unique default gen_random_uuid(), title text, price bigint",
+    )
+    .await;
+    let config = UpsertConfig {
+      key: vec!["title".to_string()],
+      ..upsert(&["uuid"], ConflictAction::Update)
+    };
+
+    for rows in [
+      json!([{"title": "a", "price": 1}, {"title": "b", "price": 2}]),
+      json!([{"title": "b", "price": 20}, {"title": "a", "price": 10}]),
+    ] {
+      let mut tx = pool.begin().await.unwrap();
+      seed_rows(
+        &mut tx,
+        &schema,
+        input(rows),
+        "items.json",
+        Some(&config),
+        &options(500, false),
+        &mut Resolver::new("dev", &[], None),
+      )
+      .await
+      .unwrap();
+      tx.commit().await.unwrap();
+    }
+
+    let prices: Vec<i64> = sqlx::query_scalar("select price from items order by title")
+      .fetch_all(&pool)
+      .await
+      .unwrap();
+    assert_eq!(prices, [10, 20]);
+  }
+
+  #[sqlx::test(migrations = false)]
+  async fn missing_conflict_key_is_reported(pool: PgPool) {
+    let schema = items_table(&pool, "id serial primary key, code text unique, name text").await;
+    let config = upsert(&["code"], ConflictAction::Update);
+
+    let mut tx = pool.begin().await.unwrap();
+    let err = seed_rows(
+      &mut tx,
+      &schema,
+      input(json!([{"name": "a"}])),
+      "items.json",
+      Some(&config),
+      &options(500, false),
+      &mut Resolver::new("dev", &[], None),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.row, Some(0));
+    assert_eq!(err.column.as_deref(), Some("code"));
+  }
+
+  // 배치가 실패하면 한 줄씩 다시 넣어서 실패한 row를 알려준다
+  #[sqlx::test(migrations = false)]
+  async fn failing_batch_reports_the_row(pool: PgPool) {
+    let schema = items_table(&pool, "id serial primary key, code text unique").await;
+
+    let mut tx = pool.begin().await.unwrap();
+    let err = seed_rows(
+      &mut tx,
+      &schema,
+      input(json!([{"code": "a"}, {"code": "b"}, {"code": "a"}, {"code": "c"}])),
+      "items.json",
+      None,
+      &options(500, false),
+      &mut Resolver::new("dev", &[], None),
+    )
+    .await
+    .unwrap_err();
+    tx.rollback().await.unwrap();
+
+    assert_eq!(err.row, Some(2));
+    assert!(err.message.contains("Key (code)=(a)"));
+    let count: i64 = sqlx::query_scalar("select count(*) from items")
+      .fetch_one(&pool)
+      .await
+      .unwrap();
+    assert_eq!(count, 0);
+  }
+
+  // 같은 키가 한 배치에 두 번 있으면 한 문장으로는 upsert 할 수 없어서 한 줄씩 넣는다
+  #[sqlx::test(migrations = false)]
+  async fn duplicate_keys_in_a_batch_fall_back_to_rows(pool: PgPool) {
+    let schema = items_table(&pool, "id serial primary key, code text unique, name text").await;
+    let config = upsert(&["code"], ConflictAction::Update);
+
+    let mut tx = pool.begin().await.unwrap();
+    let stats = seed_rows(
+      &mut tx,
+      &schema,
+      input(json!([{"code": "a", "name": "first"}, {"code": "a", "name": "second"}])),
+      "items.json",
+      Some(&config),
+      &options(500, false),
+      &mut Resolver::new("dev", &[], None),
+    )
+    .await
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    assert_eq!(stats.rows, 2);
+    let names: Vec<String> = sqlx::query_scalar("select name from items")
+      .fetch_all(&pool)
+      .await
+      .unwrap();
+    assert_eq!(names, ["second"]);
+  }
+
+  #[sqlx::test(migrations = false)]
+  async fn copy_and_small_batches_insert_every_row(pool: PgPool) {
+    let schema = items_table(
+      &pool,
+      "id serial primary key, name text, tags text[], size jsonb, price bigint",
+    )
+    .await;
+    let rows = json!([
+      {"name": "a,\"b\"", "tags": ["x", "y z"], "size": {"s": 1}, "price": 1},
+      {"name": null, "tags": [], "size": null, "price": 2},
+      {"name": "c", "price": 3}
+    ]);
+
+    for copy in [true, false] {
+      let mut tx = pool.begin().await.unwrap();
+      let stats = seed_rows(
+        &mut tx,
+        &schema,
+        input(rows.clone()),
+        "items.json",
+        None,
+        &options(2, copy),
+        &mut Resolver::new("dev", &[], None),
+      )
+      .await
+      .unwrap();
+      tx.commit().await.unwrap();
+      assert_eq!(stats.rows, 3);
+    }
+
+    type Item = (Option<String>, Option<Vec<String>>, Option<Value>);
+    let stored: Vec<Item> =
+      sqlx::query_as("select name, tags, size from items order by id limit 2")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+      stored,
+      [
+        (
+          Some("a,\"b\"".to_string()),
+          Some(vec!["x".to_string(), "y z".to_string()]),
+          Some(json!({"s": 1}))
+        ),
+        (None, Some(vec![]), None),
+      ]
+    );
+    let count: i64 = sqlx::query_scalar("select count(*) from items")
+      .fetch_one(&pool)
+      .await
+      .unwrap();
+    assert_eq!(count, 6);
+  }
+
+  // csv 셀은 컬럼 타입을 보고 읽고, 기본값이 있는 컬럼의 빈 셀은 기본값이 된다
+  #[sqlx::test(migrations = false)]
+  async fn csv_cells_follow_column_types(pool: PgPool) {
+    let schema = items_table(
+      &pool,
+      "id serial primary key,
+      name text not null,
+      note text,
+      price bigint not null default 0,
+      tags text[],
+      size jsonb",
+    )
+    .await;
+    let rows = json!([
+      {"name": "a", "note": "", "price": "", "tags": "{x,\"y,z\"}", "size": "{\"s\": 1}"},
+      {"name": "b", "note": "memo", "price": "30", "tags": "[]", "size": ""}
+    ]);
+    let input = SeedInput {
+      text_cells: true,
+      ..input(rows)
+    };
+
+    let mut tx = pool.begin().await.unwrap();
+    seed_rows(
+      &mut tx,
+      &schema,
+      input,
+      "items.csv",
+      None,
+      &options(500, false),
+      &mut Resolver::new("dev", &[], None),
+    )
+    .await
+    .unwrap();
+    tx.commit().await.unwrap();
+
+    type Item = (
+      String,
+      Option<String>,
+      i64,
+      Option<Vec<String>>,
+      Option<Value>,
+    );
+    let stored: Vec<Item> =
+      sqlx::query_as("select name, note, price, tags, size from items order by name")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+      stored,
+      [
+        (
+          "a".to_string(),
+          None,
+          0,
+          Some(vec!["x".to_string(), "y,z".to_string()]),
+          Some(json!({"s": 1}))
+        ),
+        (
+          "b".to_string(),
+          Some("memo".to_string()),
+          30,
+          Some(vec![]),
+          None
+        ),
+      ]
+    );
+  }
+}
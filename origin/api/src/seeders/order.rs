@@ -0,0 +1,240 @@
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use super::schema::ForeignKey;
+
+// task 폴더의 파일 하나, 테이블 이름은 파일 이름을 따른다
+#[derive(Debug, Clone)]
+pub struct SeedFile {
+  pub path: PathBuf,
+  pub file: String,
+  pub table: String,
+  // 먼저 들어가야 하는 파일과 그 이유가 되는 외래키
+  pub parents: Vec<(String, ForeignKey)>,
+}
+
+impl SeedFile {
+  pub fn new(path: PathBuf) -> Self {
+    let file = path
+      .file_name()
+      .map(|name| name.to_string_lossy().to_string())
+      .unwrap_or_default();
+    let table = path
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().to_string())
+      .unwrap_or_default();
+
+    SeedFile {
+      path,
+      file,
+      table,
+      parents: Vec::new(),
+    }
+  }
+}
+
+// 순환 때문에 넣을 수 없는 파일과 그 이유
+#[derive(Debug)]
+pub struct CycleError {
+  pub file: String,
+  pub message: String,
+}
+
+#[derive(Debug)]
+pub struct SeedOrder {
+  pub files: Vec<SeedFile>,
+  // 순환 때문에 순서를 정할 수 없는 파일, 이유는 cycles에 있다
+  pub blocked: Vec<SeedFile>,
+  pub cycles: Vec<CycleError>,
+}
+
+// 외래키로 부모 테이블 파일이 먼저 오도록 정렬한다, 순서가 상관없는 파일끼리는 이름순
+pub fn order_files(mut files: Vec<SeedFile>, foreign_keys: &[ForeignKey]) -> SeedOrder {
+  files.sort_by(|a, b| a.file.cmp(&b.file));
+
+  // 자기 자신을 가리키는 외래키는 파일 안의 row 순서로 해결한다
+  let mut deps: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); files.len()];
+  for index in 0..files.len() {
+    let mut parents = Vec::new();
+    for foreign_key in foreign_keys
+      .iter()
+      .filter(|fk| fk.table == files[index].table && fk.parent_table != fk.table)
+    {
+      for (parent, parent_file) in files.iter().enumerate() {
+        if parent_file.table == foreign_key.parent_table {
+          deps[index].insert(parent);
+          parents.push((parent_file.file.clone(), foreign_key.clone()));
+        }
+      }
+    }
+    files[index].parents = parents;
+  }
+
+  let mut done = vec![false; files.len()];
+  let mut ordered = Vec::new();
+
+  loop {
+    let ready = (0..files.len())
+      .find(|&index| !done[index] && deps[index].iter().all(|&parent| done[parent]));
+    let Some(index) = ready else { break };
+    done[index] = true;
+    ordered.push(index);
+  }
+
+  // 남은 파일은 순환에 들어있거나 순환에 걸린 파일에 의존한다
+  let mut cycles = Vec::new();
+  for index in (0..files.len()).filter(|&index| !done[index]) {
+    let cycle = find_cycle(index, &deps, &done);
+    let message = if cycle.first() == Some(&index) {
+      let path: Vec<&str> = cycle
+        .iter()
+        .chain(cycle.first())
+        .map(|&i| files[i].file.as_str())
+        .collect();
+      format!("foreign key cycle: {}", path.join(" → "))
+    } else {
+      let parent = deps[index]
+        .iter()
+        .find(|&&parent| !done[parent])
+        .map(|&parent| files[parent].file.as_str())
+        .unwrap_or_default();
+      format!("blocked by {} (foreign key cycle)", parent)
+    };
+    cycles.push(CycleError {
+      file: files[index].file.clone(),
+      message,
+    });
+  }
+
+  // 정렬된 파일을 꺼내고 남은 파일이 순환에 걸린 파일이다
+  let mut slots: Vec<Option<SeedFile>> = files.into_iter().map(Some).collect();
+  let files = ordered
+    .into_iter()
+    .filter_map(|index| slots[index].take())
+    .collect();
+
+  SeedOrder {
+    files,
+    blocked: slots.into_iter().flatten().collect(),
+    cycles,
+  }
+}
+
+// 남은 파일은 모두 남은 부모가 있으므로 부모를 따라가면 반드시 순환을 만난다,
+// 시작한 파일이 순환 안에 있으면 그 파일부터 시작하는 순환을 돌려준다
+fn find_cycle(start: usize, deps: &[BTreeSet<usize>], done: &[bool]) -> Vec<usize> {
+  let mut path = vec![start];
+  let mut current = start;
+
+  loop {
+    let Some(&next) = deps[current].iter().find(|&&parent| !done[parent]) else {
+      return Vec::new();
+    };
+    if let Some(position) = path.iter().position(|&index| index == next) {
+      return path.split_off(position);
+    }
+    path.push(next);
+    current = next;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn file(name: &str) -> SeedFile {
+    SeedFile::new(PathBuf::from(format!("task/{}.json", name)))
+  }
+
+  fn fk(table: &str, parent_table: &str) -> ForeignKey {
+    ForeignKey {
+      table: table.to_string(),
+      columns: "parent_id".to_string(),
+      parent_table: parent_table.to_string(),
+      parent_columns: "uuid".to_string(),
+    }
+  }
+
+  fn names(files: &[SeedFile]) -> Vec<&str> {
+    files.iter().map(|file| file.table.as_str()).collect()
+  }
+
+  #[test]
+  fn parents_come_first() {
+    let files = vec![
+      file("reviews"),
+      file("posts"),
+      file("users"),
+      file("orders"),
+    ];
+    let foreign_keys = [
+      fk("posts", "users"),
+      fk("reviews", "posts"),
+      fk("reviews", "users"),
+      fk("orders", "users"),
+    ];
+
+    let order = order_files(files, &foreign_keys);
+    assert_eq!(names(&order.files), ["users", "orders", "posts", "reviews"]);
+    assert!(order.blocked.is_empty());
+    assert!(order.cycles.is_empty());
+
+    let reviews = &order.files[3];
+    assert_eq!(reviews.parents.len(), 2);
+    assert!(reviews
+      .parents
+      .iter()
+      .any(|(parent, _)| parent == "users.json"));
+  }
+
+  #[test]
+  fn self_reference_and_missing_parent_are_ignored() {
+    let files = vec![file("categories"), file("brands")];
+    let foreign_keys = [fk("categories", "categories"), fk("brands", "sellers")];
+
+    let order = order_files(files, &foreign_keys);
+    assert_eq!(names(&order.files), ["brands", "categories"]);
+    assert!(order.files.iter().all(|file| file.parents.is_empty()));
+  }
+
+  #[test]
+  fn cycles_are_reported_and_dependents_blocked() {
+    let files = vec![file("a"), file("b"), file("c"), file("users")];
+    let foreign_keys = [fk("a", "b"), fk("b", "a"), fk("c", "a")];
+
+    let order = order_files(files, &foreign_keys);
+    assert_eq!(names(&order.files), ["users"]);
+    assert_eq!(names(&order.blocked), ["a", "b", "c"]);
+
+    let message = |name: &str| {
+      order
+        .cycles
+        .iter()
+        .find(|cycle| cycle.file == name)
+        .map(|cycle| cycle.message.as_str())
+        .unwrap()
+    };
+    assert_eq!(
+      message("a.json"),
+      "foreign key cycle: a.json → b.json → a.json"
+    );
+    assert_eq!(
+      message("b.json"),
+      "foreign key cycle: b.json → a.json → b.json"
+    );
+    assert_eq!(message("c.json"), "blocked by a.json (foreign key cycle)");
+  }
+
+  #[test]
+  fn find_cycle_starts_at_the_file_in_the_cycle() {
+    // 0 → 1 → 2 → 1
+    let deps = vec![
+      BTreeSet::from([1]),
+      BTreeSet::from([2]),
+      BTreeSet::from([1]),
+    ];
+    let done = [false; 3];
+    assert_eq!(find_cycle(1, &deps, &done), vec![1, 2]);
+    assert_eq!(find_cycle(0, &deps, &done), vec![1, 2]);
+  }
+}
@@ -0,0 +1,475 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use sqlx::PgConnection;
+
+use super::insert::UpsertConfig;
+use super::schema::{quote_ident, ForeignKey, TableSchema};
+use super::source::Row;
+use super::sqlx_seeder::SeedError;
+use super::transform::{TableTransforms, Transformer};
+
+const REF_KEY: &str = "$ref";
+
+// {"$ref": "users.email:admin@example.com"}, users 테이블에서 email이 admin@example.com인 row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Reference {
+  pub table: String,
+  pub column: String,
+  pub value: String,
+}
+
+impl Reference {
+  // $ref 객체가 아니면 None
+  pub fn from_value(value: &Value) -> Option<Result<Reference, String>> {
+    let Value::Object(object) = value else {
+      return None;
+    };
+    let target = object.get(REF_KEY)?;
+
+    let reference = target
+      .as_str()
+      .filter(|_| object.len() == 1)
+      .and_then(|target| {
+        let (key, value) = target.split_once(':')?;
+        let (table, column) = key.split_once('.')?;
+        (!table.is_empty() && !column.is_empty()).then(|| Reference {
+          table: table.to_string(),
+          column: column.to_string(),
+          value: value.to_string(),
+        })
+      })
+      .ok_or_else(|| {
+        format!(
+          "invalid $ref {}, expected {{\"$ref\": \"table.column:value\"}}",
+          target
+        )
+      });
+    Some(reference)
+  }
+}
+
+// row에서 참조하는 테이블, 순서를 정할 때 외래키처럼 쓴다
+pub fn referenced_tables(table: &str, row: &Row) -> Vec<ForeignKey> {
+  row
+    .iter()
+    .filter_map(|(field, value)| {
+      let reference = Reference::from_value(value)?.ok()?;
+      Some(ForeignKey {
+        table: table.to_string(),
+        columns: field.clone(),
+        parent_table: reference.table,
+        parent_columns: reference.column,
+      })
+    })
+    .collect()
+}
+
+// 파일 하나를 넣는 동안 $ref를 실제 키로 바꾸고 컬럼 변환을 거친 뒤 빠진 uuid를 채운다,
+// 찾은 값은 파일 트랜잭션 안에서만 쓰도록 파일마다 새로 만든다
+pub struct Resolver<'a> {
+  set: &'a str,
+  foreign_keys: &'a [ForeignKey],
+  cache: HashMap<(String, Reference), Value>,
+  // 생성한 uuid의 key 값과 처음 나온 row
+  keys: HashMap<String, usize>,
+  // key가 없을 때 내용이 같은 row가 앞에 몇 개 있었는지, 내용 hash별로 센다
+  seen: HashMap<Vec<u8>, usize>,
+  warned: bool,
+  transformer: Transformer<'a>,
+}
+
+impl<'a> Resolver<'a> {
+  pub fn new(
+    set: &'a str,
+    foreign_keys: &'a [ForeignKey],
+    transforms: Option<&'a TableTransforms>,
+  ) -> Self {
+    Resolver {
+      set,
+      foreign_keys,
+      cache: HashMap::new(),
+      keys: HashMap::new(),
+      seen: HashMap::new(),
+      warned: false,
+      transformer: Transformer::new(transforms),
+    }
+  }
+
+  // 같은 테이블을 가리키는 참조가 있는지, 앞 row가 먼저 들어가 있어야 찾을 수 있다
+  pub fn refers_to_self(schema: &TableSchema, row: &Row) -> bool {
+    row.values().any(|value| {
+      matches!(Reference::from_value(value), Some(Ok(reference)) if reference.table == schema.name)
+    })
+  }
+
+  // 외래키가 있으면 그 외래키가 가리키는 컬럼, 없으면 uuid 컬럼 값을 넣는다
+  fn target_column(&self, schema: &TableSchema, field: &str, reference: &Reference) -> String {
+    self
+      .foreign_keys
+      .iter()
+      .find(|fk| {
+        fk.table == schema.name && fk.columns == field && fk.parent_table == reference.table
+      })
+      .map(|fk| fk.parent_columns.clone())
+      .unwrap_or_else(|| "uuid".to_string())
+  }
+
+  async fn lookup(
+    &mut self,
+    conn: &mut PgConnection,
+    target: String,
+    reference: Reference,
+  ) -> Result<Value, String> {
+    let key = (target, reference);
+    if let Some(value) = self.cache.get(&key) {
+      return Ok(value.clone());
+    }
+    let (target, reference) = &key;
+
+    let statement = format!(
+      "select to_jsonb(t.{}) from {} t where t.{}::text = $1 limit 2",
+      quote_ident(target),
+      quote_ident(&reference.table),
+      quote_ident(&reference.column)
+    );
+    let found: Vec<Value> = sqlx::query_scalar(&statement)
+      .bind(&reference.value)
+      .fetch_all(conn)
+      .await
+      .map_err(|err| format!("$ref {}.{}: {}", reference.table, reference.column, err))?;
+
+    let value = match found.as_slice() {
+      [value] => value.clone(),
+      [] => {
+        return Err(format!(
+          "$ref found no {} row with {} = {:?}",
+          reference.table, reference.column, reference.value
+        ))
+      }
+      _ => {
+        return Err(format!(
+          "$ref matched more than one {} row with {} = {:?}",
+          reference.table, reference.column, reference.value
+        ))
+      }
+    };
+
+    self.cache.insert(key, value.clone());
+    Ok(value)
+  }
+
+  // $ref 값을 참조한 row의 키로 바꾸고, 바꾼 값으로 컬럼 변환을 한다
+  pub async fn resolve_row(
+    &mut self,
+    conn: &mut PgConnection,
+    schema: &TableSchema,
+    row: &mut Row,
+    file: &str,
+    index: usize,
+  ) -> Result<(), SeedError> {
+    for (field, value) in row.iter_mut() {
+      let Some(reference) = Reference::from_value(value) else {
+        continue;
+      };
+      let resolved = match reference {
+        Ok(reference) => {
+          let target = self.target_column(schema, field, &reference);
+          self.lookup(&mut *conn, target, reference).await
+        }
+        Err(message) => Err(message),
+      };
+      *value = resolved.map_err(|message| SeedError::row(file, index, Some(field), message))?;
+    }
+
+    self.transformer.apply(schema, row, file, index)
+  }
+
+  // 세트, 테이블, key 컬럼 값으로 만든 uuid, 다시 넣어도 같은 uuid가 나와서 upsert 키로 쓸 수 있다.
+  // key가 없으면 uuid를 뺀 row 내용과 같은 내용의 몇 번째 row인지로 만들어서, 내용을 고치면 다른 uuid가 된다
+  pub fn generated_uuid(
+    &mut self,
+    schema: &TableSchema,
+    row: &Row,
+    upsert: Option<&UpsertConfig>,
+    file: &str,
+    index: usize,
+  ) -> Result<Value, SeedError> {
+    let key = upsert.map(UpsertConfig::uuid_key).unwrap_or_default();
+
+    let seed = if key.is_empty() {
+      if !self.warned {
+        self.warned = true;
+        println!(
+          "⚠️ {} has no key for generated uuids, set key in [seeders.tables.{}] so edited rows keep their uuid",
+          file, schema.name
+        );
+      }
+      let contents: BTreeMap<&String, &Value> =
+        row.iter().filter(|(column, _)| *column != "uuid").collect();
+      let contents = serde_json::to_string(&contents).unwrap_or_default();
+      let seen = self
+        .seen
+        .entry(Sha256::digest(&contents).to_vec())
+        .or_insert(0);
+      *seen += 1;
+      format!("{}/{}#{}#{}", self.set, schema.name, *seen - 1, contents)
+    } else {
+      let mut values = Vec::new();
+      for column in &key {
+        let value = row.get(*column).ok_or_else(|| {
+          SeedError::row(
+            file,
+            index,
+            Some(column),
+            "uuid key column is missing from the row",
+          )
+        })?;
+        values.push((*column, value));
+      }
+      let values = serde_json::to_string(&values).unwrap_or_default();
+      if let Some(first) = self.keys.insert(values.clone(), index) {
+        return Err(SeedError::row(
+          file,
+          index,
+          key.first().copied(),
+          format!("uuid key {} is the same as row {}", values, first),
+        ));
+      }
+      format!("{}/{}#{}", self.set, schema.name, values)
+    };
+
+    let hash = Sha256::digest(seed);
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&hash[..16]);
+    Ok(Value::String(
+      uuid::Builder::from_random_bytes(bytes)
+        .into_uuid()
+        .to_string(),
+    ))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+  use sqlx::{Executor, PgPool};
+
+  use super::super::insert::ConflictAction;
+  use super::super::schema::{load_foreign_keys, load_table, ColumnInfo, PgType};
+  use super::*;
+
+  fn row(value: Value) -> Row {
+    value.as_object().unwrap().clone()
+  }
+
+  fn posts() -> TableSchema {
+    TableSchema {
+      name: "posts".to_string(),
+      columns: vec![ColumnInfo {
+        name: "uuid".to_string(),
+        pg_type: PgType::Uuid,
+        nullable: false,
+        has_default: true,
+        max_length: None,
+        serial: false,
+        generated: false,
+      }],
+    }
+  }
+
+  fn upsert(key: &[&str]) -> UpsertConfig {
+    UpsertConfig {
+      on_conflict: vec!["uuid".to_string()],
+      action: ConflictAction::Update,
+      key: key.iter().map(|column| column.to_string()).collect(),
+    }
+  }
+
+  fn uuids(set: &str, key: &[&str], rows: &[Value]) -> Result<Vec<Value>, SeedError> {
+    let mut resolver = Resolver::new(set, &[], None);
+    let upsert = upsert(key);
+    let upsert = (!key.is_empty()).then_some(&upsert);
+    rows
+      .iter()
+      .enumerate()
+      .map(|(index, value)| {
+        resolver.generated_uuid(&posts(), &row(value.clone()), upsert, "posts.json", index)
+      })
+      .collect()
+  }
+
+  #[test]
+  fn parses_reference() {
+    let reference = Reference::from_value(&json!({"$ref": "users.email:admin@example.com"}));
+    assert_eq!(
+      reference,
+      Some(Ok(Reference {
+        table: "users".to_string(),
+        column: "email".to_string(),
+        value: "admin@example.com".to_string(),
+      }))
+    );
+    // 값에 든 ":"는 값의 일부다
+    let reference = Reference::from_value(&json!({"$ref": "posts.slug:a:b"}));
+    assert_eq!(reference.unwrap().unwrap().value, "a:b");
+  }
+
+  #[test]
+  fn rejects_malformed_reference() {
+    for value in [
+      json!({"$ref": "users.email"}),
+      json!({"$ref": "users:admin@example.com"}),
+      json!({"$ref": ".email:admin@example.com"}),
+      json!({"$ref": "users.:admin@example.com"}),
+      json!({"$ref": 1}),
+      json!({"$ref": "users.email:admin@example.com", "extra": true}),
+    ] {
+      assert!(
+        matches!(Reference::from_value(&value), Some(Err(_))),
+        "{}",
+        value
+      );
+    }
+  }
+
+  #[test]
+  fn ignores_values_that_are_not_references() {
+    for value in [
+      json!("users.email:admin@example.com"),
+      json!({"ref": "users.email:admin@example.com"}),
+      json!(["$ref"]),
+      json!(null),
+    ] {
+      assert_eq!(Reference::from_value(&value), None, "{}", value);
+    }
+  }
+
+  #[test]
+  fn referenced_tables_become_edges() {
+    let edges = referenced_tables(
+      "posts",
+      &row(json!({"user_id": {"$ref": "users.email:a@b.c"}, "title": "a"})),
+    );
+    assert_eq!(edges.len(), 1);
+    assert_eq!(edges[0].parent_table, "users");
+    assert_eq!(edges[0].columns, "user_id");
+  }
+
+  #[test]
+  fn keyed_uuid_survives_edits_and_reordering() {
+    let a = json!({"title": "a", "price": 1000});
+    let b = json!({"title": "b", "price": 2000});
+    let before = uuids("dev", &["title"], &[a.clone(), b.clone()]).unwrap();
+
+    // 다른 컬럼을 고치거나 순서를 바꿔도 같은 key면 같은 uuid다
+    let edited = uuids(
+      "dev",
+      &["title"],
+      &[json!({"title": "b", "price": 2500}), json!({"title": "a"})],
+    )
+    .unwrap();
+    assert_eq!(edited, [before[1].clone(), before[0].clone()]);
+
+    assert_ne!(uuids("demo", &["title"], &[a]).unwrap()[0], before[0]);
+    assert!(uuid::Uuid::parse_str(before[0].as_str().unwrap()).is_ok());
+  }
+
+  #[test]
+  fn on_conflict_columns_are_the_default_key() {
+    let upsert = UpsertConfig {
+      on_conflict: vec!["uuid".to_string(), "slug".to_string()],
+      action: ConflictAction::Update,
+      key: Vec::new(),
+    };
+    assert_eq!(upsert.uuid_key(), ["slug"]);
+
+    let upsert = UpsertConfig {
+      key: vec!["title".to_string()],
+      ..upsert
+    };
+    assert_eq!(upsert.uuid_key(), ["title"]);
+  }
+
+  #[test]
+  fn missing_or_duplicate_key_is_an_error() {
+    let err = uuids("dev", &["title"], &[json!({"price": 1})]).unwrap_err();
+    assert_eq!(err.row, Some(0));
+    assert_eq!(err.column.as_deref(), Some("title"));
+
+    let err = uuids(
+      "dev",
+      &["title"],
+      &[
+        json!({"title": "a"}),
+        json!({"title": "b"}),
+        json!({"title": "a"}),
+      ],
+    )
+    .unwrap_err();
+    assert_eq!(err.row, Some(2));
+    assert!(err.message.contains("row 0"), "{}", err.message);
+  }
+
+  // key가 없으면 내용으로 만들고, 내용이 같은 row는 몇 번째인지로 구분한다
+  #[test]
+  fn unkeyed_uuid_falls_back_to_contents() {
+    let a = json!({"title": "a"});
+    let b = json!({"title": "b"});
+    let before = uuids("dev", &[], &[a.clone(), b.clone()]).unwrap();
+    assert_eq!(uuids("dev", &[], &[b, a.clone()]).unwrap()[1], before[0]);
+
+    let same = uuids("dev", &[], &[a.clone(), a.clone()]).unwrap();
+    assert_eq!(same[0], before[0]);
+    assert_ne!(same[0], same[1]);
+  }
+
+  #[sqlx::test(migrations = false)]
+  async fn references_resolve_to_the_foreign_key_column(pool: PgPool) {
+    pool
+      .execute(
+        "create table parent (id serial primary key, uuid uuid not null unique default gen_random_uuid(), slug text unique, kind text);
+        create table child (id serial primary key, parent_id int references parent(id), parent_uuid uuid);
+        insert into parent (slug, kind) values ('a', 'x'), ('b', 'x');",
+      )
+      .await
+      .unwrap();
+    let mut conn = pool.acquire().await.unwrap();
+    let foreign_keys = load_foreign_keys(&mut conn).await.unwrap();
+    let schema = load_table(&mut conn, "child").await.unwrap().unwrap();
+    let mut resolver = Resolver::new("dev", &foreign_keys, None);
+
+    let mut child = row(json!({
+      "parent_id": {"$ref": "parent.slug:b"},
+      "parent_uuid": {"$ref": "parent.slug:b"}
+    }));
+    resolver
+      .resolve_row(&mut conn, &schema, &mut child, "child.json", 0)
+      .await
+      .unwrap();
+
+    let (id, uuid): (i32, uuid::Uuid) =
+      sqlx::query_as("select id, uuid from parent where slug = 'b'")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    // 외래키가 있는 컬럼은 외래키가 가리키는 id, 없는 컬럼은 uuid
+    assert_eq!(child["parent_id"], json!(id));
+    assert_eq!(child["parent_uuid"], json!(uuid));
+
+    for (reference, message) in [
+      ("parent.slug:c", "found no parent row"),
+      ("parent.kind:x", "matched more than one parent row"),
+    ] {
+      let mut child = row(json!({"parent_id": {"$ref": reference}}));
+      let err = resolver
+        .resolve_row(&mut conn, &schema, &mut child, "child.json", 3)
+        .await
+        .unwrap_err();
+      assert_eq!(err.row, Some(3));
+      assert_eq!(err.column.as_deref(), Some("parent_id"));
+      assert!(err.message.contains(message), "{}", err.message);
+    }
+  }
+}
@@ -0,0 +1,357 @@
+use std::fmt;
+
+use sqlx::PgConnection;
+
+// information_schema.columns.udt_name 기준으로 나눈 postgres 타입
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgType {
+  Uuid,
+  Timestamptz,
+  Timestamp,
+  Date,
+  Time,
+  Int2,
+  Int4,
+  Int8,
+  Numeric,
+  Float4,
+  Float8,
+  Bool,
+  Json,
+  Jsonb,
+  Text,
+  Array(Box<PgType>),
+  Enum { name: String, labels: Vec<String> },
+  // 따로 처리하지 않는 타입은 문자열로 보내고 postgres에서 변환한다
+  Other(String),
+}
+
+impl PgType {
+  fn from_udt(udt_name: &str) -> PgType {
+    match udt_name {
+      "uuid" => PgType::Uuid,
+      "timestamptz" => PgType::Timestamptz,
+      "timestamp" => PgType::Timestamp,
+      "date" => PgType::Date,
+      "time" => PgType::Time,
+      "int2" => PgType::Int2,
+      "int4" => PgType::Int4,
+      "int8" => PgType::Int8,
+      "numeric" => PgType::Numeric,
+      "float4" => PgType::Float4,
+      "float8" => PgType::Float8,
+      "bool" => PgType::Bool,
+      "json" => PgType::Json,
+      "jsonb" => PgType::Jsonb,
+      "text" | "varchar" | "bpchar" | "citext" | "name" => PgType::Text,
+      other => match other.strip_prefix('_') {
+        Some(element) => PgType::Array(Box::new(PgType::from_udt(element))),
+        None => PgType::Other(other.to_string()),
+      },
+    }
+  }
+
+  // placeholder 뒤에 붙이는 캐스트, "$1::int8"
+  pub fn sql_name(&self) -> String {
+    match self {
+      PgType::Uuid => "uuid".to_string(),
+      PgType::Timestamptz => "timestamptz".to_string(),
+      PgType::Timestamp => "timestamp".to_string(),
+      PgType::Date => "date".to_string(),
+      PgType::Time => "time".to_string(),
+      PgType::Int2 => "int2".to_string(),
+      PgType::Int4 => "int4".to_string(),
+      PgType::Int8 => "int8".to_string(),
+      PgType::Numeric => "numeric".to_string(),
+      PgType::Float4 => "float4".to_string(),
+      PgType::Float8 => "float8".to_string(),
+      PgType::Bool => "bool".to_string(),
+      PgType::Json => "json".to_string(),
+      PgType::Jsonb => "jsonb".to_string(),
+      PgType::Text => "text".to_string(),
+      PgType::Array(element) => format!("{}[]", element.sql_name()),
+      PgType::Enum { name, .. } | PgType::Other(name) => quote_ident(name),
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct ColumnInfo {
+  pub name: String,
+  pub pg_type: PgType,
+  pub nullable: bool,
+  pub has_default: bool,
+  pub max_length: Option<i32>,
+  // serial, identity 처럼 postgres가 번호를 매기는 컬럼
+  pub serial: bool,
+  // generated always as (...) stored, 값을 넣을 수 없다
+  pub generated: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct TableSchema {
+  pub name: String,
+  pub columns: Vec<ColumnInfo>,
+}
+
+impl TableSchema {
+  pub fn column(&self, name: &str) -> Option<&ColumnInfo> {
+    self.columns.iter().find(|column| column.name == name)
+  }
+}
+
+pub fn quote_ident(name: &str) -> String {
+  format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+struct ColumnRow {
+  column_name: Option<String>,
+  udt_name: Option<String>,
+  is_nullable: Option<String>,
+  column_default: Option<String>,
+  character_maximum_length: Option<i32>,
+  is_identity: Option<String>,
+  is_generated: Option<String>,
+  is_enum: Option<bool>,
+}
+
+// 테이블이 없으면 None
+pub async fn load_table(
+  conn: &mut PgConnection,
+  table: &str,
+) -> Result<Option<TableSchema>, sqlx::Error> {
+  let rows = sqlx::query_as!(
+    ColumnRow,
+    r#"select c.column_name::text, c.udt_name::text, c.is_nullable::text, c.column_default::text,
+      c.character_maximum_length::int4, c.is_identity::text, c.is_generated::text,
+      exists (
+        select 1 from pg_type t join pg_namespace n on n.oid = t.typnamespace
+        where t.typname = ltrim(c.udt_name, '_') and n.nspname = c.udt_schema and t.typtype = 'e'
+      ) as is_enum
+    from information_schema.columns c
+    where c.table_schema = current_schema() and c.table_name = $1
+    order by c.ordinal_position"#,
+    table
+  )
+  .fetch_all(&mut *conn)
+  .await?;
+
+  if rows.is_empty() {
+    return Ok(None);
+  }
+
+  let mut columns = Vec::new();
+
+  for row in rows {
+    let udt_name = row.udt_name.unwrap_or_default();
+
+    let pg_type = if row.is_enum == Some(true) {
+      let enum_name = udt_name.trim_start_matches('_').to_string();
+      let labels = sqlx::query_scalar!(
+        r#"select e.enumlabel::text as "label!" from pg_enum e
+        join pg_type t on t.oid = e.enumtypid
+        where t.typname = $1
+        order by e.enumsortorder"#,
+        enum_name
+      )
+      .fetch_all(&mut *conn)
+      .await?;
+
+      let enum_type = PgType::Enum {
+        name: enum_name,
+        labels,
+      };
+      if udt_name.starts_with('_') {
+        PgType::Array(Box::new(enum_type))
+      } else {
+        enum_type
+      }
+    } else {
+      PgType::from_udt(&udt_name)
+    };
+
+    let serial = row.is_identity.as_deref() == Some("YES")
+      || row
+        .column_default
+        .as_deref()
+        .is_some_and(|default| default.starts_with("nextval("));
+
+    columns.push(ColumnInfo {
+      name: row.column_name.unwrap_or_default(),
+      pg_type,
+      nullable: row.is_nullable.as_deref() == Some("YES"),
+      has_default: row.column_default.is_some(),
+      max_length: row.character_maximum_length,
+      serial,
+      generated: row.is_generated.as_deref() == Some("ALWAYS"),
+    });
+  }
+
+  Ok(Some(TableSchema {
+    name: table.to_string(),
+    columns,
+  }))
+}
+
+// posts.user_id → users.uuid 같은 외래키, 복합키는 컬럼을 ", "로 이어서 보여준다
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForeignKey {
+  pub table: String,
+  pub columns: String,
+  pub parent_table: String,
+  pub parent_columns: String,
+}
+
+impl fmt::Display for ForeignKey {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}.{} → {}.{}",
+      self.table, self.columns, self.parent_table, self.parent_columns
+    )
+  }
+}
+
+// 현재 스키마의 모든 외래키
+pub async fn load_foreign_keys(conn: &mut PgConnection) -> Result<Vec<ForeignKey>, sqlx::Error> {
+  sqlx::query_as!(
+    ForeignKey,
+    r#"select cl.relname::text as "table!", parent.relname::text as "parent_table!",
+      (select string_agg(a.attname, ', ' order by k.ord)
+        from unnest(c.conkey) with ordinality k(attnum, ord)
+        join pg_attribute a on a.attrelid = c.conrelid and a.attnum = k.attnum) as "columns!",
+      (select string_agg(a.attname, ', ' order by k.ord)
+        from unnest(c.confkey) with ordinality k(attnum, ord)
+        join pg_attribute a on a.attrelid = c.confrelid and a.attnum = k.attnum) as "parent_columns!"
+    from pg_constraint c
+    join pg_class cl on cl.oid = c.conrelid
+    join pg_class parent on parent.oid = c.confrelid
+    join pg_namespace n on n.oid = cl.relnamespace
+    where c.contype = 'f' and n.nspname = current_schema()
+    order by cl.relname, c.conname"#
+  )
+  .fetch_all(&mut *conn)
+  .await
+}
+
+// 컬럼 하나로 된 unique 인덱스, 번호 키 대신 이 값으로 row를 가리킬 수 있다
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniqueColumn {
+  pub table: String,
+  pub column: String,
+}
+
+// 현재 스키마의 unique 컬럼, primary key와 조건부, 식 인덱스는 뺀다. 테이블마다 컬럼 순서대로
+pub async fn load_unique_columns(
+  conn: &mut PgConnection,
+) -> Result<Vec<UniqueColumn>, sqlx::Error> {
+  sqlx::query_as!(
+    UniqueColumn,
+    r#"select t.relname::text as "table!", a.attname::text as "column!"
+    from pg_index i
+    join pg_class t on t.oid = i.indrelid
+    join pg_namespace n on n.oid = t.relnamespace
+    join pg_attribute a on a.attrelid = i.indrelid and a.attnum = i.indkey[0]
+    where i.indisunique and not i.indisprimary and i.indnatts = 1
+      and i.indpred is null and i.indexprs is null and n.nspname = current_schema()
+    order by t.relname, a.attnum"#
+  )
+  .fetch_all(&mut *conn)
+  .await
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use sqlx::{Executor, PgPool};
+
+  #[test]
+  fn udt_names_are_mapped() {
+    assert_eq!(PgType::from_udt("int8"), PgType::Int8);
+    assert_eq!(PgType::from_udt("varchar"), PgType::Text);
+    assert_eq!(
+      PgType::from_udt("_text"),
+      PgType::Array(Box::new(PgType::Text))
+    );
+    assert_eq!(PgType::from_udt("inet"), PgType::Other("inet".to_string()));
+  }
+
+  #[test]
+  fn sql_names_are_castable() {
+    assert_eq!(PgType::Array(Box::new(PgType::Int4)).sql_name(), "int4[]");
+    assert_eq!(
+      PgType::Other("my type".to_string()).sql_name(),
+      "\"my type\""
+    );
+    assert_eq!(quote_ident("a\"b"), "\"a\"\"b\"");
+  }
+
+  #[sqlx::test(migrations = false)]
+  async fn table_columns_are_loaded(pool: PgPool) {
+    pool
+      .execute(
+        "create type mood as enum ('good', 'bad');
+        create table diary (
+          id serial primary key,
+          title varchar(20) not null,
+          moods mood[],
+          mood mood,
+          shout text generated always as (upper(title)) stored
+        );",
+      )
+      .await
+      .unwrap();
+
+    let mut conn = pool.acquire().await.unwrap();
+    let schema = load_table(&mut conn, "diary").await.unwrap().unwrap();
+    let enum_type = PgType::Enum {
+      name: "mood".to_string(),
+      labels: vec!["good".to_string(), "bad".to_string()],
+    };
+
+    let id = schema.column("id").unwrap();
+    assert!(id.has_default && !id.nullable);
+    assert!(id.serial && !id.generated);
+    let shout = schema.column("shout").unwrap();
+    assert!(shout.generated && !shout.serial);
+    let title = schema.column("title").unwrap();
+    assert_eq!(title.pg_type, PgType::Text);
+    assert_eq!(title.max_length, Some(20));
+    assert_eq!(
+      schema.column("moods").unwrap().pg_type,
+      PgType::Array(Box::new(enum_type.clone()))
+    );
+    assert_eq!(schema.column("mood").unwrap().pg_type, enum_type);
+
+    assert!(load_table(&mut conn, "missing").await.unwrap().is_none());
+  }
+
+  #[sqlx::test(migrations = false)]
+  async fn single_column_unique_indexes_are_loaded(pool: PgPool) {
+    pool
+      .execute(
+        "create table shop (
+          id serial primary key,
+          slug text unique,
+          code text,
+          owner text,
+          region text,
+          unique (owner, region)
+        );
+        create unique index shop_code_lower on shop (lower(code));
+        create unique index shop_region_active on shop (region) where owner is not null;",
+      )
+      .await
+      .unwrap();
+
+    let mut conn = pool.acquire().await.unwrap();
+    let unique = load_unique_columns(&mut conn).await.unwrap();
+    assert_eq!(
+      unique,
+      [UniqueColumn {
+        table: "shop".to_string(),
+        column: "slug".to_string(),
+      }]
+    );
+  }
+}
@@ -0,0 +1,380 @@
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use super::sqlx_seeder::SeedError;
+
+pub type Row = Map<String, Value>;
+
+pub type RowStream = Box<dyn Iterator<Item = Result<Row, SeedError>>>;
+
+// 파일 확장자로 고르는 입력 형식
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SeedFormat {
+  Json,
+  Ndjson,
+  Csv,
+  Yaml,
+}
+
+impl SeedFormat {
+  pub fn from_path(path: &Path) -> Option<SeedFormat> {
+    match path.extension()?.to_str()? {
+      "json" => Some(SeedFormat::Json),
+      "ndjson" | "jsonl" => Some(SeedFormat::Ndjson),
+      "csv" => Some(SeedFormat::Csv),
+      "yaml" | "yml" => Some(SeedFormat::Yaml),
+      _ => None,
+    }
+  }
+
+  // csv 셀은 모두 문자열이라 넣기 전에 컬럼 타입을 보고 다시 읽어야 한다
+  pub fn text_cells(self) -> bool {
+    self == SeedFormat::Csv
+  }
+}
+
+// json, yaml은 통째로 읽어두고 ndjson, csv는 넣을 때 한 row씩 읽는다
+#[derive(Debug)]
+pub enum SeedRows {
+  Loaded(Vec<Row>),
+  Streamed { count: usize },
+}
+
+impl SeedRows {
+  pub fn len(&self) -> usize {
+    match self {
+      SeedRows::Loaded(rows) => rows.len(),
+      SeedRows::Streamed { count } => *count,
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+}
+
+// 큰 파일도 메모리에 올리지 않고 해시한다
+pub fn checksum(path: &Path) -> std::io::Result<String> {
+  let mut reader = BufReader::new(File::open(path)?);
+  let mut hasher = Sha256::new();
+  let mut buffer = [0u8; 64 * 1024];
+
+  loop {
+    let read = reader.read(&mut buffer)?;
+    if read == 0 {
+      break;
+    }
+    hasher.update(&buffer[..read]);
+  }
+
+  Ok(hex::encode(hasher.finalize()))
+}
+
+// {"테이블이름": [{...}, {...}]} 형태, yaml도 같은 모양이다
+fn rows_from_document(document: Value, table: &str, file: &str) -> Result<Vec<Row>, SeedError> {
+  let Value::Object(mut document) = document else {
+    return Err(SeedError::file(
+      file,
+      format!("expected an array under the {:?} key", table),
+    ));
+  };
+  let Some(Value::Array(rows)) = document.remove(table) else {
+    return Err(SeedError::file(
+      file,
+      format!("expected an array under the {:?} key", table),
+    ));
+  };
+
+  rows
+    .into_iter()
+    .enumerate()
+    .map(|(index, row)| match row {
+      Value::Object(row) => Ok(row),
+      _ => Err(SeedError::row(file, index, None, "row is not an object")),
+    })
+    .collect()
+}
+
+fn read_text(path: &Path, file: &str) -> Result<String, SeedError> {
+  fs::read_to_string(path).map_err(|err| SeedError::file(file, err.to_string()))
+}
+
+// 계획을 세울 때 한 번 읽는다, 스트리밍 형식은 row 수만 센다
+pub fn load_rows(
+  path: &Path,
+  format: SeedFormat,
+  table: &str,
+  file: &str,
+) -> Result<SeedRows, SeedError> {
+  match format {
+    SeedFormat::Json => {
+      let document = serde_json::from_str(&read_text(path, file)?)
+        .map_err(|err| SeedError::file(file, format!("invalid JSON: {}", err)))?;
+      Ok(SeedRows::Loaded(rows_from_document(document, table, file)?))
+    }
+    SeedFormat::Yaml => {
+      let document = serde_yaml::from_str(&read_text(path, file)?)
+        .map_err(|err| SeedError::file(file, format!("invalid YAML: {}", err)))?;
+      Ok(SeedRows::Loaded(rows_from_document(document, table, file)?))
+    }
+    SeedFormat::Ndjson => {
+      let reader =
+        BufReader::new(File::open(path).map_err(|err| SeedError::file(file, err.to_string()))?);
+      let mut count = 0;
+      for line in reader.lines() {
+        let line = line.map_err(|err| SeedError::file(file, err.to_string()))?;
+        if !line.trim().is_empty() {
+          count += 1;
+        }
+      }
+      Ok(SeedRows::Streamed { count })
+    }
+    SeedFormat::Csv => {
+      let mut reader = csv_reader(path, file)?;
+      let mut count = 0;
+      for record in reader.records() {
+        record.map_err(|err| SeedError::row(file, count, None, format!("invalid CSV: {}", err)))?;
+        count += 1;
+      }
+      Ok(SeedRows::Streamed { count })
+    }
+  }
+}
+
+fn csv_reader(path: &Path, file: &str) -> Result<csv::Reader<File>, SeedError> {
+  csv::ReaderBuilder::new()
+    .has_headers(true)
+    .from_path(path)
+    .map_err(|err| SeedError::file(file, err.to_string()))
+}
+
+// 넣을 때 다시 열어서 한 row씩 읽는다, 빈 줄은 row로 세지 않는다
+pub fn stream_rows(path: &Path, format: SeedFormat, file: &str) -> Result<RowStream, SeedError> {
+  let file = file.to_string();
+
+  match format {
+    SeedFormat::Ndjson => {
+      let reader =
+        BufReader::new(File::open(path).map_err(|err| SeedError::file(&file, err.to_string()))?);
+      let lines = reader.lines().filter(|line| {
+        line
+          .as_ref()
+          .map(|line| !line.trim().is_empty())
+          .unwrap_or(true)
+      });
+
+      Ok(Box::new(lines.enumerate().map(move |(index, line)| {
+        let line = line.map_err(|err| SeedError::row(&file, index, None, err.to_string()))?;
+        match serde_json::from_str(&line) {
+          Ok(Value::Object(row)) => Ok(row),
+          Ok(_) => Err(SeedError::row(&file, index, None, "row is not an object")),
+          Err(err) => Err(SeedError::row(
+            &file,
+            index,
+            None,
+            format!("invalid JSON: {}", err),
+          )),
+        }
+      })))
+    }
+    SeedFormat::Csv => {
+      let mut reader = csv_reader(path, &file)?;
+      // 스프레드시트에서 내보낸 파일은 첫 헤더 앞에 BOM이 붙어 있을 수 있다
+      let headers: Vec<String> = reader
+        .headers()
+        .map_err(|err| SeedError::file(&file, format!("invalid CSV header: {}", err)))?
+        .iter()
+        .map(|header| header.trim_start_matches('\u{feff}').trim().to_string())
+        .collect();
+
+      Ok(Box::new(reader.into_records().enumerate().map(
+        move |(index, record)| {
+          let record = record
+            .map_err(|err| SeedError::row(&file, index, None, format!("invalid CSV: {}", err)))?;
+          Ok(
+            headers
+              .iter()
+              .zip(record.iter())
+              .map(|(header, cell)| (header.clone(), Value::String(cell.to_string())))
+              .collect(),
+          )
+        },
+      )))
+    }
+    SeedFormat::Json | SeedFormat::Yaml => Err(SeedError::file(&file, "format is not streamed")),
+  }
+}
+
+// <폴더>/<테이블>.json 또는 .ndjson 으로 row를 쓴다, export와 generate가 쓴다
+pub fn write_rows(
+  folder: &Path,
+  table: &str,
+  rows: Vec<Row>,
+  ndjson: bool,
+) -> std::io::Result<PathBuf> {
+  fs::create_dir_all(folder)?;
+  let path = folder.join(format!(
+    "{}.{}",
+    table,
+    if ndjson { "ndjson" } else { "json" }
+  ));
+  let mut writer = BufWriter::new(File::create(&path)?);
+
+  if ndjson {
+    for row in rows {
+      serde_json::to_writer(&mut writer, &row)?;
+      writer.write_all(b"\n")?;
+    }
+  } else {
+    let mut document = Map::new();
+    document.insert(
+      table.to_string(),
+      Value::Array(rows.into_iter().map(Value::Object).collect()),
+    );
+    serde_json::to_writer_pretty(&mut writer, &document)?;
+    writer.write_all(b"\n")?;
+  }
+
+  writer.flush()?;
+  Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+  use std::path::PathBuf;
+
+  // 테스트마다 따로 쓰는 임시 파일
+  fn write_temp(name: &str, contents: &str) -> PathBuf {
+    let folder = std::env::temp_dir().join(format!("seed-source-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&folder).unwrap();
+    let path = folder.join(name);
+    fs::write(&path, contents).unwrap();
+    path
+  }
+
+  fn streamed(path: &Path, format: SeedFormat, file: &str) -> Vec<Result<Row, SeedError>> {
+    stream_rows(path, format, file).unwrap().collect()
+  }
+
+  #[test]
+  fn format_follows_the_extension() {
+    assert_eq!(
+      SeedFormat::from_path(Path::new("a.json")),
+      Some(SeedFormat::Json)
+    );
+    assert_eq!(
+      SeedFormat::from_path(Path::new("a.jsonl")),
+      Some(SeedFormat::Ndjson)
+    );
+    assert_eq!(
+      SeedFormat::from_path(Path::new("a.yml")),
+      Some(SeedFormat::Yaml)
+    );
+    assert_eq!(
+      SeedFormat::from_path(Path::new("a.csv")),
+      Some(SeedFormat::Csv)
+    );
+    assert_eq!(SeedFormat::from_path(Path::new("a.txt")), None);
+    assert!(SeedFormat::Csv.text_cells());
+    assert!(!SeedFormat::Yaml.text_cells());
+  }
+
+  #[test]
+  fn json_and_yaml_rows_are_under_the_table_key() {
+    let path = write_temp("items.json", r#"{"items": [{"name": "a"}, {"name": "b"}]}"#);
+    let Ok(SeedRows::Loaded(rows)) = load_rows(&path, SeedFormat::Json, "items", "items.json")
+    else {
+      panic!("json rows were not loaded");
+    };
+    assert_eq!(rows[1]["name"], json!("b"));
+
+    let path = write_temp(
+      "items.yaml",
+      "items:\n  - name: a\n    tags: [x, y]\n    price: 3\n",
+    );
+    let Ok(SeedRows::Loaded(rows)) = load_rows(&path, SeedFormat::Yaml, "items", "items.yaml")
+    else {
+      panic!("yaml rows were not loaded");
+    };
+    assert_eq!(
+      rows,
+      [json!({"name": "a", "tags": ["x", "y"], "price": 3})
+        .as_object()
+        .cloned()
+        .unwrap()]
+    );
+  }
+
+  #[test]
+  fn malformed_documents_are_reported() {
+    let path = write_temp("items.json", r#"{"other": []}"#);
+    let err = load_rows(&path, SeedFormat::Json, "items", "items.json").unwrap_err();
+    assert!(err.message.contains("\"items\""));
+
+    let path = write_temp("items.json", r#"{"items": [{"name": "a"}, 1]}"#);
+    let err = load_rows(&path, SeedFormat::Json, "items", "items.json").unwrap_err();
+    assert_eq!(err.row, Some(1));
+
+    let path = write_temp("items.yaml", "items: [a: 1\n");
+    let err = load_rows(&path, SeedFormat::Yaml, "items", "items.yaml").unwrap_err();
+    assert!(err.message.starts_with("invalid YAML"));
+  }
+
+  #[test]
+  fn ndjson_skips_blank_lines() {
+    let path = write_temp(
+      "items.ndjson",
+      "{\"name\": \"a\"}\n\n{\"name\": \"b\"}\n[1]\n{\n",
+    );
+    assert_eq!(
+      load_rows(&path, SeedFormat::Ndjson, "items", "items.ndjson")
+        .unwrap()
+        .len(),
+      4
+    );
+
+    let rows = streamed(&path, SeedFormat::Ndjson, "items.ndjson");
+    assert_eq!(rows[1].as_ref().unwrap()["name"], json!("b"));
+    assert_eq!(rows[2].as_ref().unwrap_err().row, Some(2));
+    assert!(rows[3]
+      .as_ref()
+      .unwrap_err()
+      .message
+      .starts_with("invalid JSON"));
+  }
+
+  #[test]
+  fn csv_cells_stay_text_and_bom_is_dropped() {
+    let path = write_temp("items.csv", "\u{feff}name, price\n\"a, b\",3\nc,\n");
+    assert_eq!(
+      load_rows(&path, SeedFormat::Csv, "items", "items.csv")
+        .unwrap()
+        .len(),
+      2
+    );
+
+    let rows = streamed(&path, SeedFormat::Csv, "items.csv");
+    let first = rows[0].as_ref().unwrap();
+    assert_eq!(first["name"], json!("a, b"));
+    assert_eq!(first["price"], json!("3"));
+    assert_eq!(rows[1].as_ref().unwrap()["price"], json!(""));
+
+    let path = write_temp("items.csv", "name,price\na,1,extra\n");
+    assert!(load_rows(&path, SeedFormat::Csv, "items", "items.csv").is_err());
+  }
+
+  #[test]
+  fn checksum_hashes_the_file() {
+    let path = write_temp("items.json", "abc");
+    assert_eq!(
+      checksum(&path).unwrap(),
+      "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+  }
+}
@@ -1,323 +1,1265 @@
+use std::collections::{HashMap, HashSet};
 use std::env::current_dir;
 use std::error::Error;
-use std::fs::{self};
-use std::io::Read;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
-use serde_json::{self, Value};
+use chrono::{DateTime, Utc};
 
-use sqlx::{Pool, Postgres};
+use sqlx::postgres::PgDatabaseError;
+use sqlx::{Connection, PgConnection, Pool, Postgres, Transaction};
 
-use chrono::{DateTime, FixedOffset};
-use config::Config;
-use sqlx::types::chrono;
-use sqlx::types::Uuid;
+use config::{Config, ConfigError};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
 
-struct Seeder {
-  file_names: Vec<String>,
-  table_names: Vec<String>,
+use super::insert::{seed_rows, BatchOptions, ConflictAction, SeedInput, SeedStats, UpsertConfig};
+use super::order::{order_files, SeedFile};
+use super::resolve::{referenced_tables, Resolver};
+use super::schema::{load_foreign_keys, load_table, quote_ident, ForeignKey, TableSchema};
+use super::source::{checksum, load_rows, stream_rows, Row, RowStream, SeedFormat, SeedRows};
+use super::transform::TableTransforms;
+use super::validate::RowValidator;
+
+const CONFIG_FILE: &str = "pg-seeder.toml";
+
+const DEFAULT_SET: &str = "dev";
+
+// 어느 pg-seeder.toml의 어느 세트를 넣을지, 비어있으면 기본값을 쓴다
+#[derive(Debug, Clone, Default)]
+pub struct SeedTarget {
+  // pg-seeder.toml이 있는 폴더
+  pub root: Option<PathBuf>,
+  pub set: Option<String>,
+}
+
+impl SeedTarget {
+  // SEED_ROOT, SEED_SET 환경 변수
+  pub fn from_env() -> Self {
+    SeedTarget {
+      root: std::env::var_os("SEED_ROOT").map(PathBuf::from),
+      set: std::env::var("SEED_SET").ok().filter(|set| !set.is_empty()),
+    }
+  }
 }
 
-trait SeederFn {
-  fn new() -> Self;
+// [seeders.sets.<세트>], 세트마다 폴더와 _seed_history 기록을 따로 쓴다
+#[derive(Debug, Deserialize)]
+struct SetConfig {
+  task_folder: String,
+}
+
+#[derive(Debug)]
+struct SeederConfig {
+  set: String,
+  folder: PathBuf,
+  // [seeders.tables.<테이블>] 과 [seeders.files."<파일>"], 파일 설정이 테이블 설정보다 우선한다
+  tables: HashMap<String, UpsertConfig>,
+  files: HashMap<String, UpsertConfig>,
+  // [seeders.transforms.<테이블>] 컬럼 = "bcrypt"
+  transforms: HashMap<String, TableTransforms>,
+  batch: BatchOptions,
+}
+
+impl SeederConfig {
+  fn upsert(&self, file: &str, table: &str) -> Option<&UpsertConfig> {
+    self.files.get(file).or_else(|| self.tables.get(table))
+  }
 }
 
-impl SeederFn for Seeder {
-  fn new() -> Self {
-    Seeder {
-      file_names: Vec::new(),
-      table_names: Vec::new(),
+// root를 정하지 않았으면 현재 폴더부터 위로 올라가며 pg-seeder.toml을 찾고,
+// 없으면 이 크레이트 폴더를 본다
+fn find_root(target: &SeedTarget) -> Result<PathBuf, String> {
+  let current = current_dir().map_err(|err| err.to_string())?;
+
+  if let Some(root) = &target.root {
+    let root = current.join(root);
+    if !root.join(CONFIG_FILE).is_file() {
+      return Err(format!("{} not found in {}", CONFIG_FILE, root.display()));
     }
+    return Ok(root);
+  }
+
+  let found = current
+    .ancestors()
+    .chain([Path::new(env!("CARGO_MANIFEST_DIR"))])
+    .find(|dir| dir.join(CONFIG_FILE).is_file())
+    .map(Path::to_path_buf);
+
+  found.ok_or_else(|| {
+    format!(
+      "{} not found in {} or its parents, set SEED_ROOT or pass --root",
+      CONFIG_FILE,
+      current.display()
+    )
+  })
+}
+
+// 설정이 없으면 기본값, 잘못 적었으면 엉뚱한 데이터가 들어가지 않도록 멈춘다
+fn optional_setting<T: Default + DeserializeOwned>(
+  settings: &Config,
+  key: &str,
+) -> Result<T, String> {
+  match settings.get::<T>(key) {
+    Ok(value) => Ok(value),
+    Err(ConfigError::NotFound(_)) => Ok(T::default()),
+    Err(err) => Err(format!("{} {}: {}", CONFIG_FILE, key, err)),
+  }
+}
+
+// 변환 이름을 잘못 적었으면 평문 비밀번호가 그대로 들어가지 않도록 멈춘다
+fn read_transforms(settings: &Config) -> Result<HashMap<String, TableTransforms>, String> {
+  let declared: HashMap<String, HashMap<String, String>> =
+    optional_setting(settings, "seeders.transforms")?;
+
+  declared
+    .into_iter()
+    .map(|(table, columns)| {
+      let transforms = columns
+        .into_iter()
+        .map(|(column, transform)| {
+          let parsed = transform.parse().map_err(|err| {
+            format!(
+              "{} seeders.transforms.{}.{}: {}",
+              CONFIG_FILE, table, column, err
+            )
+          })?;
+          Ok((column, parsed))
+        })
+        .collect::<Result<TableTransforms, String>>()?;
+      Ok((table, transforms))
+    })
+    .collect()
+}
+
+fn read_config(target: &SeedTarget) -> Result<SeederConfig, String> {
+  let root = find_root(target)?;
+  let settings = Config::builder()
+    .add_source(config::File::from(root.join(CONFIG_FILE)))
+    .build()
+    .map_err(|err| {
+      format!(
+        "Failed to load {}: {}",
+        root.join(CONFIG_FILE).display(),
+        err
+      )
+    })?;
+
+  // 세트를 따로 적지 않았으면 seeders.task_folder가 dev 세트 폴더다
+  let mut sets: HashMap<String, SetConfig> = optional_setting(&settings, "seeders.sets")?;
+  if !sets.contains_key(DEFAULT_SET) {
+    let task_folder = settings
+      .get::<String>("seeders.task_folder")
+      .unwrap_or_else(|_| "src/seeders/task".to_string());
+    sets.insert(DEFAULT_SET.to_string(), SetConfig { task_folder });
   }
+
+  let set = target
+    .set
+    .clone()
+    .unwrap_or_else(|| DEFAULT_SET.to_string());
+  let Some(set_config) = sets.get(&set) else {
+    let mut names: Vec<&str> = sets.keys().map(String::as_str).collect();
+    names.sort();
+    return Err(format!(
+      "unknown seed set {:?}, {} has: {}",
+      set,
+      CONFIG_FILE,
+      names.join(", ")
+    ));
+  };
+
+  Ok(SeederConfig {
+    folder: root.join(&set_config.task_folder),
+    set,
+    tables: optional_setting(&settings, "seeders.tables")?,
+    files: optional_setting(&settings, "seeders.files")?,
+    transforms: read_transforms(&settings)?,
+    batch: BatchOptions {
+      batch_size: settings.get::<usize>("seeders.batch_size").unwrap_or(500),
+      copy: settings.get::<bool>("seeders.copy").unwrap_or(false),
+    },
+  })
 }
 
-trait MyDateTimeEncode {
-  fn my_encode(&self) -> String;
+// 세트 이름과 폴더, 시드 파일을 쓰는 export 명령에서 쓴다
+pub fn seed_folder(target: &SeedTarget) -> Result<(String, PathBuf), Box<dyn Error>> {
+  let seed_config = read_config(target)?;
+  Ok((seed_config.set, seed_config.folder))
 }
 
-impl MyDateTimeEncode for DateTime<FixedOffset> {
-  fn my_encode(&self) -> String {
-    self.to_rfc3339()
+// 어느 파일의 몇 번째 row, 어떤 컬럼에서 실패했는지 (row는 0부터)
+#[derive(Debug, Clone)]
+pub struct SeedError {
+  pub file: String,
+  pub row: Option<usize>,
+  pub column: Option<String>,
+  pub message: String,
+}
+
+impl SeedError {
+  pub fn file(file: &str, message: impl Into<String>) -> Self {
+    SeedError {
+      file: file.to_string(),
+      row: None,
+      column: None,
+      message: message.into(),
+    }
+  }
+
+  pub fn row(file: &str, row: usize, column: Option<&str>, message: impl Into<String>) -> Self {
+    SeedError {
+      file: file.to_string(),
+      row: Some(row),
+      column: column.map(str::to_string),
+      message: message.into(),
+    }
   }
 }
 
+impl fmt::Display for SeedError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.file)?;
+    if let Some(row) = self.row {
+      write!(f, " row {}", row)?;
+    }
+    if let Some(column) = &self.column {
+      write!(f, " column {:?}", column)?;
+    }
+    write!(f, ": {}", self.message)
+  }
+}
+
+impl Error for SeedError {}
+
+// 실패한 파일 목록, 실패한 파일은 task 폴더에 그대로 남는다
 #[derive(Debug)]
-struct SeederConfig {
-  task_folder_path: String,
-  success_folder_path: String,
-  jsonb_name: String,
-  array_string_name: String,
-  created_at_name: String,
-  updated_at_name: String,
-}
-
-trait SeederConfigFn {
-  fn new() -> Self;
-}
-
-impl SeederConfigFn for SeederConfig {
-  fn new() -> Self {
-    SeederConfig {
-      task_folder_path: String::new(),
-      success_folder_path: String::new(),
-      jsonb_name: String::new(),
-      array_string_name: String::new(),
-      created_at_name: String::new(),
-      updated_at_name: String::new(),
+pub struct SeederFailed(pub Vec<SeedError>);
+
+impl fmt::Display for SeederFailed {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{} seed file(s) failed", self.0.len())?;
+    for err in &self.0 {
+      write!(f, "\n  - {}", err)?;
     }
+    Ok(())
   }
 }
 
-fn read_config() -> Result<SeederConfig, config::ConfigError> {
-  let mut new_seeder_config = SeederConfig::new();
-  let settings = match Config::builder()
-    .add_source(config::File::with_name("pg-seeder"))
-    .build()
-  {
-    Ok(config) => config,
-    Err(err) => {
-      eprintln!("Error: Failed to load configuration: {}", err);
-      Config::default()
+impl Error for SeederFailed {}
+
+// 넣기 전 검사에서 찾은 문제, 하나라도 있으면 아무 파일도 넣지 않는다
+#[derive(Debug)]
+pub struct InvalidSeeds(pub Vec<SeedError>);
+
+impl fmt::Display for InvalidSeeds {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} problem(s) in seed files, nothing was applied",
+      self.0.len()
+    )?;
+    for err in &self.0 {
+      write!(f, "\n  - {}", err)?;
     }
-  };
+    Ok(())
+  }
+}
 
-  match settings.get::<String>("seeders.task_folder") {
-    Ok(value) => new_seeder_config.task_folder_path = value,
-    Err(_) => new_seeder_config.task_folder_path = "src/seeders/task".to_string(),
-  };
-  match settings.get::<String>("seeders.success_folder") {
-    Ok(value) => new_seeder_config.success_folder_path = value,
-    Err(_) => new_seeder_config.success_folder_path = "src/seeders/success".to_string(),
-  };
-  match settings.get::<String>("seeders.created_at_name") {
-    Ok(value) => new_seeder_config.created_at_name = value,
-    Err(_) => new_seeder_config.created_at_name = "created_at".to_string(),
-  };
-  match settings.get::<String>("seeders.updated_at_name") {
-    Ok(value) => new_seeder_config.updated_at_name = value,
-    Err(_) => new_seeder_config.updated_at_name = "updated_at".to_string(),
-  };
-  match settings.get::<String>("seeders.jsonb_name") {
-    Ok(value) => new_seeder_config.jsonb_name = value,
-    Err(_) => new_seeder_config.jsonb_name = "size".to_string(),
-  };
-  match settings.get::<String>("seeders.array_string_name") {
-    Ok(value) => new_seeder_config.array_string_name = value,
-    Err(_) => new_seeder_config.array_string_name = "thumbnail_src".to_string(),
-  };
+impl Error for InvalidSeeds {}
 
-  Ok(new_seeder_config)
+// task 폴더의 json, ndjson, csv, yaml 파일, 실행 순서는 order_files에서 정한다
+fn seed_files(folder: &Path) -> std::io::Result<Vec<PathBuf>> {
+  let mut files: Vec<PathBuf> = fs::read_dir(folder)?
+    .flatten()
+    .map(|entry| entry.path())
+    .filter(|path| SeedFormat::from_path(path).is_some())
+    .collect();
+  files.sort();
+  Ok(files)
 }
 
-#[warn(unused_variables)]
-pub async fn seeder(pool: &Pool<Postgres>) -> Result<(), Box<dyn Error>> {
-  let seed_config = read_config().unwrap();
-  // println!("read config {:?}", seed_config.success_folder_path);
-
-  let mut new_seeder = Seeder::new();
-
-  let seeder_folder = current_dir()
-    .and_then(|a| Ok(a.join(seed_config.task_folder_path)))
-    .expect("No seed_folder exists");
-
-  let success_folder = current_dir()
-    .and_then(|a| Ok(a.join(seed_config.success_folder_path)))
-    .expect("No seed_folder exists");
-
-  if let Ok(entries) = fs::read_dir(&seeder_folder) {
-    for entry in entries {
-      if let Ok(entry) = entry {
-        if let Ok(file_name) = entry.file_name().into_string() {
-          new_seeder.file_names.push(file_name.to_string());
-
-          if let Some(ext) = file_name.split(".").last() {
-            if ext == "json" {
-              if let Some(first_part) = file_name.split(".").next() {
-                new_seeder.table_names.push(first_part.to_string());
-
-                let json_data = read_json_file();
-
-                for json_value in json_data {
-                  if let Some(field_value) = json_value.get(first_part) {
-                    let arr_field_value = field_value.as_array().unwrap();
-
-                    for each in arr_field_value {
-                      let mut field_names: Vec<&str> = Vec::new();
-                      let mut field_values: Vec<String> = Vec::new();
-
-                      if let Some(json_obj) = each.as_object() {
-                        for (key, value) in json_obj {
-                          field_names.push(key);
-
-                          field_values.push(value.to_string());
-                        }
-                      }
-                      // println!("Field Names: {:?}", &field_names);
-                      // println!("Field Values: {:?}", &field_values);
-
-                      let mut placeholders = String::new();
-
-                      for (idx, n) in (1..=field_values.len()).enumerate() {
-                        let field_name = &field_names[idx];
-
-                        let placeholder = match field_name {
-                          _ if field_name == &seed_config.jsonb_name => {
-                            format!("${}::JSONB", n)
-                          }
-
-                          field if field == &seed_config.array_string_name => {
-                            format!("${}::TEXT[]", n)
-                          }
-                          _ => format!("${}", n),
-                        };
-
-                        placeholders.push_str(&placeholder);
-
-                        if idx < field_values.len() - 1 {
-                          placeholders.push_str(", ");
-                        }
-                      }
-
-                      let query = format!(
-                        "insert into {} ({}) values ({})",
-                        &first_part,
-                        &field_names.join(", "),
-                        placeholders
-                      );
-
-                      // println!("postgres query : {:?}", &query);
-
-                      let mut query = sqlx::query(&query);
-
-                      for (index, value) in field_values.iter().enumerate() {
-                        match each.get(field_names[index]) {
-                          Some(json_value) => match json_value {
-                            Value::Bool(bool_value) => {
-                              query = query.bind(bool_value);
-                            }
-                            Value::Number(int_value) => {
-                              if let Some(i64_value) = int_value.as_i64() {
-                                query = query.bind(i64_value);
-                              } else if let Some(f64_value) = int_value.as_f64() {
-                                query = query.bind(f64_value);
-                              } else {
-                                println!("Number Error")
-                              }
-                            }
-
-                            Value::String(uuid_string) => {
-                              // println!("index {:?}", field_names[index]);
-                              match Uuid::parse_str(uuid_string) {
-                                Ok(uuid_value) => {
-                                  query = query.bind(uuid_value);
-                                }
-                                Err(_) => match field_names[index]
-                                  == seed_config.created_at_name
-                                  || field_names[index] == seed_config.updated_at_name
-                                {
-                                  true => {
-                                    if let Ok(timestamp) =
-                                      chrono::DateTime::parse_from_rfc3339(uuid_string)
-                                    {
-                                      query = query.bind(timestamp);
-                                      // println!(
-                                      //   "string: created_at!!! true, filed_name : {:?}",
-                                      //   field_names[index]
-                                      // )
-                                    }
-                                  }
-                                  false => {
-                                    query = query.bind(uuid_string);
-                                    // println!(
-                                    //   "string: created_at!!! false, filed_name : {:?}",
-                                    //   field_names[index]
-                                    // )
-                                  }
-                                },
-                              }
-                            }
-                            Value::Array(array_value) => {
-                              // println!("array {:?}", field_names[index]);
-
-                              query = query.bind(array_value);
-                            }
-                            Value::Object(obj_value) => {
-                              // println!("JSONB!!!! {:?}", field_names[index]);
-                              let json_string = serde_json::to_string(obj_value)
-                                .expect("Failed to serialize JSON object to string");
-
-                              // Bind the JSON string to the SQL query
-                              query = query.bind(json_string);
-                            }
-                            _ => {
-                              query = query.bind(value);
-                            }
-                          },
-                          None => {
-                            println!("Seeder Error!")
-                          }
-                        }
-                      }
-                      // 쿼리 실행
-                      query.execute(pool).await.unwrap();
-                    }
-                  }
-                }
-              }
-            }
-          }
-          println!("✅ Seed completed for the {:?}.json", file_name);
+async fn seed_file(
+  tx: &mut Transaction<'_, Postgres>,
+  table: &str,
+  input: SeedInput,
+  file: &str,
+  upsert: Option<&UpsertConfig>,
+  batch: &BatchOptions,
+  resolver: &mut Resolver<'_>,
+) -> Result<SeedStats, SeedError> {
+  // 컬럼 타입은 설정 파일이 아니라 실제 테이블 정의에서 읽는다
+  let schema = load_table(&mut *tx, table)
+    .await
+    .map_err(|err| SeedError::file(file, err.to_string()))?
+    .ok_or_else(|| SeedError::file(file, format!("table {} does not exist", table)))?;
+
+  seed_rows(tx, &schema, input, file, upsert, batch, resolver).await
+}
+
+struct AppliedSeed {
+  file_name: String,
+  checksum: String,
+  row_count: i64,
+  applied_at: DateTime<Utc>,
+}
+
+// 시드 데이터와 같은 트랜잭션에서 기록해서 데이터와 기록이 어긋나지 않게 한다
+async fn record_applied(
+  tx: &mut Transaction<'_, Postgres>,
+  set: &str,
+  file: &str,
+  checksum: &str,
+  row_count: usize,
+) -> Result<(), sqlx::Error> {
+  sqlx::query!(
+    r#"insert into "_seed_history" (seed_set, file_name, checksum, row_count)
+    values ($1, $2, $3, $4)
+    on conflict (seed_set, file_name) do update set
+      checksum = excluded.checksum,
+      row_count = excluded.row_count,
+      applied_at = now()"#,
+    set,
+    file,
+    checksum,
+    row_count as i64
+  )
+  .execute(&mut **tx)
+  .await?;
+
+  Ok(())
+}
+
+// _seed_history와 비교한 파일 상태
+#[derive(Debug)]
+pub enum SeedState {
+  Pending,
+  Applied {
+    applied_at: DateTime<Utc>,
+    row_count: i64,
+  },
+  // upsert 설정이 있으면 다시 넣고, 없으면 경고만 하고 건너뛴다
+  Changed {
+    applied_at: DateTime<Utc>,
+    reapply: bool,
+  },
+  // 외래키 순환 때문에 넣을 수 없다
+  Cycle(String),
+}
+
+#[derive(Debug)]
+pub struct PlannedFile {
+  pub path: PathBuf,
+  pub format: SeedFormat,
+  pub file: String,
+  pub table: String,
+  pub parents: Vec<(String, ForeignKey)>,
+  pub checksum: String,
+  pub rows: Result<SeedRows, SeedError>,
+  pub state: SeedState,
+  // 넣을 파일을 테이블 정의로 검사한 결과, 파일을 읽지 못했으면 그 에러
+  pub problems: Vec<SeedError>,
+  upsert: Option<UpsertConfig>,
+}
+
+impl PlannedFile {
+  pub fn row_count(&self) -> Result<usize, &SeedError> {
+    self.rows.as_ref().map(SeedRows::len)
+  }
+
+  // apply에서 실제로 넣을 파일
+  pub fn will_apply(&self) -> bool {
+    matches!(
+      self.state,
+      SeedState::Pending | SeedState::Changed { reapply: true, .. }
+    )
+  }
+}
+
+// 기록은 있는데 task 폴더에 파일이 없는 시드
+#[derive(Debug)]
+pub struct MissingSeed {
+  pub file: String,
+  pub row_count: i64,
+  pub applied_at: DateTime<Utc>,
+}
+
+// 외래키 순서로 정렬한 파일과 각 파일의 상태, 순환에 걸린 파일은 맨 뒤에 온다
+#[derive(Debug)]
+pub struct SeedPlan {
+  pub set: String,
+  pub folder: PathBuf,
+  pub files: Vec<PlannedFile>,
+  pub missing: Vec<MissingSeed>,
+  batch: BatchOptions,
+  foreign_keys: Vec<ForeignKey>,
+  transforms: HashMap<String, TableTransforms>,
+}
+
+pub async fn plan(pool: &Pool<Postgres>, target: &SeedTarget) -> Result<SeedPlan, Box<dyn Error>> {
+  let seed_config = read_config(target)?;
+
+  // 폴더가 없을 때 조용히 아무것도 안 넣으면 경로를 잘못 적은 걸 알 수 없다
+  let files = seed_files(&seed_config.folder).map_err(|err| {
+    format!(
+      "seed folder {} for the {:?} set: {}",
+      seed_config.folder.display(),
+      seed_config.set,
+      err
+    )
+  })?;
+
+  // 외래키를 보고 부모 테이블 파일부터 넣는다
+  let mut conn = pool.acquire().await?;
+  let foreign_keys = load_foreign_keys(&mut conn).await?;
+  let mut schemas: HashMap<String, Option<TableSchema>> = HashMap::new();
+
+  // $ref로 가리키는 테이블도 외래키처럼 먼저 넣는다,
+  // ndjson, csv는 미리 읽지 않으므로 실제 외래키 순서만 따른다
+  let mut edges = foreign_keys.clone();
+  let mut loaded = HashMap::new();
+  for path in &files {
+    let entry = SeedFile::new(path.clone());
+    let format =
+      SeedFormat::from_path(path).ok_or_else(|| format!("{}: unknown format", entry.file))?;
+    let rows = load_rows(path, format, &entry.table, &entry.file);
+    if let Ok(SeedRows::Loaded(rows)) = &rows {
+      for edge in rows
+        .iter()
+        .flat_map(|row| referenced_tables(&entry.table, row))
+      {
+        if !edges.contains(&edge) {
+          edges.push(edge);
         }
+      }
+    }
+    loaded.insert(entry.file, (format, rows));
+  }
+
+  let order = order_files(files.into_iter().map(SeedFile::new).collect(), &edges);
+
+  let mut applied: HashMap<String, AppliedSeed> = sqlx::query_as!(
+    AppliedSeed,
+    r#"select file_name, checksum, row_count, applied_at from "_seed_history" where seed_set = $1"#,
+    seed_config.set
+  )
+  .fetch_all(pool)
+  .await?
+  .into_iter()
+  .map(|seed| (seed.file_name.clone(), seed))
+  .collect();
+
+  let mut cycles: HashMap<String, String> = order
+    .cycles
+    .into_iter()
+    .map(|cycle| (cycle.file, cycle.message))
+    .collect();
+
+  let mut planned = Vec::new();
+
+  for entry in order.files.into_iter().chain(order.blocked) {
+    let checksum = checksum(&entry.path).map_err(|err| format!("{}: {}", entry.file, err))?;
+    let Some((format, rows)) = loaded.remove(&entry.file) else {
+      continue;
+    };
+
+    let upsert = seed_config.upsert(&entry.file, &entry.table).cloned();
+
+    let state = match (cycles.remove(&entry.file), applied.remove(&entry.file)) {
+      (Some(message), _) => SeedState::Cycle(message),
+      (None, None) => SeedState::Pending,
+      (None, Some(seed)) if seed.checksum == checksum => SeedState::Applied {
+        applied_at: seed.applied_at,
+        row_count: seed.row_count,
+      },
+      (None, Some(seed)) => SeedState::Changed {
+        applied_at: seed.applied_at,
+        reapply: upsert.is_some(),
+      },
+    };
+
+    let mut planned_file = PlannedFile {
+      path: entry.path,
+      format,
+      file: entry.file,
+      table: entry.table,
+      parents: entry.parents,
+      checksum,
+      rows,
+      state,
+      problems: Vec::new(),
+      upsert,
+    };
+    if planned_file.will_apply() {
+      if !schemas.contains_key(&planned_file.table) {
+        let schema = load_table(&mut conn, &planned_file.table).await?;
+        schemas.insert(planned_file.table.clone(), schema);
+      }
+      planned_file.problems = validate_file(
+        &planned_file,
+        schemas[&planned_file.table].as_ref(),
+        seed_config.transforms.get(&planned_file.table),
+      );
+    }
+    planned.push(planned_file);
+  }
+
+  let mut missing: Vec<MissingSeed> = applied
+    .into_values()
+    .map(|seed| MissingSeed {
+      file: seed.file_name,
+      row_count: seed.row_count,
+      applied_at: seed.applied_at,
+    })
+    .collect();
+  missing.sort_by(|a, b| a.file.cmp(&b.file));
+
+  Ok(SeedPlan {
+    set: seed_config.set,
+    folder: seed_config.folder,
+    files: planned,
+    missing,
+    batch: seed_config.batch,
+    foreign_keys,
+    transforms: seed_config.transforms,
+  })
+}
+
+// 파일을 끝까지 읽으면서 테이블 정의에 맞지 않는 값을 모두 모은다
+fn validate_file(
+  planned: &PlannedFile,
+  schema: Option<&TableSchema>,
+  transforms: Option<&TableTransforms>,
+) -> Vec<SeedError> {
+  let file = &planned.file;
+  let Some(schema) = schema else {
+    return vec![SeedError::file(
+      file,
+      format!("table {} does not exist", planned.table),
+    )];
+  };
+  let validator = RowValidator::new(schema, planned.upsert.as_ref(), transforms);
+  let text_cells = planned.format.text_cells();
 
-        let new_path = success_folder.join(entry.file_name());
-        // println!("success folder : {:?}", new_path);
-        if let Err(err) = fs::rename(entry.path(), new_path) {
-          println!("Failed to move file: {}", err);
-        };
-      } else {
-        println!("Failed to read the directory.");
+  match &planned.rows {
+    Ok(SeedRows::Loaded(rows)) => rows
+      .iter()
+      .enumerate()
+      .flat_map(|(index, row)| validator.check_row(row, file, index, text_cells))
+      .collect(),
+    Ok(SeedRows::Streamed { .. }) => match stream_rows(&planned.path, planned.format, file) {
+      Ok(rows) => validator.check_rows(rows, file, text_cells),
+      Err(err) => vec![err],
+    },
+    Err(err) => vec![err.clone()],
+  }
+}
+
+// 파일마다 트랜잭션을 따로 연다, conn이 이미 트랜잭션 안이면 savepoint가 된다
+async fn apply_files(conn: &mut PgConnection, plan: SeedPlan) -> Result<usize, Box<dyn Error>> {
+  // 넣기 전에 모든 파일의 문제를 한 번에 보여준다
+  let problems: Vec<SeedError> = plan
+    .files
+    .iter()
+    .filter(|planned| planned.will_apply())
+    .flat_map(|planned| planned.problems.iter().cloned())
+    .collect();
+  if !problems.is_empty() {
+    return Err(Box::new(InvalidSeeds(problems)));
+  }
+
+  let mut failures = Vec::new();
+  // 실패했거나 건너뛴 파일, 이 파일에 의존하는 파일도 건너뛴다
+  let mut failed = HashSet::new();
+  let mut applied = 0;
+  let mut total_rows = 0;
+  let started = Instant::now();
+
+  for planned in plan.files {
+    let PlannedFile {
+      path,
+      format,
+      file,
+      table,
+      parents,
+      checksum,
+      rows,
+      state,
+      upsert,
+      ..
+    } = planned;
+
+    match state {
+      SeedState::Pending => {}
+      SeedState::Applied { .. } => continue,
+      SeedState::Changed {
+        applied_at,
+        reapply: false,
+      } => {
+        println!(
+          "⚠️ {:?} changed since it was applied at {}, skipping",
+          file, applied_at
+        );
+        continue;
       }
+      SeedState::Changed { reapply: true, .. } => {
+        println!("🔁 {:?} changed, upserting again", file);
+      }
+      SeedState::Cycle(message) => {
+        println!("🔥 Seed skipped: {}: {}", file, message);
+        failed.insert(file.clone());
+        failures.push(SeedError::file(&file, message));
+        continue;
+      }
+    }
+
+    if let Some((parent, foreign_key)) = parents.iter().find(|(parent, _)| failed.contains(parent))
+    {
+      let err = SeedError::file(
+        &file,
+        format!(
+          "blocked by {}, which was not seeded ({})",
+          parent, foreign_key
+        ),
+      );
+      println!("🔥 Seed skipped: {}", err);
+      failed.insert(file);
+      failures.push(err);
+      continue;
     }
+
+    // json, yaml은 계획을 세울 때 읽어둔 row를 그대로 쓰고 ndjson, csv는 다시 열어서 읽는다
+    let input = rows.and_then(|rows| {
+      let total = rows.len();
+      let rows = match rows {
+        SeedRows::Loaded(rows) => Box::new(rows.into_iter().map(Ok)) as RowStream,
+        SeedRows::Streamed { .. } => stream_rows(&path, format, &file)?,
+      };
+      Ok(SeedInput {
+        rows,
+        total,
+        text_cells: format.text_cells(),
+      })
+    });
+    let input = match input {
+      Ok(input) => input,
+      Err(err) => {
+        println!("🔥 Seed failed: {}", err);
+        failed.insert(file);
+        failures.push(err);
+        continue;
+      }
+    };
+
+    let mut tx = conn.begin().await?;
+
+    let mut resolver = Resolver::new(&plan.set, &plan.foreign_keys, plan.transforms.get(&table));
+    let seeded = seed_file(
+      &mut tx,
+      &table,
+      input,
+      &file,
+      upsert.as_ref(),
+      &plan.batch,
+      &mut resolver,
+    )
+    .await;
+
+    match seeded {
+      Ok(stats) => {
+        record_applied(&mut tx, &plan.set, &file, &checksum, stats.rows).await?;
+        tx.commit().await?;
+        applied += 1;
+        total_rows += stats.rows;
+        println!(
+          "✅ Seed completed for the {:?} ({} rows in {:.2}s, {:.0} rows/s)",
+          file,
+          stats.rows,
+          stats.elapsed.as_secs_f64(),
+          stats.rows_per_sec()
+        );
+      }
+      Err(err) => {
+        tx.rollback().await?;
+        println!("🔥 Seed failed, rolled back: {}", err);
+        failed.insert(file);
+        failures.push(err);
+      }
+    }
+  }
+
+  if applied > 0 {
+    let stats = SeedStats {
+      rows: total_rows,
+      elapsed: started.elapsed(),
+    };
+    println!(
+      "📦 {} rows from {} file(s) in {:.2}s ({:.0} rows/s)",
+      stats.rows,
+      applied,
+      stats.elapsed.as_secs_f64(),
+      stats.rows_per_sec()
+    );
+  }
+
+  if !failures.is_empty() {
+    return Err(Box::new(SeederFailed(failures)));
+  }
+
+  Ok(applied)
+}
+
+// 대기중인 파일을 넣는다, dry_run이면 전체를 하나의 트랜잭션 안에서 실행한 뒤 롤백한다
+pub async fn apply(
+  pool: &Pool<Postgres>,
+  target: &SeedTarget,
+  dry_run: bool,
+) -> Result<usize, Box<dyn Error>> {
+  let plan = plan(pool, target).await?;
+
+  if !dry_run {
+    return apply_files(&mut *pool.acquire().await?, plan).await;
   }
 
-  // println!(
-  //   "file_name: {:?} parts : {:?}",
-  //   new_seeder.file_names, new_seeder.table_names
-  // );
+  let mut outer = pool.begin().await?;
+  let result = apply_files(&mut outer, plan).await;
+  outer.rollback().await?;
+  println!("↩️ Dry run, all changes rolled back");
+  result
+}
 
-  print!("✅ Seeder Work Success! ✅ \n");
+// 이미 같은 내용으로 넣은 파일은 건너뛰고 파일은 옮기지 않는다, 세트는 SEED_SET으로 고른다
+pub async fn seeder(pool: &Pool<Postgres>) -> Result<(), Box<dyn Error>> {
+  apply(pool, &SeedTarget::from_env(), false).await?;
+  println!("✅ Seeder Work Success! ✅");
   Ok(())
 }
 
-pub fn read_json_file() -> Vec<Value> {
-  let dir_path = current_dir()
-    .expect("Can't retreive directory")
-    .join("src/seeders/task");
+// 파일 없이 만든 row를 테이블 순서대로 넣는다 (seed generate), 전체를 트랜잭션 하나로 넣고
+// _seed_history에는 남기지 않는다. uuid가 같은 row는 덮어써서 같은 데이터를 다시 넣어도 된다
+pub async fn seed_tables(
+  pool: &Pool<Postgres>,
+  target: &SeedTarget,
+  tables: Vec<(&str, Vec<Row>)>,
+) -> Result<usize, Box<dyn Error>> {
+  let seed_config = read_config(target)?;
+  let mut tx = pool.begin().await?;
+  let mut total_rows = 0;
+  let started = Instant::now();
 
-  let mut json_values: Vec<Value> = Vec::new();
+  for (table, rows) in tables {
+    let Some(schema) = load_table(&mut tx, table).await? else {
+      println!(
+        "⚠️ table {} does not exist, skipped {} rows",
+        table,
+        rows.len()
+      );
+      continue;
+    };
+    let upsert = schema.column("uuid").map(|_| UpsertConfig {
+      on_conflict: vec!["uuid".to_string()],
+      action: ConflictAction::Update,
+      key: Vec::new(),
+    });
+    let input = SeedInput {
+      total: rows.len(),
+      rows: Box::new(rows.into_iter().map(Ok)),
+      text_cells: false,
+    };
 
-  if let Ok(entries) = fs::read_dir(dir_path) {
-    for entry in entries {
-      if let Ok(entry) = entry {
-        let file_path = entry.path();
+    let mut resolver = Resolver::new(&seed_config.set, &[], seed_config.transforms.get(table));
+    let stats = seed_rows(
+      &mut tx,
+      &schema,
+      input,
+      table,
+      upsert.as_ref(),
+      &seed_config.batch,
+      &mut resolver,
+    )
+    .await?;
+    total_rows += stats.rows;
+    println!(
+      "✅ {} ({} rows in {:.2}s, {:.0} rows/s)",
+      table,
+      stats.rows,
+      stats.elapsed.as_secs_f64(),
+      stats.rows_per_sec()
+    );
+  }
+
+  tx.commit().await?;
+  let stats = SeedStats {
+    rows: total_rows,
+    elapsed: started.elapsed(),
+  };
+  println!(
+    "📦 {} rows in {:.2}s ({:.0} rows/s)",
+    stats.rows,
+    stats.elapsed.as_secs_f64(),
+    stats.rows_per_sec()
+  );
+  Ok(total_rows)
+}
 
-        if let Some(ext) = file_path.extension() {
-          if ext == "json" {
-            let mut file = fs::File::open(&file_path).expect("Cannot open the file");
+// 부모보다 자식 테이블이 먼저 오는 순서
+pub fn reset_order(plan: &SeedPlan) -> Vec<String> {
+  let mut tables: Vec<String> = Vec::new();
+  for planned in plan.files.iter().rev() {
+    if !tables.contains(&planned.table) {
+      tables.push(planned.table.clone());
+    }
+  }
+  tables
+}
 
-            let mut contents = String::new();
-            file
-              .read_to_string(&mut contents)
-              .expect("There was an issue reading the file");
+// task 폴더 파일의 테이블을 의존 순서의 역순으로 비우고 기록도 지운다,
+// cascade가 아니면 다른 테이블이 참조하고 있을 때 postgres 에러로 멈춘다
+pub async fn reset(
+  pool: &Pool<Postgres>,
+  target: &SeedTarget,
+  cascade: bool,
+) -> Result<Vec<String>, Box<dyn Error>> {
+  let plan = plan(pool, target).await?;
+  let tables = reset_order(&plan);
+  if tables.is_empty() {
+    return Ok(tables);
+  }
 
-            let json_value: Value =
-              serde_json::from_str(&contents).expect("Failed to parse JSON");
+  let files: Vec<String> = plan.files.into_iter().map(|planned| planned.file).collect();
+  let quoted: Vec<String> = tables.iter().map(|table| quote_ident(table)).collect();
+  let statement = format!(
+    "truncate table {} restart identity{}",
+    quoted.join(", "),
+    if cascade { " cascade" } else { "" }
+  );
 
-            json_values.push(json_value);
-          }
-        }
+  let mut tx = pool.begin().await?;
+  sqlx::query(&statement)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| -> Box<dyn Error> {
+      // 시드 파일이 없는 테이블이 참조하고 있으면 어느 테이블인지 알려준다
+      match err
+        .as_database_error()
+        .and_then(|db_err| db_err.try_downcast_ref::<PgDatabaseError>())
+      {
+        Some(pg_err) if !cascade && pg_err.code() == "0A000" => format!(
+          "{} ({}), pass --cascade to truncate the referencing tables too",
+          pg_err.message(),
+          pg_err.detail().unwrap_or_default()
+        )
+        .into(),
+        _ => err.into(),
       }
+    })?;
+  sqlx::query!(
+    r#"delete from "_seed_history" where seed_set = $1 and file_name = any($2)"#,
+    plan.set,
+    &files
+  )
+  .execute(&mut *tx)
+  .await?;
+  tx.commit().await?;
+
+  Ok(tables)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::fake::{generate, FakeOptions};
+  use super::super::insert::ConflictAction;
+  use super::super::transform::Transform;
+  use super::*;
+  use sqlx::PgPool;
+
+  #[test]
+  fn error_names_file_row_and_column() {
+    let err = SeedError::row("posts.json", 3, Some("price"), "invalid input");
+    assert_eq!(
+      err.to_string(),
+      "posts.json row 3 column \"price\": invalid input"
+    );
+
+    let err = SeedError::file("posts.json", "invalid JSON");
+    assert_eq!(err.to_string(), "posts.json: invalid JSON");
+
+    let failed = SeederFailed(vec![
+      err,
+      SeedError::row("users.json", 0, None, "duplicate"),
+    ]);
+    assert_eq!(
+      failed.to_string(),
+      "2 seed file(s) failed\n  - posts.json: invalid JSON\n  - users.json row 0: duplicate"
+    );
+  }
+
+  // 테스트마다 따로 쓰는 pg-seeder.toml과 폴더
+  fn temp_root(config: &str, folders: &[&str]) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("seed-root-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join(CONFIG_FILE), config).unwrap();
+    for folder in folders {
+      fs::create_dir_all(root.join(folder)).unwrap();
+    }
+    root
+  }
+
+  fn target(root: Option<&Path>, set: Option<&str>) -> SeedTarget {
+    SeedTarget {
+      root: root.map(Path::to_path_buf),
+      set: set.map(str::to_string),
+    }
+  }
+
+  #[test]
+  fn every_configured_set_has_its_folder() {
+    let config = read_config(&SeedTarget::default()).unwrap();
+    assert_eq!(config.set, "dev");
+    assert_eq!(
+      config.folder,
+      Path::new(env!("CARGO_MANIFEST_DIR")).join("src/seeders/task")
+    );
+    assert_eq!(config.batch.batch_size, 500);
+    assert!(!config.batch.copy);
+
+    for (set, expected) in [
+      ("dev", vec!["posts.json", "users.json"]),
+      ("test", vec!["posts.yaml", "users.csv"]),
+      ("demo", vec!["posts.json", "users.json"]),
+    ] {
+      let config = read_config(&target(None, Some(set))).unwrap();
+      let names: Vec<String> = seed_files(&config.folder)
+        .unwrap()
+        .into_iter()
+        .map(|path| SeedFile::new(path).file)
+        .collect();
+      assert_eq!(names, expected, "{} set", set);
     }
   }
 
-  json_values
+  #[test]
+  fn sets_are_resolved_against_the_root() {
+    let root = temp_root(
+      "[seeders]\nbatch_size = 10\n\n[seeders.sets.dev]\ntask_folder = \"seeds/dev\"\n\n[seeders.sets.ci]\ntask_folder = \"seeds/ci\"\n",
+      &["seeds/dev", "seeds/ci"],
+    );
+
+    let config = read_config(&target(Some(&root), None)).unwrap();
+    assert_eq!(
+      (config.set.as_str(), config.folder.clone()),
+      ("dev", root.join("seeds/dev"))
+    );
+    assert_eq!(config.batch.batch_size, 10);
+
+    let config = read_config(&target(Some(&root), Some("ci"))).unwrap();
+    assert_eq!(config.folder, root.join("seeds/ci"));
+
+    let err = read_config(&target(Some(&root), Some("demo"))).unwrap_err();
+    assert_eq!(
+      err,
+      "unknown seed set \"demo\", pg-seeder.toml has: ci, dev"
+    );
+  }
+
+  #[test]
+  fn task_folder_is_the_dev_set_without_sets() {
+    let root = temp_root("[seeders]\ntask_folder = \"fixtures\"\n", &["fixtures"]);
+    let config = read_config(&target(Some(&root), None)).unwrap();
+    assert_eq!(config.folder, root.join("fixtures"));
+
+    let root = temp_root("[seeders]\n", &[]);
+    let config = read_config(&target(Some(&root), None)).unwrap();
+    assert_eq!(config.folder, root.join("src/seeders/task"));
+  }
+
+  #[test]
+  fn bad_root_and_settings_are_errors() {
+    let missing = std::env::temp_dir().join(format!("seed-root-{}", uuid::Uuid::new_v4()));
+    let err = read_config(&target(Some(&missing), None)).unwrap_err();
+    assert!(err.starts_with("pg-seeder.toml not found in"));
+
+    let root = temp_root("[seeders.tables.users]\non_conflict = \"email\"\n", &[]);
+    let err = read_config(&target(Some(&root), None)).unwrap_err();
+    assert!(err.starts_with("pg-seeder.toml seeders.tables"), "{}", err);
+  }
+
+  #[test]
+  fn transforms_are_read_from_the_config() {
+    let config = read_config(&SeedTarget::default()).unwrap();
+    assert_eq!(
+      config.transforms["users"]["password"],
+      Transform::Bcrypt { cost: 10 }
+    );
+    assert_eq!(
+      config.transforms["brands"]["slug"],
+      Transform::Slugify {
+        source: "name".to_string()
+      }
+    );
+
+    let root = temp_root("[seeders.transforms.users]\npassword = \"md5\"\n", &[]);
+    let err = read_config(&target(Some(&root), None)).unwrap_err();
+    assert!(
+      err.starts_with("pg-seeder.toml seeders.transforms.users.password: unknown transform"),
+      "{}",
+      err
+    );
+  }
+
+  fn upsert(keys: &[&str], action: ConflictAction) -> UpsertConfig {
+    UpsertConfig {
+      on_conflict: keys.iter().map(|key| key.to_string()).collect(),
+      action,
+      key: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn file_config_wins_over_table_config() {
+    let config = SeederConfig {
+      set: "dev".to_string(),
+      folder: PathBuf::from("task"),
+      tables: HashMap::from([(
+        "posts".to_string(),
+        upsert(&["uuid"], ConflictAction::Update),
+      )]),
+      files: HashMap::from([(
+        "posts.json".to_string(),
+        upsert(&["title"], ConflictAction::Nothing),
+      )]),
+      transforms: HashMap::new(),
+      batch: BatchOptions {
+        batch_size: 500,
+        copy: false,
+      },
+    };
+
+    assert_eq!(
+      config.upsert("posts.json", "posts").unwrap().on_conflict,
+      ["title"]
+    );
+    assert_eq!(
+      config.upsert("posts.csv", "posts").unwrap().on_conflict,
+      ["uuid"]
+    );
+    assert!(config.upsert("users.json", "users").is_none());
+  }
+
+  // 저장소에 있는 세트 폴더 파일이 실제 스키마에 그대로 들어가야 하고, 기록은 세트마다 따로 남는다
+  #[sqlx::test(migrator = "crate::entities::migration::repo::MIGRATOR")]
+  async fn set_fixtures_seed_into_the_schema(pool: PgPool) {
+    for set in ["dev", "test", "demo"] {
+      let target = target(None, Some(set));
+      let seed_plan = plan(&pool, &target).await.unwrap();
+      let tables: Vec<&str> = seed_plan
+        .files
+        .iter()
+        .map(|planned| planned.table.as_str())
+        .collect();
+      assert_eq!(tables, ["users", "posts"], "{} set", set);
+      assert!(seed_plan.files.iter().all(PlannedFile::will_apply));
+
+      assert_eq!(
+        apply(&pool, &target, false).await.unwrap(),
+        2,
+        "{} set",
+        set
+      );
+
+      let seed_plan = plan(&pool, &target).await.unwrap();
+      assert!(seed_plan
+        .files
+        .iter()
+        .all(|planned| matches!(planned.state, SeedState::Applied { .. })));
+      assert_eq!(apply(&pool, &target, false).await.unwrap(), 0);
+    }
+
+    let sold_out: String = sqlx::query_scalar(
+      "select title from posts where count_in_stock = 0 and title like '테스트%'",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(sold_out, "테스트 운동화");
+    let history: Vec<(String, i64)> = sqlx::query_as(
+      r#"select seed_set, count(*) from "_seed_history" group by seed_set order by seed_set"#,
+    )
+    .fetch_all(&pool)
+    .await
+    .unwrap();
+    assert_eq!(
+      history,
+      [
+        ("demo".to_string(), 2),
+        ("dev".to_string(), 2),
+        ("test".to_string(), 2)
+      ]
+    );
+  }
+
+  #[sqlx::test(migrator = "crate::entities::migration::repo::MIGRATOR")]
+  async fn dry_run_leaves_nothing_behind(pool: PgPool) {
+    assert_eq!(apply(&pool, &SeedTarget::default(), true).await.unwrap(), 2);
+
+    let history: i64 = sqlx::query_scalar(r#"select count(*) from "_seed_history""#)
+      .fetch_one(&pool)
+      .await
+      .unwrap();
+    let users: i64 = sqlx::query_scalar("select count(*) from users")
+      .fetch_one(&pool)
+      .await
+      .unwrap();
+    assert_eq!((history, users), (0, 0));
+  }
+
+  #[sqlx::test(migrator = "crate::entities::migration::repo::MIGRATOR")]
+  async fn configured_sets_have_no_problems(pool: PgPool) {
+    for set in ["dev", "test", "demo"] {
+      let seed_plan = plan(&pool, &target(None, Some(set))).await.unwrap();
+      for planned in &seed_plan.files {
+        assert!(
+          planned.problems.is_empty(),
+          "{}: {:?}",
+          set,
+          planned.problems
+        );
+      }
+    }
+  }
+
+  // 문제가 하나라도 있으면 문제가 없는 파일도 넣지 않고 전부 보여준다
+  #[sqlx::test(migrator = "crate::entities::migration::repo::MIGRATOR")]
+  async fn invalid_files_stop_the_whole_apply(pool: PgPool) {
+    let root = temp_root("[seeders]\ntask_folder = \"seeds\"\n", &["seeds"]);
+    fs::write(
+      root.join("seeds/users.json"),
+      r#"{"users": [
+        {"name": "a", "email": "a@test.local", "password": "pw"},
+        {"name": "b", "emial": "b@test.local", "password": "pw"}
+      ]}"#,
+    )
+    .unwrap();
+    fs::write(
+      root.join("seeds/posts.yaml"),
+      "posts:\n  - title: 1\n    price: cheap\n",
+    )
+    .unwrap();
+
+    let err = apply(&pool, &target(Some(&root), None), false)
+      .await
+      .unwrap_err();
+    let invalid = err.downcast_ref::<InvalidSeeds>().unwrap();
+    let users: Vec<(Option<usize>, Option<&str>)> = invalid
+      .0
+      .iter()
+      .filter(|problem| problem.file == "users.json")
+      .map(|problem| (problem.row, problem.column.as_deref()))
+      .collect();
+    assert_eq!(users, [(Some(1), Some("emial")), (Some(1), Some("email"))]);
+    assert!(invalid
+      .0
+      .iter()
+      .any(|problem| problem.file == "posts.yaml" && problem.column.as_deref() == Some("price")));
+
+    let count: i64 = sqlx::query_scalar("select count(*) from users")
+      .fetch_one(&pool)
+      .await
+      .unwrap();
+    assert_eq!(count, 0);
+  }
+
+  // 같은 seed로 다시 넣으면 uuid로 덮어써서 row 수가 그대로다
+  #[sqlx::test(migrator = "crate::entities::migration::repo::MIGRATOR")]
+  async fn generated_tables_can_be_seeded_twice(pool: PgPool) {
+    let options = FakeOptions {
+      seed: 1,
+      users: 3,
+      posts: 6,
+      orders: 4,
+      reviews: 2,
+    };
+
+    for _ in 0..2 {
+      let tables = generate(&options)
+        .into_iter()
+        .map(|fake| (fake.table, fake.rows))
+        .collect();
+      seed_tables(&pool, &SeedTarget::default(), tables)
+        .await
+        .unwrap();
+    }
+
+    let counts: (i64, i64, i64) = sqlx::query_as(
+      "select (select count(*) from users), (select count(*) from posts), (select count(*) from orders)",
+    )
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(counts, (3, 6, 4));
+  }
+
+  #[sqlx::test(migrator = "crate::entities::migration::repo::MIGRATOR")]
+  async fn reset_truncates_children_first(pool: PgPool) {
+    let dev = SeedTarget::default();
+    let test = target(None, Some("test"));
+    apply(&pool, &dev, false).await.unwrap();
+    apply(&pool, &test, false).await.unwrap();
+
+    assert_eq!(
+      reset_order(&plan(&pool, &test).await.unwrap()),
+      ["posts", "users"]
+    );
+
+    // orders 같은 시드 파일이 없는 테이블도 users를 참조한다
+    let err = reset(&pool, &test, false).await.unwrap_err();
+    assert!(err.to_string().contains("--cascade"));
+
+    assert_eq!(reset(&pool, &test, true).await.unwrap(), ["posts", "users"]);
+    let history: Vec<String> =
+      sqlx::query_scalar(r#"select distinct seed_set from "_seed_history""#)
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(history, ["dev"]);
+    assert!(plan(&pool, &test)
+      .await
+      .unwrap()
+      .files
+      .iter()
+      .all(|planned| matches!(planned.state, SeedState::Pending)));
+  }
+
+  #[sqlx::test(migrator = "crate::entities::migration::repo::MIGRATOR")]
+  async fn missing_set_folder_is_an_error(pool: PgPool) {
+    let root = temp_root("[seeders.sets.dev]\ntask_folder = \"nowhere\"\n", &[]);
+
+    let err = plan(&pool, &target(Some(&root), None)).await.unwrap_err();
+    assert!(err.to_string().starts_with("seed folder"), "{}", err);
+  }
+
+  #[sqlx::test(migrator = "crate::entities::migration::repo::MIGRATOR")]
+  async fn reapplied_file_updates_its_history(pool: PgPool) {
+    let mut tx = pool.begin().await.unwrap();
+    record_applied(&mut tx, "dev", "items.json", "a", 1)
+      .await
+      .unwrap();
+    record_applied(&mut tx, "dev", "items.json", "b", 2)
+      .await
+      .unwrap();
+    record_applied(&mut tx, "test", "items.json", "c", 3)
+      .await
+      .unwrap();
+    tx.commit().await.unwrap();
+
+    let (stored, rows): (String, i64) =
+      sqlx::query_as(r#"select checksum, row_count from "_seed_history" where seed_set = 'dev'"#)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(stored, "b");
+    assert_eq!(rows, 2);
+  }
 }
@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use chrono::{Duration, Utc};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::schema::{PgType, TableSchema};
+use super::source::Row;
+use super::sqlx_seeder::SeedError;
+
+const DEFAULT_BCRYPT_COST: u32 = 10;
+
+const DEFAULT_RANDOM_DAYS: u32 = 30;
+
+// [seeders.transforms.<테이블>] 에 컬럼별로 적는 변환
+// password = "bcrypt", slug = "slugify(title)", created_at = "now() - random days"
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transform {
+  // 평문을 bcrypt 해시로 바꾼다, 이미 해시면 그대로 둔다
+  Bcrypt { cost: u32 },
+  // 다른 컬럼 값으로 slug를 만든다, 값을 적었으면 그대로 둔다
+  Slugify { source: String },
+  // 현재 시각에서 최대 days일 전 사이, 값을 적었으면 그대로 둔다
+  Now { random_days: Option<u32> },
+}
+
+// "name(arg)" 에서 arg, 괄호가 없으면 None
+fn call<'a>(text: &'a str, name: &str) -> Option<Option<&'a str>> {
+  let rest = text.strip_prefix(name)?.trim();
+  if rest.is_empty() {
+    return Some(None);
+  }
+  let arg = rest.strip_prefix('(')?.strip_suffix(')')?.trim();
+  Some(Some(arg))
+}
+
+impl FromStr for Transform {
+  type Err = String;
+
+  fn from_str(text: &str) -> Result<Self, Self::Err> {
+    let text = text.trim();
+    let invalid = || {
+      format!(
+        "unknown transform {:?}, expected bcrypt, bcrypt(cost), slugify(column), now(), now() - random days or now() - random(n) days",
+        text
+      )
+    };
+
+    if let Some(cost) = call(text, "bcrypt") {
+      let cost = match cost {
+        Some(cost) => cost.parse().map_err(|_| invalid())?,
+        None => DEFAULT_BCRYPT_COST,
+      };
+      // bcrypt crate가 받는 범위
+      if !(4..=31).contains(&cost) {
+        return Err(format!(
+          "bcrypt cost must be between 4 and 31, got {}",
+          cost
+        ));
+      }
+      return Ok(Transform::Bcrypt { cost });
+    }
+
+    if let Some(Some(source)) = call(text, "slugify") {
+      if !source.is_empty() {
+        return Ok(Transform::Slugify {
+          source: source.to_string(),
+        });
+      }
+    }
+
+    let Some(rest) = text.strip_prefix("now()").map(str::trim) else {
+      return Err(invalid());
+    };
+    if rest.is_empty() {
+      return Ok(Transform::Now { random_days: None });
+    }
+    let random = rest
+      .strip_prefix('-')
+      .and_then(|rest| rest.trim().strip_suffix("days"))
+      .and_then(|random| call(random.trim(), "random"))
+      .ok_or_else(invalid)?;
+    let days = match random {
+      Some(days) => days.parse().map_err(|_| invalid())?,
+      None => DEFAULT_RANDOM_DAYS,
+    };
+    Ok(Transform::Now {
+      random_days: Some(days),
+    })
+  }
+}
+
+// 테이블별 컬럼 변환, 적은 순서가 아니라 컬럼 이름순으로 실행한다
+pub type TableTransforms = HashMap<String, Transform>;
+
+// category_slug()와 같은 규칙, 소문자로 바꾸고 글자와 숫자가 아닌 문자는 "-" 하나로 바꾼다
+pub fn slugify(text: &str) -> String {
+  let mut slug = String::new();
+  for ch in text.trim().to_lowercase().chars() {
+    if ch.is_alphanumeric() {
+      slug.push(ch);
+    } else if !slug.ends_with('-') {
+      slug.push('-');
+    }
+  }
+  slug.trim_matches('-').to_string()
+}
+
+fn is_bcrypt_hash(text: &str) -> bool {
+  ["$2a$", "$2b$", "$2y$"]
+    .iter()
+    .any(|prefix| text.starts_with(prefix))
+    && text.len() == 60
+}
+
+// 파일, row, 컬럼으로 정하는 0..1 사이 값, 다시 넣어도 같은 값이 나온다
+fn stable_fraction(file: &str, index: usize, column: &str) -> f64 {
+  let hash = Sha256::digest(format!("{}#{}.{}", file, index, column));
+  let mut bytes = [0u8; 8];
+  bytes.copy_from_slice(&hash[..8]);
+  (u64::from_be_bytes(bytes) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+fn time_value(pg_type: &PgType, days_ago: f64) -> Value {
+  let time = Utc::now() - Duration::seconds((days_ago * 86_400.0) as i64);
+  let text = match pg_type {
+    PgType::Date => time.format("%Y-%m-%d").to_string(),
+    PgType::Timestamp => time.naive_utc().format("%Y-%m-%dT%H:%M:%S").to_string(),
+    _ => time.to_rfc3339(),
+  };
+  Value::String(text)
+}
+
+// 파일 하나를 넣는 동안 쓰는 변환기
+pub struct Transformer<'a> {
+  transforms: Option<&'a TableTransforms>,
+}
+
+impl<'a> Transformer<'a> {
+  pub fn new(transforms: Option<&'a TableTransforms>) -> Self {
+    Transformer { transforms }
+  }
+
+  pub fn apply(
+    &self,
+    schema: &TableSchema,
+    row: &mut Row,
+    file: &str,
+    index: usize,
+  ) -> Result<(), SeedError> {
+    let Some(transforms) = self.transforms else {
+      return Ok(());
+    };
+    let mut columns: Vec<(&String, &Transform)> = transforms.iter().collect();
+    columns.sort_by(|a, b| a.0.cmp(b.0));
+
+    for (column, transform) in columns {
+      let error = |message: String| SeedError::row(file, index, Some(column), message);
+      let Some(info) = schema.column(column) else {
+        return Err(error(format!("table {} has no such column", schema.name)));
+      };
+
+      match transform {
+        Transform::Bcrypt { cost } => match row.get(column) {
+          Some(Value::String(password)) if !is_bcrypt_hash(password) => {
+            let hash =
+              bcrypt::hash(password, *cost).map_err(|err| error(format!("bcrypt: {}", err)))?;
+            row.insert(column.clone(), Value::String(hash));
+          }
+          Some(Value::String(_)) | Some(Value::Null) | None => {}
+          Some(other) => return Err(error(format!("bcrypt expects a string, got {}", other))),
+        },
+        Transform::Slugify { source } => {
+          if row.get(column).is_some_and(|value| !value.is_null()) {
+            continue;
+          }
+          let text = match row.get(source) {
+            Some(Value::String(text)) => text.clone(),
+            Some(Value::Null) | None => {
+              return Err(error(format!(
+                "slugify({}) needs a {} value",
+                source, source
+              )))
+            }
+            Some(other) => other.to_string(),
+          };
+          row.insert(column.clone(), Value::String(slugify(&text)));
+        }
+        Transform::Now { random_days } => {
+          if row.get(column).is_some_and(|value| !value.is_null()) {
+            continue;
+          }
+          let days_ago = random_days
+            .map(|days| stable_fraction(file, index, column) * days as f64)
+            .unwrap_or(0.0);
+          row.insert(column.clone(), time_value(&info.pg_type, days_ago));
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::seeders::schema::ColumnInfo;
+
+  fn parse(text: &str) -> Result<Transform, String> {
+    text.parse()
+  }
+
+  #[test]
+  fn parses_bcrypt() {
+    assert_eq!(
+      parse("bcrypt"),
+      Ok(Transform::Bcrypt {
+        cost: DEFAULT_BCRYPT_COST
+      })
+    );
+    assert_eq!(parse(" bcrypt( 12 ) "), Ok(Transform::Bcrypt { cost: 12 }));
+    assert!(parse("bcrypt(3)").is_err());
+    assert!(parse("bcrypt(32)").is_err());
+    assert!(parse("bcrypt(high)").is_err());
+    assert!(parse("bcryptx").is_err());
+  }
+
+  #[test]
+  fn parses_slugify() {
+    assert_eq!(
+      parse("slugify(title)"),
+      Ok(Transform::Slugify {
+        source: "title".to_string()
+      })
+    );
+    assert!(parse("slugify").is_err());
+    assert!(parse("slugify()").is_err());
+  }
+
+  #[test]
+  fn parses_now() {
+    assert_eq!(parse("now()"), Ok(Transform::Now { random_days: None }));
+    assert_eq!(
+      parse("now() - random days"),
+      Ok(Transform::Now {
+        random_days: Some(DEFAULT_RANDOM_DAYS)
+      })
+    );
+    assert_eq!(
+      parse("now()-random(7)days"),
+      Ok(Transform::Now {
+        random_days: Some(7)
+      })
+    );
+    assert!(parse("now() - random(-1) days").is_err());
+    assert!(parse("now() + random days").is_err());
+    assert!(parse("now() - random").is_err());
+    assert!(parse("today").is_err());
+  }
+
+  #[test]
+  fn slugifies() {
+    assert_eq!(slugify("Hello, World!"), "hello-world");
+    assert_eq!(slugify("  --Rust  &  Actix--  "), "rust-actix");
+    assert_eq!(slugify("여름 신상 2024"), "여름-신상-2024");
+    assert_eq!(slugify("!!!"), "");
+  }
+
+  #[test]
+  fn recognizes_bcrypt_hashes() {
+    let hash = format!("$2b$10${}", "a".repeat(53));
+    assert!(is_bcrypt_hash(&hash));
+    assert!(!is_bcrypt_hash("$2b$10$short"));
+    assert!(!is_bcrypt_hash(&format!("$1$10${}", "a".repeat(54))));
+  }
+
+  fn users() -> TableSchema {
+    let column = |name: &str, pg_type: PgType| ColumnInfo {
+      name: name.to_string(),
+      pg_type,
+      nullable: true,
+      has_default: false,
+      max_length: None,
+      serial: false,
+      generated: false,
+    };
+    TableSchema {
+      name: "users".to_string(),
+      columns: vec![
+        column("password", PgType::Text),
+        column("name", PgType::Text),
+        column("slug", PgType::Text),
+        column("created_at", PgType::Timestamptz),
+      ],
+    }
+  }
+
+  fn row(value: Value) -> Row {
+    serde_json::from_value(value).unwrap()
+  }
+
+  #[test]
+  fn bcrypt_hashes_plain_passwords_only() {
+    let transforms =
+      TableTransforms::from([("password".to_string(), Transform::Bcrypt { cost: 4 })]);
+    let transformer = Transformer::new(Some(&transforms));
+
+    let mut plain = row(serde_json::json!({ "password": "secret" }));
+    transformer
+      .apply(&users(), &mut plain, "users.json", 0)
+      .unwrap();
+    let hash = plain["password"].as_str().unwrap();
+    assert!(is_bcrypt_hash(hash));
+    assert!(bcrypt::verify("secret", hash).unwrap());
+
+    let existing = bcrypt::hash("other", 4).unwrap();
+    let mut hashed = row(serde_json::json!({ "password": existing }));
+    transformer
+      .apply(&users(), &mut hashed, "users.json", 1)
+      .unwrap();
+    assert_eq!(hashed["password"], Value::String(existing));
+
+    let mut number = row(serde_json::json!({ "password": 1234 }));
+    let err = transformer
+      .apply(&users(), &mut number, "users.json", 2)
+      .unwrap_err();
+    assert!(
+      err.to_string().contains("bcrypt expects a string"),
+      "{}",
+      err
+    );
+  }
+
+  #[test]
+  fn slugify_and_now_fill_empty_columns() {
+    let transforms = TableTransforms::from([
+      (
+        "slug".to_string(),
+        Transform::Slugify {
+          source: "name".to_string(),
+        },
+      ),
+      (
+        "created_at".to_string(),
+        Transform::Now {
+          random_days: Some(30),
+        },
+      ),
+    ]);
+    let transformer = Transformer::new(Some(&transforms));
+
+    let mut filled = row(serde_json::json!({ "name": "Rust 입문" }));
+    transformer
+      .apply(&users(), &mut filled, "users.json", 0)
+      .unwrap();
+    assert_eq!(filled["slug"], "rust-입문");
+    let created_at =
+      chrono::DateTime::parse_from_rfc3339(filled["created_at"].as_str().unwrap()).unwrap();
+    let days_ago = Utc::now().signed_duration_since(created_at).num_days();
+    assert!((0..=30).contains(&days_ago), "{}", days_ago);
+
+    let mut written = row(serde_json::json!({
+      "name": "Rust 입문",
+      "slug": "rust",
+      "created_at": "2024-01-01T00:00:00Z",
+    }));
+    transformer
+      .apply(&users(), &mut written, "users.json", 0)
+      .unwrap();
+    assert_eq!(written["slug"], "rust");
+    assert_eq!(written["created_at"], "2024-01-01T00:00:00Z");
+
+    let mut nameless = row(serde_json::json!({}));
+    let err = transformer
+      .apply(&users(), &mut nameless, "users.json", 1)
+      .unwrap_err();
+    assert!(
+      err.to_string().contains("slugify(name) needs a name value"),
+      "{}",
+      err
+    );
+  }
+
+  #[test]
+  fn unknown_columns_are_errors() {
+    let transforms =
+      TableTransforms::from([("nickname".to_string(), Transform::Now { random_days: None })]);
+    let transformer = Transformer::new(Some(&transforms));
+    let mut row = row(serde_json::json!({}));
+    let err = transformer
+      .apply(&users(), &mut row, "users.json", 0)
+      .unwrap_err();
+    assert!(
+      err.to_string().contains("table users has no such column"),
+      "{}",
+      err
+    );
+  }
+}
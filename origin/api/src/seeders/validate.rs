@@ -0,0 +1,364 @@
+use serde_json::Value;
+
+use super::bind::convert;
+use super::insert::{cell_value, needs_uuid, UpsertConfig};
+use super::resolve::Reference;
+use super::schema::{ColumnInfo, TableSchema};
+use super::source::{Row, RowStream};
+use super::sqlx_seeder::SeedError;
+use super::transform::{TableTransforms, Transform};
+
+// 테이블 정의로 만든 검사 규칙, 넣기 전에 파일 전체를 검사해서 문제를 모두 모은다
+pub struct RowValidator<'a> {
+  schema: &'a TableSchema,
+  upsert: Option<&'a UpsertConfig>,
+  transforms: Option<&'a TableTransforms>,
+  // NOT NULL인데 기본값이 없어서 row에 꼭 있어야 하는 컬럼
+  required: Vec<&'a ColumnInfo>,
+}
+
+impl<'a> RowValidator<'a> {
+  pub fn new(
+    schema: &'a TableSchema,
+    upsert: Option<&'a UpsertConfig>,
+    transforms: Option<&'a TableTransforms>,
+  ) -> Self {
+    let required = schema
+      .columns
+      .iter()
+      .filter(|column| {
+        !column.nullable && !column.has_default && !column.serial && !column.generated
+      })
+      .collect();
+
+    RowValidator {
+      schema,
+      upsert,
+      transforms,
+      required,
+    }
+  }
+
+  fn transform(&self, column: &str) -> Option<&Transform> {
+    self.transforms?.get(column)
+  }
+
+  // 값이 비어 있어도 slugify, now() 변환이 채운다
+  fn filled_by_transform(&self, column: &str) -> bool {
+    matches!(
+      self.transform(column),
+      Some(Transform::Slugify { .. } | Transform::Now { .. })
+    )
+  }
+
+  fn check_value(&self, column: &ColumnInfo, value: &Value) -> Result<(), String> {
+    // 참조한 row의 값은 넣을 때 찾으므로 형태만 본다
+    if let Some(reference) = Reference::from_value(value) {
+      return reference.map(|_| ());
+    }
+    if value.is_null() && self.filled_by_transform(&column.name) {
+      return Ok(());
+    }
+    // bcrypt는 평문 대신 해시가 들어가므로 길이는 보지 않는다
+    if let (Some(Transform::Bcrypt { .. }), Value::String(_)) =
+      (self.transform(&column.name), value)
+    {
+      return Ok(());
+    }
+    convert(column, value).map(|_| ())
+  }
+
+  pub fn check_row(&self, row: &Row, file: &str, index: usize, text_cells: bool) -> Vec<SeedError> {
+    let mut problems = Vec::new();
+    let mut values = Row::new();
+
+    for (key, value) in row {
+      let mut problem = |message: String| {
+        problems.push(SeedError::row(file, index, Some(key), message));
+      };
+      let Some(column) = self.schema.column(key) else {
+        problem(format!("table {} has no such column", self.schema.name));
+        continue;
+      };
+      if column.generated {
+        problem("generated column, postgres computes its value".to_string());
+        continue;
+      }
+
+      let value = match value {
+        Value::String(text) if text_cells => match cell_value(column, text) {
+          Ok(Some(value)) => value,
+          Ok(None) => continue,
+          Err(message) => {
+            problem(message);
+            continue;
+          }
+        },
+        value => value.clone(),
+      };
+      if let Err(message) = self.check_value(column, &value) {
+        problem(message);
+      }
+      values.insert(key.clone(), value);
+    }
+
+    for column in &self.required {
+      let present = values.contains_key(&column.name);
+      if present || self.filled_by_transform(&column.name) {
+        continue;
+      }
+      if column.name == "uuid" && needs_uuid(self.schema, &values, self.upsert) {
+        continue;
+      }
+      problems.push(SeedError::row(
+        file,
+        index,
+        Some(&column.name),
+        "missing, column is NOT NULL and has no default",
+      ));
+    }
+
+    if let Some(upsert) = self.upsert {
+      for key in &upsert.on_conflict {
+        // 필수 컬럼이라 이미 빠졌다고 나온 키는 다시 적지 않는다
+        let reported = self.required.iter().any(|column| &column.name == key);
+        if reported
+          || values.contains_key(key)
+          || (key == "uuid" && needs_uuid(self.schema, &values, self.upsert))
+        {
+          continue;
+        }
+        problems.push(SeedError::row(
+          file,
+          index,
+          Some(key),
+          "missing, it is the upsert on_conflict key",
+        ));
+      }
+    }
+
+    // 비워둔 uuid는 key 컬럼 값으로 만들므로 key 컬럼이 빠지면 넣을 때 실패한다
+    if needs_uuid(self.schema, &values, self.upsert) {
+      for key in self.upsert.map(UpsertConfig::uuid_key).unwrap_or_default() {
+        let reported = problems
+          .iter()
+          .any(|problem| problem.column.as_deref() == Some(key));
+        if reported || values.contains_key(key) {
+          continue;
+        }
+        problems.push(SeedError::row(
+          file,
+          index,
+          Some(key),
+          "missing, it is the key for generated uuids",
+        ));
+      }
+    }
+
+    problems
+  }
+
+  // 스트리밍 형식도 끝까지 읽는다, 읽다가 깨진 줄도 문제로 모은다
+  pub fn check_rows(&self, rows: RowStream, file: &str, text_cells: bool) -> Vec<SeedError> {
+    let mut problems = Vec::new();
+    for (index, row) in rows.enumerate() {
+      match row {
+        Ok(row) => problems.extend(self.check_row(&row, file, index, text_cells)),
+        Err(err) => problems.push(err),
+      }
+    }
+    problems
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::seeders::insert::ConflictAction;
+  use crate::seeders::schema::PgType;
+
+  fn column(name: &str, pg_type: PgType, nullable: bool, has_default: bool) -> ColumnInfo {
+    ColumnInfo {
+      name: name.to_string(),
+      pg_type,
+      nullable,
+      has_default,
+      max_length: None,
+      serial: false,
+      generated: false,
+    }
+  }
+
+  fn posts() -> TableSchema {
+    let mut id = column("id", PgType::Int4, false, false);
+    id.serial = true;
+    let mut title = column("title", PgType::Text, false, false);
+    title.max_length = Some(10);
+    let mut search = column("search", PgType::Text, true, false);
+    search.generated = true;
+    TableSchema {
+      name: "posts".to_string(),
+      columns: vec![
+        id,
+        column("uuid", PgType::Uuid, false, false),
+        title,
+        column("slug", PgType::Text, false, false),
+        column("price", PgType::Int4, false, false),
+        column("user_id", PgType::Int4, false, false),
+        column("created_at", PgType::Timestamptz, false, true),
+        search,
+      ],
+    }
+  }
+
+  fn upsert_on(keys: &[&str]) -> UpsertConfig {
+    UpsertConfig {
+      on_conflict: keys.iter().map(|key| key.to_string()).collect(),
+      action: ConflictAction::default(),
+      key: Vec::new(),
+    }
+  }
+
+  fn transforms() -> TableTransforms {
+    TableTransforms::from([(
+      "slug".to_string(),
+      Transform::Slugify {
+        source: "title".to_string(),
+      },
+    )])
+  }
+
+  fn problems(validator: &RowValidator, value: Value) -> Vec<(Option<String>, String)> {
+    let row = value.as_object().unwrap().clone();
+    validator
+      .check_row(&row, "posts.json", 0, false)
+      .into_iter()
+      .map(|problem| (problem.column, problem.message))
+      .collect()
+  }
+
+  fn columns(problems: &[(Option<String>, String)]) -> Vec<&str> {
+    problems
+      .iter()
+      .filter_map(|(column, _)| column.as_deref())
+      .collect()
+  }
+
+  #[test]
+  fn accepts_valid_row() {
+    let schema = posts();
+    let transforms = transforms();
+    let validator = RowValidator::new(&schema, None, Some(&transforms));
+    let row = json!({"title": "Hello", "price": 1000, "user_id": 1});
+    assert!(problems(&validator, row).is_empty());
+  }
+
+  #[test]
+  fn reports_unknown_and_generated_columns() {
+    let schema = posts();
+    let transforms = transforms();
+    let validator = RowValidator::new(&schema, None, Some(&transforms));
+    let row = json!({"title": "a", "price": 1, "user_id": 1, "titel": "a", "search": "a"});
+    assert_eq!(columns(&problems(&validator, row)), ["search", "titel"]);
+  }
+
+  #[test]
+  fn reports_missing_required_columns() {
+    let schema = posts();
+    let validator = RowValidator::new(&schema, None, None);
+    let found = problems(&validator, json!({"title": "a"}));
+    // uuid는 채워 주고 id, created_at은 postgres가 채운다
+    assert_eq!(columns(&found), ["slug", "price", "user_id"]);
+    assert!(found
+      .iter()
+      .all(|(_, message)| message.contains("NOT NULL")));
+  }
+
+  #[test]
+  fn reports_bad_values() {
+    let schema = posts();
+    let transforms = transforms();
+    let validator = RowValidator::new(&schema, None, Some(&transforms));
+    let row = json!({"title": "much too long", "price": "cheap", "user_id": null});
+    assert_eq!(
+      columns(&problems(&validator, row)),
+      ["price", "title", "user_id"]
+    );
+  }
+
+  #[test]
+  fn checks_reference_syntax_only() {
+    let schema = posts();
+    let transforms = transforms();
+    let validator = RowValidator::new(&schema, None, Some(&transforms));
+
+    let row = json!({"title": "a", "price": 1, "user_id": {"$ref": "users.email:a@example.com"}});
+    assert!(problems(&validator, row).is_empty());
+
+    let row = json!({"title": "a", "price": 1, "user_id": {"$ref": "users.email"}});
+    let found = problems(&validator, row);
+    assert_eq!(columns(&found), ["user_id"]);
+    assert!(found[0].1.contains("invalid $ref"));
+  }
+
+  #[test]
+  fn reports_missing_upsert_key() {
+    let schema = posts();
+    let transforms = transforms();
+    let row = json!({"title": "a", "price": 1, "user_id": 1});
+
+    // uuid로 upsert하면 uuid는 만들어 넣는다
+    let upsert = upsert_on(&["uuid"]);
+    let validator = RowValidator::new(&schema, Some(&upsert), Some(&transforms));
+    assert!(problems(&validator, row.clone()).is_empty());
+
+    // 다른 키로 upsert하면 uuid를 만들지 않으므로 빠졌다고 나온다, slug는 변환이 채운다
+    let upsert = upsert_on(&["slug", "created_at"]);
+    let validator = RowValidator::new(&schema, Some(&upsert), Some(&transforms));
+    let found = problems(&validator, row);
+    assert_eq!(columns(&found), ["uuid", "created_at"]);
+    assert!(found[1].1.contains("on_conflict"));
+  }
+
+  #[test]
+  fn checks_text_cells() {
+    let schema = posts();
+    let transforms = transforms();
+    let validator = RowValidator::new(&schema, None, Some(&transforms));
+    let row = json!({"title": "a", "price": "1000", "user_id": "x"})
+      .as_object()
+      .unwrap()
+      .clone();
+    let found = validator.check_row(&row, "posts.csv", 3, true);
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].row, Some(3));
+    assert_eq!(found[0].column.as_deref(), Some("user_id"));
+  }
+
+  #[test]
+  fn reports_missing_uuid_key_columns() {
+    let schema = posts();
+    let transforms = transforms();
+    let upsert = UpsertConfig {
+      key: vec!["title".to_string(), "created_at".to_string()],
+      ..upsert_on(&["uuid"])
+    };
+    let validator = RowValidator::new(&schema, Some(&upsert), Some(&transforms));
+
+    let row = json!({"title": "a", "price": 1, "user_id": 1, "created_at": "2024-01-01T00:00:00Z"});
+    assert!(problems(&validator, row).is_empty());
+
+    // title은 NOT NULL로 이미 나왔으므로 한 번만 적는다, created_at은 기본값이 있어도 key라서 있어야 한다
+    let found = problems(&validator, json!({"price": 1, "user_id": 1}));
+    assert_eq!(columns(&found), ["title", "created_at"]);
+    assert!(found[0].1.contains("NOT NULL"));
+    assert!(found[1].1.contains("key for generated uuids"));
+
+    // uuid를 적었으면 key는 보지 않는다
+    let row = json!({"uuid": "7b0c1f52-0000-4000-8000-000000000001", "price": 1, "user_id": 1});
+    let validator = RowValidator::new(&schema, Some(&upsert), None);
+    assert_eq!(columns(&problems(&validator, row)), ["title", "slug"]);
+  }
+}
@@ -0,0 +1,115 @@
+use std::{fmt, sync::Arc};
+
+use async_trait::async_trait;
+
+use super::{local::LocalStorage, s3::S3Storage};
+
+#[derive(Debug)]
+pub enum StorageError {
+	Io(std::io::Error),
+	Remote(String),
+}
+
+impl fmt::Display for StorageError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			StorageError::Io(err) => {
+				write!(f, "storage io error: {}", err)
+			}
+			StorageError::Remote(message) => {
+				write!(f, "remote storage error: {}", message)
+			}
+		}
+	}
+}
+
+impl From<std::io::Error> for StorageError {
+	fn from(err: std::io::Error) -> Self {
+		StorageError::Io(err)
+	}
+}
+
+// 업로드한 파일을 저장하는 곳, key는 "uuid/w320.webp" 같은 상대 경로
+#[async_trait]
+pub trait Storage: Send + Sync {
+	async fn put(
+		&self,
+		key: &str,
+		bytes: Vec<u8>,
+		content_type: &str,
+	) -> Result<(), StorageError>;
+
+	// 없으면 None
+	async fn get(
+		&self,
+		key: &str,
+	) -> Result<Option<Vec<u8>>, StorageError>;
+
+	async fn delete(
+		&self,
+		key: &str,
+	) -> Result<(), StorageError>;
+
+	// image_src, thumbnail_src에 그대로 넣을 수 있는 주소
+	fn url(&self, key: &str) -> String;
+}
+
+fn env_or(name: &str, default: &str) -> String {
+	std::env::var(name)
+		.unwrap_or_else(|_| default.to_string())
+}
+
+// STORAGE_BACKEND=local(기본) 또는 s3
+pub fn storage_from_env() -> Arc<dyn Storage> {
+	match env_or("STORAGE_BACKEND", "local").as_str() {
+		"s3" => Arc::new(S3Storage::new(
+			env_or("S3_ENDPOINT", "http://localhost:9000"),
+			env_or("S3_BUCKET", "uploads"),
+			env_or("S3_REGION", "us-east-1"),
+			env_or("S3_ACCESS_KEY", ""),
+			env_or("S3_SECRET_KEY", ""),
+			std::env::var("S3_PUBLIC_URL").ok(),
+		)),
+		_ => Arc::new(LocalStorage::new(
+			env_or("UPLOAD_DIR", "uploads"),
+			env_or("UPLOAD_PUBLIC_URL", "/api/upload/files"),
+		)),
+	}
+}
+
+// 우리가 만든 key만 받지만, 파일 경로로 쓰기 전에 한번 더 막는다
+pub fn is_safe_key(key: &str) -> bool {
+	!key.is_empty()
+		&& !key.starts_with('/')
+		&& key.split('/').all(|part| {
+			!part.is_empty()
+				&& part != ".."
+				&& part != "."
+				&& part.chars().all(|c| {
+					c.is_ascii_alphanumeric()
+						|| matches!(c, '-' | '_' | '.')
+				})
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn upload_keys_are_safe() {
+		assert!(is_safe_key("3f1c/w320.webp"));
+		assert!(is_safe_key("cache/ab-12_x.jpg"));
+	}
+
+	#[test]
+	fn path_tricks_are_not_safe() {
+		assert!(!is_safe_key(""));
+		assert!(!is_safe_key("/etc/passwd"));
+		assert!(!is_safe_key("a/../b.png"));
+		assert!(!is_safe_key("./a.png"));
+		assert!(!is_safe_key("a//b.png"));
+		assert!(!is_safe_key("a/b c.png"));
+		assert!(!is_safe_key("a\\b.png"));
+	}
+}
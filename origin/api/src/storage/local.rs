@@ -0,0 +1,195 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use uuid::Uuid;
+
+use super::backend::{is_safe_key, Storage, StorageError};
+
+pub struct LocalStorage {
+	root: PathBuf,
+	public_url: String,
+}
+
+impl LocalStorage {
+	pub fn new(
+		root: impl Into<PathBuf>,
+		public_url: String,
+	) -> Self {
+		LocalStorage {
+			root: root.into(),
+			public_url: public_url
+				.trim_end_matches('/')
+				.to_string(),
+		}
+	}
+
+	pub fn path(&self, key: &str) -> Option<PathBuf> {
+		is_safe_key(key).then(|| self.root.join(key))
+	}
+}
+
+fn invalid_key(key: &str) -> StorageError {
+	StorageError::Io(std::io::Error::new(
+		std::io::ErrorKind::InvalidInput,
+		format!("invalid storage key: {}", key),
+	))
+}
+
+#[async_trait]
+impl Storage for LocalStorage {
+	async fn put(
+		&self,
+		key: &str,
+		bytes: Vec<u8>,
+		_content_type: &str,
+	) -> Result<(), StorageError> {
+		let path =
+			self.path(key).ok_or_else(|| invalid_key(key))?;
+
+		if let Some(parent) = path.parent() {
+			tokio::fs::create_dir_all(parent).await?;
+		}
+
+		// 같은 key를 동시에 쓰거나 읽어도 반쯤 쓴 파일이 보이지 않게 rename으로 바꾼다
+		let temp_path = path
+			.with_extension(format!("{}.tmp", Uuid::new_v4()));
+		tokio::fs::write(&temp_path, bytes).await?;
+		if let Err(err) =
+			tokio::fs::rename(&temp_path, &path).await
+		{
+			let _ = tokio::fs::remove_file(&temp_path).await;
+			return Err(err.into());
+		}
+		Ok(())
+	}
+
+	async fn get(
+		&self,
+		key: &str,
+	) -> Result<Option<Vec<u8>>, StorageError> {
+		let Some(path) = self.path(key) else {
+			return Ok(None);
+		};
+
+		match tokio::fs::read(path).await {
+			Ok(bytes) => Ok(Some(bytes)),
+			Err(err)
+				if err.kind() == std::io::ErrorKind::NotFound =>
+			{
+				Ok(None)
+			}
+			Err(err) => Err(err.into()),
+		}
+	}
+
+	async fn delete(
+		&self,
+		key: &str,
+	) -> Result<(), StorageError> {
+		let path =
+			self.path(key).ok_or_else(|| invalid_key(key))?;
+
+		match tokio::fs::remove_file(path).await {
+			Err(err)
+				if err.kind() != std::io::ErrorKind::NotFound =>
+			{
+				Err(err.into())
+			}
+			_ => Ok(()),
+		}
+	}
+
+	fn url(&self, key: &str) -> String {
+		format!("{}/{}", self.public_url, key)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// 테스트마다 따로 쓰는 임시 폴더, drop 될 때 지운다
+	struct TempRoot(PathBuf);
+
+	impl TempRoot {
+		fn new() -> Self {
+			TempRoot(
+				std::env::temp_dir().join(format!(
+					"local-storage-{}",
+					Uuid::new_v4()
+				)),
+			)
+		}
+	}
+
+	impl Drop for TempRoot {
+		fn drop(&mut self) {
+			let _ = std::fs::remove_dir_all(&self.0);
+		}
+	}
+
+	fn storage(root: &TempRoot) -> LocalStorage {
+		LocalStorage::new(&root.0, "/files/".to_string())
+	}
+
+	#[tokio::test]
+	async fn put_get_delete() {
+		let root = TempRoot::new();
+		let storage = storage(&root);
+
+		storage
+			.put("a1/w320.webp", b"first".to_vec(), "image/webp")
+			.await
+			.unwrap();
+		storage
+			.put("a1/w320.webp", b"second".to_vec(), "image/webp")
+			.await
+			.unwrap();
+		assert_eq!(
+			storage.get("a1/w320.webp").await.unwrap(),
+			Some(b"second".to_vec())
+		);
+
+		// rename하고 남은 임시 파일이 없어야 한다
+		let names: Vec<_> =
+			std::fs::read_dir(root.0.join("a1"))
+				.unwrap()
+				.map(|entry| entry.unwrap().file_name())
+				.collect();
+		assert_eq!(names, vec!["w320.webp"]);
+
+		storage.delete("a1/w320.webp").await.unwrap();
+		assert_eq!(
+			storage.get("a1/w320.webp").await.unwrap(),
+			None
+		);
+		// 없는 파일을 지워도 에러가 아니다
+		storage.delete("a1/w320.webp").await.unwrap();
+	}
+
+	#[tokio::test]
+	async fn unsafe_keys_are_rejected() {
+		let root = TempRoot::new();
+		let storage = storage(&root);
+
+		assert!(storage
+			.put("../escape.png", b"x".to_vec(), "image/png")
+			.await
+			.is_err());
+		assert!(storage.delete("/abs.png").await.is_err());
+		assert_eq!(
+			storage.get("../escape.png").await.unwrap(),
+			None
+		);
+		assert!(!root.0.join("../escape.png").exists());
+	}
+
+	#[test]
+	fn url_joins_public_url() {
+		let root = TempRoot::new();
+		assert_eq!(
+			storage(&root).url("a1/o.png"),
+			"/files/a1/o.png"
+		);
+	}
+}
@@ -0,0 +1,34 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub async fn insert_user(
+	pool: &PgPool,
+	name: &str,
+) -> Uuid {
+	sqlx::query_scalar(
+		"insert into users (name, email, password) values ($1, $2, 'x')
+		returning uuid",
+	)
+	.bind(name)
+	.bind(format!("{}@test.local", name))
+	.fetch_one(pool)
+	.await
+	.expect("insert user")
+}
+
+pub async fn insert_post(
+	pool: &PgPool,
+	user_id: Uuid,
+	title: &str,
+) -> Uuid {
+	sqlx::query_scalar(
+		"insert into posts (user_id, title, image_src, description, brand, category, size)
+		values ($1, $2, '', '', 'brand', 'category', '{}')
+		returning uuid",
+	)
+	.bind(user_id)
+	.bind(title)
+	.fetch_one(pool)
+	.await
+	.expect("insert post")
+}